@@ -0,0 +1,379 @@
+/*
+cargo run -p apple-app-store-receipts-demo --bin iap_receipt_inspector -- [OPTIONS] RECEIPT
+
+RECEIPT is a receipt file (DER or base64), a verifyReceipt response JSON file, or a base64 string.
+Without --verify or --root-ca the receipt is decoded locally, the signature is not checked.
+
+OPTIONS
+    --verify PASSWORD       verify with verifyReceipt, APPLE_IAP_PASSWORD is used when PASSWORD is -
+    --root-ca PATH          verify the signature locally, PATH is the DER encoded root certificate
+    --at RFC3339            computes the entitlements at, defaults to now
+    --json                  prints JSON instead of tables
+
+e.g.
+cargo run -p apple-app-store-receipts-demo --bin iap_receipt_inspector -- apple-app-store-receipts/tests/fixture_files/response_body_sandbox.json --at 2025-01-15T00:00:00Z
+*/
+
+use std::{env, error, fs, path::Path};
+
+use apple_app_store_receipts::{
+    entitlement::{Entitlements, ProductEntitlement},
+    local_receipt::{LocalReceipt, LocalReceiptVerifier},
+    objects::response_body::{
+        Environment, ReceiptType, ResponseBody, ResponseBodyWithSuccess, Transaction,
+    },
+    types::status::Status,
+    ReceiptData, VerifyReceipt,
+};
+use chrono::{DateTime, Utc};
+use futures_lite::future::block_on;
+use http_api_isahc_client::{IsahcClient, RetryableClient as _};
+use serde_json::json;
+
+fn main() -> Result<(), Box<dyn error::Error>> {
+    env_logger::init();
+
+    block_on(run())
+}
+
+struct Args {
+    receipt: String,
+    password: Option<String>,
+    root_ca: Option<String>,
+    at: DateTime<Utc>,
+    json: bool,
+}
+
+impl Args {
+    fn parse() -> Result<Self, Box<dyn error::Error>> {
+        let mut receipt = None;
+        let mut password = None;
+        let mut root_ca = None;
+        let mut at = Utc::now();
+        let mut json = false;
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("missing value of {arg}"));
+            match arg.as_str() {
+                "--verify" => {
+                    password = Some(match value()?.as_str() {
+                        "-" => env::var("APPLE_IAP_PASSWORD")?,
+                        x => x.to_owned(),
+                    })
+                }
+                "--root-ca" => root_ca = Some(value()?),
+                "--at" => at = DateTime::parse_from_rfc3339(&value()?)?.with_timezone(&Utc),
+                "--json" => json = true,
+                "-h" | "--help" => return Err(USAGE.into()),
+                _ if arg.starts_with("--") => return Err(format!("unknown option {arg}").into()),
+                _ => receipt = Some(arg),
+            }
+        }
+
+        Ok(Self {
+            receipt: receipt.ok_or(USAGE)?,
+            password,
+            root_ca,
+            at,
+            json,
+        })
+    }
+}
+
+const USAGE: &str = "usage: iap_receipt_inspector [--verify PASSWORD] [--root-ca PATH] [--at RFC3339] [--json] RECEIPT";
+
+async fn run() -> Result<(), Box<dyn error::Error>> {
+    let args = Args::parse()?;
+
+    let response_body = match read_receipt(&args.receipt)? {
+        Input::ResponseBody(response_body) => response_body,
+        Input::ReceiptData(receipt_data) => {
+            if let Some(password) = &args.password {
+                let verify_receipt = VerifyReceipt::new(password.to_owned(), receipt_data, None);
+
+                let isahc_client = IsahcClient::new()?;
+
+                isahc_client
+                    .respond_endpoint_until_done(&verify_receipt)
+                    .await?
+            } else if let Some(root_ca) = &args.root_ca {
+                let verifier = LocalReceiptVerifier::new(&fs::read(root_ca)?)?;
+                local_receipt_to_response_body(verifier.verify_receipt_data(&receipt_data)?)
+            } else {
+                local_receipt_to_response_body(LocalReceipt::from_receipt_data(&receipt_data)?)
+            }
+        }
+    };
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&to_json(&response_body, &args.at))?
+        );
+    } else {
+        print_tables(&response_body, &args.at);
+    }
+
+    Ok(())
+}
+
+//
+//
+//
+enum Input {
+    ResponseBody(ResponseBody),
+    ReceiptData(ReceiptData),
+}
+
+fn read_receipt(receipt: &str) -> Result<Input, Box<dyn error::Error>> {
+    let bytes = if Path::new(receipt).is_file() {
+        fs::read(receipt)?
+    } else {
+        receipt.as_bytes().to_vec()
+    };
+
+    match bytes.iter().find(|x| !x.is_ascii_whitespace()) {
+        Some(b'{') => Ok(Input::ResponseBody(serde_json::from_slice(&bytes)?)),
+        // DER SEQUENCE
+        Some(0x30) => Ok(Input::ReceiptData(ReceiptData::from_bytes(bytes)?)),
+        _ => Ok(Input::ReceiptData(ReceiptData::from_base64(
+            String::from_utf8(bytes)?,
+        )?)),
+    }
+}
+
+// The same shape as verifyReceipt, so that the entitlements are computed from in_app.
+fn local_receipt_to_response_body(local_receipt: LocalReceipt) -> ResponseBody {
+    let environment = match local_receipt.receipt.receipt_type {
+        ReceiptType::ProductionSandbox | ReceiptType::ProductionVPPSandbox => Environment::Sandbox,
+        _ => Environment::Production,
+    };
+
+    ResponseBody::Success(Box::new(ResponseBodyWithSuccess {
+        status: Status::Success,
+        environment,
+        receipt: local_receipt.receipt,
+        latest_receipt: None,
+        latest_receipt_info: None,
+        pending_renewal_info: None,
+        extra: Default::default(),
+    }))
+}
+
+fn transactions(body: &ResponseBodyWithSuccess) -> Vec<&Transaction> {
+    match &body.latest_receipt_info {
+        Some(latest_receipt_info) => latest_receipt_info.iter().map(|x| &x.transaction).collect(),
+        None => body
+            .receipt
+            .in_app
+            .iter()
+            .flatten()
+            .map(|x| &x.transaction)
+            .collect(),
+    }
+}
+
+//
+// JSON
+//
+fn to_json(response_body: &ResponseBody, at: &DateTime<Utc>) -> serde_json::Value {
+    match response_body {
+        ResponseBody::Success(body) => json!({
+            "status": body.status.detail(),
+            "environment": body.environment,
+            "receipt": body.receipt,
+            "latest_receipt_info": body.latest_receipt_info,
+            "pending_renewal_info": body.pending_renewal_info,
+            "entitlements_at": at.to_rfc3339(),
+            "entitlements": Entitlements::from_response_body(body, at)
+                .products
+                .iter()
+                .map(entitlement_to_json)
+                .collect::<Vec<_>>(),
+        }),
+        ResponseBody::Error(body) => json!({
+            "status": body.status.detail(),
+            "environment": body.environment,
+            "is_retryable": body.is_retryable,
+            "exception": body.exception,
+        }),
+    }
+}
+
+fn entitlement_to_json(entitlement: &ProductEntitlement) -> serde_json::Value {
+    json!({
+        "product_id": entitlement.product_id,
+        "original_transaction_id": entitlement.original_transaction_id,
+        "latest_transaction_id": entitlement.latest_transaction_id,
+        "subscription_group_identifier": entitlement.subscription_group_identifier,
+        "state": format!("{:?}", entitlement.state),
+        "expires_date": entitlement.expires_date.map(|x| x.to_rfc3339()),
+        "cancellation_date": entitlement.cancellation_date.map(|x| x.to_rfc3339()),
+        "is_upgraded": entitlement.is_upgraded,
+        "auto_renew_status": entitlement.auto_renew_status,
+        "is_in_billing_retry_period": entitlement.is_in_billing_retry_period,
+        "is_in_grace_period": entitlement.is_in_grace_period,
+        "in_app_ownership_type": entitlement.in_app_ownership_type,
+    })
+}
+
+//
+// Tables
+//
+fn print_tables(response_body: &ResponseBody, at: &DateTime<Utc>) {
+    let body = match response_body {
+        ResponseBody::Success(body) => body,
+        ResponseBody::Error(body) => {
+            print_table(
+                "Status",
+                &["field", "value"],
+                vec![
+                    vec!["status".to_owned(), body.status.to_string()],
+                    vec!["class".to_owned(), format!("{:?}", body.status.class())],
+                    vec![
+                        "environment".to_owned(),
+                        opt(body.environment.as_ref().map(|x| format!("{x:?}"))),
+                    ],
+                    vec!["is_retryable".to_owned(), opt(body.is_retryable)],
+                    vec!["exception".to_owned(), opt(body.exception.as_ref())],
+                ],
+            );
+            return;
+        }
+    };
+
+    let receipt = &body.receipt;
+    print_table(
+        "Receipt",
+        &["field", "value"],
+        vec![
+            vec!["status".to_owned(), body.status.to_string()],
+            vec!["environment".to_owned(), format!("{:?}", body.environment)],
+            vec!["receipt_type".to_owned(), receipt.receipt_type.to_string()],
+            vec!["bundle_id".to_owned(), receipt.bundle_id.to_owned()],
+            vec![
+                "application_version".to_owned(),
+                receipt.application_version.to_owned(),
+            ],
+            vec![
+                "original_application_version".to_owned(),
+                opt(receipt.original_application_version.as_ref()),
+            ],
+            vec![
+                "receipt_creation_date".to_owned(),
+                date(&receipt.receipt_creation_date),
+            ],
+            vec!["request_date".to_owned(), date(&receipt.request_date)],
+            vec![
+                "original_purchase_date".to_owned(),
+                date(&receipt.original_purchase_date),
+            ],
+        ],
+    );
+
+    print_table(
+        "Transactions",
+        &[
+            "transaction_id",
+            "original_transaction_id",
+            "product_id",
+            "qty",
+            "purchase_date",
+            "expires_date",
+            "cancellation_date",
+            "trial",
+            "ownership",
+        ],
+        transactions(body)
+            .into_iter()
+            .map(|x| {
+                vec![
+                    x.transaction_id.to_owned(),
+                    x.original_transaction_id.to_owned(),
+                    x.product_id.to_owned(),
+                    x.quantity.to_string(),
+                    date(&x.purchase_date),
+                    opt(x.expires_date.as_ref().map(date)),
+                    opt(x.cancellation_date.as_ref().map(date)),
+                    opt(x.is_trial_period),
+                    opt(x.in_app_ownership_type.as_ref()),
+                ]
+            })
+            .collect(),
+    );
+
+    print_table(
+        &format!("Entitlements at {}", date(at)),
+        &[
+            "product_id",
+            "state",
+            "expires_date",
+            "auto_renew",
+            "billing_retry",
+            "grace_period",
+            "group",
+        ],
+        Entitlements::from_response_body(body, at)
+            .products
+            .iter()
+            .map(|x| {
+                vec![
+                    x.product_id.to_owned(),
+                    format!("{:?}", x.state),
+                    opt(x.expires_date.as_ref().map(date)),
+                    opt(x.auto_renew_status),
+                    x.is_in_billing_retry_period.to_string(),
+                    x.is_in_grace_period.to_string(),
+                    opt(x.subscription_group_identifier.as_ref()),
+                ]
+            })
+            .collect(),
+    );
+}
+
+fn print_table(title: &str, header: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths = header.iter().map(|x| x.len()).collect::<Vec<_>>();
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+
+    println!("{title}");
+    println!("{}", line(header.to_vec()));
+    println!(
+        "{}",
+        line(
+            widths
+                .iter()
+                .map(|x| "-".repeat(*x))
+                .collect::<Vec<_>>()
+                .iter()
+                .map(|x| x.as_str())
+                .collect()
+        )
+    );
+    for row in &rows {
+        println!("{}", line(row.iter().map(|x| x.as_str()).collect()));
+    }
+    println!();
+}
+
+fn date(dt: &DateTime<Utc>) -> String {
+    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn opt(v: Option<impl ToString>) -> String {
+    v.map(|x| x.to_string()).unwrap_or_else(|| "-".to_owned())
+}
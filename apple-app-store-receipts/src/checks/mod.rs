@@ -0,0 +1,5 @@
+pub mod receipt;
+pub mod usage_store;
+
+pub use receipt::{Anomaly, ReceiptChecker};
+pub use usage_store::{InMemoryReceiptUsageStore, ReceiptUsageStore};
@@ -0,0 +1,271 @@
+use crate::objects::response_body::{Environment, Receipt, ReceiptType, ResponseBodyWithSuccess};
+
+use super::usage_store::ReceiptUsageStore;
+
+pub const MAX_USERS_PER_RECEIPT_DEFAULT: usize = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Anomaly {
+    BundleIdMismatch {
+        expected: String,
+        actual: String,
+    },
+    ApplicationVersionMismatch {
+        actual: String,
+    },
+    /// Verified in sandbox, but the server runs in production.
+    SandboxReceiptInProduction,
+    /// e.g. `ProductionVPPSandbox`, or a value not known.
+    SuspiciousReceiptType(ReceiptType),
+    /// The same purchase presented by more user accounts than allowed.
+    ReceiptReused {
+        original_transaction_id: String,
+        user_count: usize,
+    },
+}
+
+/// Heuristics only, it is up to the caller to decide what to do with the anomalies.
+#[derive(Debug, Clone)]
+pub struct ReceiptChecker {
+    bundle_id: String,
+    environment: Environment,
+    application_versions: Option<Vec<String>>,
+    max_users_per_receipt: usize,
+}
+
+impl ReceiptChecker {
+    pub fn new(bundle_id: impl AsRef<str>, environment: Environment) -> Self {
+        Self {
+            bundle_id: bundle_id.as_ref().to_owned(),
+            environment,
+            application_versions: None,
+            max_users_per_receipt: MAX_USERS_PER_RECEIPT_DEFAULT,
+        }
+    }
+
+    /// The `CFBundleVersion`s currently shipped, `receipt.application_version` must be one of them.
+    pub fn set_application_versions(
+        &mut self,
+        application_versions: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> &mut Self {
+        self.application_versions = Some(
+            application_versions
+                .into_iter()
+                .map(|x| x.as_ref().to_owned())
+                .collect(),
+        );
+        self
+    }
+
+    pub fn set_max_users_per_receipt(&mut self, max_users_per_receipt: usize) -> &mut Self {
+        self.max_users_per_receipt = max_users_per_receipt;
+        self
+    }
+
+    /// Only sees the receipt type, `check_response_body` also uses the environment it was verified in.
+    pub fn check_receipt(&self, receipt: &Receipt) -> Vec<Anomaly> {
+        let mut anomalies = vec![];
+
+        if self.environment == Environment::Production
+            && receipt.receipt_type == ReceiptType::ProductionSandbox
+        {
+            anomalies.push(Anomaly::SandboxReceiptInProduction);
+        }
+
+        if receipt.bundle_id != self.bundle_id {
+            anomalies.push(Anomaly::BundleIdMismatch {
+                expected: self.bundle_id.to_owned(),
+                actual: receipt.bundle_id.to_owned(),
+            });
+        }
+
+        if let Some(application_versions) = &self.application_versions {
+            if !application_versions.contains(&receipt.application_version) {
+                anomalies.push(Anomaly::ApplicationVersionMismatch {
+                    actual: receipt.application_version.to_owned(),
+                });
+            }
+        }
+
+        // ProductionSandbox in production is reported as SandboxReceiptInProduction above.
+        let is_receipt_type_expected = matches!(
+            (&self.environment, &receipt.receipt_type),
+            (
+                Environment::Production,
+                ReceiptType::Production
+                    | ReceiptType::ProductionVPP
+                    | ReceiptType::ProductionSandbox
+            ) | (Environment::Sandbox, ReceiptType::ProductionSandbox)
+        );
+        if !is_receipt_type_expected {
+            anomalies.push(Anomaly::SuspiciousReceiptType(
+                receipt.receipt_type.to_owned(),
+            ));
+        }
+
+        anomalies
+    }
+
+    pub fn check_response_body(&self, body: &ResponseBodyWithSuccess) -> Vec<Anomaly> {
+        let mut anomalies = vec![];
+
+        if self.environment == Environment::Production && body.environment == Environment::Sandbox {
+            anomalies.push(Anomaly::SandboxReceiptInProduction);
+        }
+
+        for anomaly in self.check_receipt(&body.receipt) {
+            if !anomalies.contains(&anomaly) {
+                anomalies.push(anomaly);
+            }
+        }
+
+        anomalies
+    }
+
+    /// Records the usage by `user_id` in `store`, then checks `check_response_body` and the reuse.
+    pub fn check_response_body_for_user<S: ReceiptUsageStore>(
+        &self,
+        body: &ResponseBodyWithSuccess,
+        user_id: &str,
+        store: &mut S,
+    ) -> Result<Vec<Anomaly>, S::Error> {
+        let mut anomalies = self.check_response_body(body);
+
+        for original_transaction_id in original_transaction_ids(body) {
+            let user_count = store.record_receipt_usage(original_transaction_id, user_id)?;
+            if user_count > self.max_users_per_receipt {
+                anomalies.push(Anomaly::ReceiptReused {
+                    original_transaction_id: original_transaction_id.to_owned(),
+                    user_count,
+                });
+            }
+        }
+
+        Ok(anomalies)
+    }
+}
+
+// An original_transaction_id is bound to the Apple ID that purchased, so it identifies the
+// purchase across receipt refreshes.
+fn original_transaction_ids(body: &ResponseBodyWithSuccess) -> Vec<&str> {
+    let mut ids = body
+        .receipt
+        .in_app
+        .iter()
+        .flatten()
+        .map(|x| x.transaction.original_transaction_id.as_str())
+        .chain(
+            body.latest_receipt_info
+                .iter()
+                .flatten()
+                .map(|x| x.transaction.original_transaction_id.as_str()),
+        )
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::{checks::InMemoryReceiptUsageStore, objects::response_body::ResponseBody};
+
+    fn body(value: serde_json::Value) -> Result<ResponseBodyWithSuccess, Box<dyn error::Error>> {
+        match serde_json::from_value(value)? {
+            ResponseBody::Success(body) => Ok(*body),
+            ResponseBody::Error(body) => Err(format!("unexpected {body:?}").into()),
+        }
+    }
+
+    #[test]
+    fn test_check() -> Result<(), Box<dyn error::Error>> {
+        let value: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/entitlement_files/subscriptions.json"
+        ))?;
+        let bundle_id = value["receipt"]["bundle_id"]
+            .as_str()
+            .ok_or("bundle_id missing")?
+            .to_owned();
+        let application_version = value["receipt"]["application_version"]
+            .as_str()
+            .ok_or("application_version missing")?
+            .to_owned();
+
+        let mut checker = ReceiptChecker::new(&bundle_id, Environment::Sandbox);
+        checker.set_application_versions([&application_version]);
+        assert_eq!(checker.check_response_body(&body(value.clone())?), vec![]);
+
+        //
+        let mut checker = ReceiptChecker::new("com.example.other", Environment::Production);
+        checker.set_application_versions(["0"]);
+        assert_eq!(
+            checker.check_response_body(&body(value.clone())?),
+            vec![
+                Anomaly::SandboxReceiptInProduction,
+                Anomaly::BundleIdMismatch {
+                    expected: "com.example.other".to_owned(),
+                    actual: bundle_id.to_owned(),
+                },
+                Anomaly::ApplicationVersionMismatch {
+                    actual: application_version.to_owned(),
+                },
+            ]
+        );
+
+        // check_receipt alone
+        let receipt = body(value.clone())?.receipt;
+        assert_eq!(receipt.receipt_type, ReceiptType::ProductionSandbox);
+        assert_eq!(
+            ReceiptChecker::new(&bundle_id, Environment::Production).check_receipt(&receipt),
+            vec![Anomaly::SandboxReceiptInProduction]
+        );
+        assert_eq!(
+            ReceiptChecker::new(&bundle_id, Environment::Sandbox).check_receipt(&receipt),
+            vec![]
+        );
+
+        //
+        let mut value_vpp = value.clone();
+        value_vpp["receipt"]["receipt_type"] = "ProductionVPPSandbox".into();
+        let checker = ReceiptChecker::new(&bundle_id, Environment::Sandbox);
+        assert_eq!(
+            checker.check_response_body(&body(value_vpp)?),
+            vec![Anomaly::SuspiciousReceiptType(
+                ReceiptType::ProductionVPPSandbox
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_reuse() -> Result<(), Box<dyn error::Error>> {
+        let value: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/entitlement_files/subscriptions.json"
+        ))?;
+        let body = body(value)?;
+        let checker = ReceiptChecker::new(&body.receipt.bundle_id, Environment::Sandbox);
+
+        let mut store = InMemoryReceiptUsageStore::new();
+        assert_eq!(
+            checker.check_response_body_for_user(&body, "user_1", &mut store)?,
+            vec![]
+        );
+        assert_eq!(
+            checker.check_response_body_for_user(&body, "user_1", &mut store)?,
+            vec![]
+        );
+
+        let anomalies = checker.check_response_body_for_user(&body, "user_2", &mut store)?;
+        assert!(!anomalies.is_empty());
+        assert!(anomalies
+            .iter()
+            .all(|x| matches!(x, Anomaly::ReceiptReused { user_count: 2, .. })));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+};
+
+/// Where the receipt to user account associations live, e.g. a database table.
+pub trait ReceiptUsageStore {
+    type Error;
+
+    /// Records that `user_id` presented the receipt, returns the count of distinct user ids
+    /// that presented it so far, including this one.
+    fn record_receipt_usage(
+        &mut self,
+        receipt_key: &str,
+        user_id: &str,
+    ) -> Result<usize, Self::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryReceiptUsageStore {
+    inner: HashMap<String, HashSet<String>>,
+}
+
+impl InMemoryReceiptUsageStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ReceiptUsageStore for InMemoryReceiptUsageStore {
+    type Error = Infallible;
+
+    fn record_receipt_usage(
+        &mut self,
+        receipt_key: &str,
+        user_id: &str,
+    ) -> Result<usize, Self::Error> {
+        let user_ids = self.inner.entry(receipt_key.to_owned()).or_default();
+        user_ids.insert(user_id.to_owned());
+        Ok(user_ids.len())
+    }
+}
@@ -7,7 +7,10 @@ use http::{
 use serde_json::Error as SerdeJsonError;
 
 use crate::{
-    objects::{request_body::RequestBody, response_body::ResponseBody},
+    objects::{
+        request_body::RequestBody,
+        response_body::{Environment, ResponseBody},
+    },
     types::status::Status,
 };
 
@@ -16,17 +19,53 @@ use super::endpoint_prelude::*;
 const URL_PRODUCTION: &str = "https://buy.itunes.apple.com/verifyReceipt";
 const URL_SANDBOX: &str = "https://sandbox.itunes.apple.com/verifyReceipt";
 
+pub const MAX_RETRY_COUNT_DEFAULT: usize = 3;
+
+/// `ServiceUnavailable` and `TryAgainWithStatus` carry the environment of the failed attempt.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RetryReason {
-    ServiceUnavailable,
+    ServiceUnavailable(Environment),
     GotoSandbox,
-    TryAgainWithStatus(Status),
+    GotoProduction,
+    TryAgainWithStatus(Environment, Status),
+}
+
+// https://developer.apple.com/documentation/storekit/in-app_purchase/original_api_for_in-app_purchase/validating_receipts_with_the_app_store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EnvironmentPolicy {
+    /// Sandbox receipts are rejected with 21007.
+    ProductionOnly,
+    SandboxOnly,
+    /// Goto sandbox on 21007, what Apple recommends.
+    #[default]
+    ProductionThenSandbox,
+    /// Goto production on 21008.
+    SandboxThenProduction,
+}
+
+impl EnvironmentPolicy {
+    pub fn initial_environment(&self) -> Environment {
+        match self {
+            Self::ProductionOnly | Self::ProductionThenSandbox => Environment::Production,
+            Self::SandboxOnly | Self::SandboxThenProduction => Environment::Sandbox,
+        }
+    }
+
+    pub fn fallback_environment(&self) -> Option<Environment> {
+        match self {
+            Self::ProductionOnly | Self::SandboxOnly => None,
+            Self::ProductionThenSandbox => Some(Environment::Sandbox),
+            Self::SandboxThenProduction => Some(Environment::Production),
+        }
+    }
 }
 
 pub struct VerifyReceipt {
     password: String,
     receipt_data: ReceiptData,
     exclude_old_transactions: Option<bool>,
+    environment_policy: EnvironmentPolicy,
+    max_retry_count: usize,
 }
 impl VerifyReceipt {
     pub fn new(
@@ -38,6 +77,32 @@ impl VerifyReceipt {
             password,
             receipt_data,
             exclude_old_transactions,
+            environment_policy: Default::default(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    pub fn set_environment_policy(&mut self, environment_policy: EnvironmentPolicy) -> &mut Self {
+        self.environment_policy = environment_policy;
+        self
+    }
+
+    /// Retries for transient statuses and service unavailable, environment switches included.
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+
+    fn current_environment(
+        &self,
+        retry: Option<&RetryableEndpointRetry<RetryReason>>,
+    ) -> Environment {
+        match retry.map(|x| &x.reason) {
+            None => self.environment_policy.initial_environment(),
+            Some(RetryReason::GotoSandbox) => Environment::Sandbox,
+            Some(RetryReason::GotoProduction) => Environment::Production,
+            Some(RetryReason::ServiceUnavailable(environment))
+            | Some(RetryReason::TryAgainWithStatus(environment, _)) => environment.to_owned(),
         }
     }
 }
@@ -48,6 +113,41 @@ pub enum ReceiptData {
     Base64String(String),
 }
 impl ReceiptData {
+    /// Accepts the standard or the URL-safe alphabet (not mixed), with or without padding, and ignores whitespace.
+    #[cfg(feature = "with-base64")]
+    pub fn from_base64(s: impl AsRef<str>) -> Result<Self, ReceiptDataError> {
+        Self::from_base64_with_max_size(s, RECEIPT_DATA_MAX_SIZE_DEFAULT)
+    }
+
+    #[cfg(feature = "with-base64")]
+    pub fn from_base64_with_max_size(
+        s: impl AsRef<str>,
+        max_size: usize,
+    ) -> Result<Self, ReceiptDataError> {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let bytes = decode_base64(s.as_ref()).map_err(ReceiptDataError::Base64DecodeFailed)?;
+        validate_bytes(&bytes, max_size)?;
+
+        Ok(Self::Base64String(general_purpose::STANDARD.encode(bytes)))
+    }
+
+    #[cfg(feature = "with-base64")]
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Result<Self, ReceiptDataError> {
+        Self::from_bytes_with_max_size(bytes, RECEIPT_DATA_MAX_SIZE_DEFAULT)
+    }
+
+    #[cfg(feature = "with-base64")]
+    pub fn from_bytes_with_max_size(
+        bytes: impl Into<Vec<u8>>,
+        max_size: usize,
+    ) -> Result<Self, ReceiptDataError> {
+        let bytes = bytes.into();
+        validate_bytes(&bytes, max_size)?;
+
+        Ok(Self::Bytes(bytes))
+    }
+
     pub fn data(&self) -> String {
         match self {
             #[cfg(feature = "with-base64")]
@@ -61,6 +161,54 @@ impl ReceiptData {
     }
 }
 
+pub const RECEIPT_DATA_MAX_SIZE_DEFAULT: usize = 5 * 1024 * 1024;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReceiptDataError {
+    #[error("Empty")]
+    Empty,
+    #[error("TooLarge {0}")]
+    TooLarge(usize),
+    #[cfg(feature = "with-base64")]
+    #[error("Base64DecodeFailed {0}")]
+    Base64DecodeFailed(base64::DecodeError),
+    #[error("NotPkcs7SignedData")]
+    NotPkcs7SignedData,
+}
+
+#[cfg(feature = "with-base64")]
+pub(crate) fn decode_base64(s: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let s = s
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<String>();
+    let s = s.trim_end_matches('=');
+
+    if s.contains(['-', '_']) {
+        general_purpose::URL_SAFE_NO_PAD.decode(s)
+    } else {
+        general_purpose::STANDARD_NO_PAD.decode(s)
+    }
+}
+
+#[cfg(feature = "with-base64")]
+fn validate_bytes(bytes: &[u8], max_size: usize) -> Result<(), ReceiptDataError> {
+    use crate::local_receipt::pkcs7::is_pkcs7_signed_data;
+
+    if bytes.is_empty() {
+        return Err(ReceiptDataError::Empty);
+    }
+    if bytes.len() > max_size {
+        return Err(ReceiptDataError::TooLarge(bytes.len()));
+    }
+    if !is_pkcs7_signed_data(bytes) {
+        return Err(ReceiptDataError::NotPkcs7SignedData);
+    }
+    Ok(())
+}
+
 impl RetryableEndpoint for VerifyReceipt {
     type RetryReason = RetryReason;
 
@@ -73,14 +221,10 @@ impl RetryableEndpoint for VerifyReceipt {
         &self,
         retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
     ) -> Result<Request<Body>, Self::RenderRequestError> {
-        let mut url = URL_PRODUCTION;
-        if let Some(retry) = retry {
-            match retry.reason {
-                RetryReason::ServiceUnavailable => {}
-                RetryReason::GotoSandbox => url = URL_SANDBOX,
-                RetryReason::TryAgainWithStatus(_) => {}
-            }
-        }
+        let url = match self.current_environment(retry) {
+            Environment::Production => URL_PRODUCTION,
+            Environment::Sandbox => URL_SANDBOX,
+        };
 
         let receipt_data = self.receipt_data.data();
 
@@ -112,13 +256,15 @@ impl RetryableEndpoint for VerifyReceipt {
         retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
     ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
     {
+        let environment = self.current_environment(retry);
+
         match response.status() {
             StatusCode::OK => {}
             /*
             503 <html><body><b>Http/1.1 Service Unavailable</b></body> </html>
             */
             StatusCode::SERVICE_UNAVAILABLE => {
-                return Ok(Err(RetryReason::ServiceUnavailable));
+                return Ok(Err(RetryReason::ServiceUnavailable(environment)));
             }
             status => {
                 return Err(VerifyReceiptError::StatusMismatch(status));
@@ -133,40 +279,39 @@ impl RetryableEndpoint for VerifyReceipt {
             ResponseBody::Error(body) => match &body.status {
                 Status::Error21007 => {
                     if let Some(retry) = retry {
-                        match retry.reason {
-                            RetryReason::ServiceUnavailable => {}
-                            RetryReason::GotoSandbox => debug_assert!(false, "double goto sandbox"),
-                            RetryReason::TryAgainWithStatus(_) => {}
+                        if retry.reason == RetryReason::GotoSandbox {
+                            debug_assert!(false, "double goto sandbox")
                         }
                     }
-                    return Ok(Err(RetryReason::GotoSandbox));
+                    if environment == Environment::Production
+                        && self.environment_policy.fallback_environment()
+                            == Some(Environment::Sandbox)
+                    {
+                        return Ok(Err(RetryReason::GotoSandbox));
+                    }
                 }
-                Status::Error21002 | Status::Error21005 | Status::Error21009 => {
+                Status::Error21008 => {
                     if let Some(retry) = retry {
-                        if retry.count < self.max_retry_count() {
-                            return Ok(Err(RetryReason::TryAgainWithStatus(
-                                body.status.to_owned(),
-                            )));
+                        if retry.reason == RetryReason::GotoProduction {
+                            debug_assert!(false, "double goto production")
                         }
-                    } else {
-                        return Ok(Err(RetryReason::TryAgainWithStatus(body.status.to_owned())));
                     }
-                }
-                Status::InternalDataAccessError(_) => {
-                    if body.is_retryable == Some(true) {
-                        if let Some(retry) = retry {
-                            if retry.count < self.max_retry_count() {
-                                return Ok(Err(RetryReason::TryAgainWithStatus(
-                                    body.status.to_owned(),
-                                )));
-                            }
-                        } else {
-                            return Ok(Err(RetryReason::TryAgainWithStatus(
-                                body.status.to_owned(),
-                            )));
-                        }
+                    if environment == Environment::Sandbox
+                        && self.environment_policy.fallback_environment()
+                            == Some(Environment::Production)
+                    {
+                        return Ok(Err(RetryReason::GotoProduction));
                     }
                 }
+                status
+                    if status.should_retry(body.is_retryable)
+                        && retry.map(|x| x.count).unwrap_or_default() < self.max_retry_count() =>
+                {
+                    return Ok(Err(RetryReason::TryAgainWithStatus(
+                        environment,
+                        status.to_owned(),
+                    )));
+                }
                 _ => {}
             },
         }
@@ -175,7 +320,7 @@ impl RetryableEndpoint for VerifyReceipt {
     }
 
     fn max_retry_count(&self) -> usize {
-        3
+        self.max_retry_count
     }
 }
 
@@ -0,0 +1,344 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+
+use crate::objects::response_body::{ResponseBody, ResponseBodyWithSuccess, Transaction};
+
+use super::ledger_store::PurchaseLedgerStore;
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProductType {
+    Consumable,
+    NonConsumable,
+    AutoRenewableSubscription,
+    NonRenewingSubscription,
+}
+
+impl ProductType {
+    /// A non-consumable is granted once per original_transaction_id, so that restored
+    /// purchases are not credited again, everything else once per transaction_id.
+    pub fn ledger_key<'a>(&self, transaction: &'a Transaction) -> &'a str {
+        match self {
+            Self::NonConsumable => &transaction.original_transaction_id,
+            Self::Consumable | Self::AutoRenewableSubscription | Self::NonRenewingSubscription => {
+                &transaction.transaction_id
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurchaseEventKind {
+    /// First time seen, credit it.
+    NewlyGranted,
+    AlreadyGranted,
+    /// Refunded or revoked, `was_granted` tells whether to take the credit back.
+    Revoked {
+        was_granted: bool,
+    },
+    AlreadyRevoked,
+}
+
+#[derive(Debug, Clone)]
+pub struct PurchaseEvent {
+    pub kind: PurchaseEventKind,
+    /// The key of the ledger entry, see `ProductType::ledger_key`.
+    pub ledger_key: String,
+    pub transaction_id: String,
+    pub original_transaction_id: String,
+    pub product_id: String,
+    /// None for the products not registered.
+    pub product_type: Option<ProductType>,
+    pub quantity: usize,
+    pub purchase_date: DateTime<Utc>,
+    pub cancellation_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerEntryState {
+    Granted,
+    Revoked { was_granted: bool },
+}
+
+/// Tracks which purchases were granted in a `PurchaseLedgerStore`, so that feeding the same
+/// receipt again, from any process, never credits a consumable twice.
+#[derive(Debug, Clone, Default)]
+pub struct PurchaseLedger {
+    product_types: HashMap<String, ProductType>,
+}
+
+impl PurchaseLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_product(
+        &mut self,
+        product_id: impl Into<String>,
+        product_type: ProductType,
+    ) -> &mut Self {
+        self.product_types.insert(product_id.into(), product_type);
+        self
+    }
+
+    pub fn product_type(&self, product_id: &str) -> Option<ProductType> {
+        self.product_types.get(product_id).copied()
+    }
+
+    /// Unregistered products are keyed like consumables.
+    pub fn ledger_key<'a>(&self, transaction: &'a Transaction) -> &'a str {
+        self.product_type(&transaction.product_id)
+            .unwrap_or(ProductType::Consumable)
+            .ledger_key(transaction)
+    }
+
+    /// Error bodies produce no events.
+    pub fn apply_response_body<S: PurchaseLedgerStore>(
+        &self,
+        body: &ResponseBody,
+        store: &mut S,
+    ) -> Result<Vec<PurchaseEvent>, S::Error> {
+        match body {
+            ResponseBody::Success(body) => self.apply_response_body_with_success(body, store),
+            ResponseBody::Error(_) => Ok(vec![]),
+        }
+    }
+
+    /// Uses both `receipt.in_app` and `latest_receipt_info`.
+    pub fn apply_response_body_with_success<S: PurchaseLedgerStore>(
+        &self,
+        body: &ResponseBodyWithSuccess,
+        store: &mut S,
+    ) -> Result<Vec<PurchaseEvent>, S::Error> {
+        let items = body
+            .receipt
+            .in_app
+            .iter()
+            .flatten()
+            .map(|x| (&x.transaction, false))
+            .chain(
+                body.latest_receipt_info
+                    .iter()
+                    .flatten()
+                    .map(|x| (&x.transaction, x.is_upgraded == Some(true))),
+            );
+        self.apply_items(items, store)
+    }
+
+    /// An upgraded subscription is cancelled at the upgrade time, which is not a revocation,
+    /// so only pass `is_upgraded` true for those.
+    pub fn apply_transactions<'a, S: PurchaseLedgerStore>(
+        &self,
+        transactions: impl IntoIterator<Item = (&'a Transaction, bool)>,
+        store: &mut S,
+    ) -> Result<Vec<PurchaseEvent>, S::Error> {
+        self.apply_items(transactions, store)
+    }
+
+    fn apply_items<'a, S: PurchaseLedgerStore>(
+        &self,
+        items: impl IntoIterator<Item = (&'a Transaction, bool)>,
+        store: &mut S,
+    ) -> Result<Vec<PurchaseEvent>, S::Error> {
+        // The same transaction may be in both in_app and latest_receipt_info, only the latter
+        // carries is_upgraded, so merge the flags and keep the cancelled entry.
+        let mut transactions: BTreeMap<&str, (&Transaction, bool)> = BTreeMap::new();
+        for (transaction, is_upgraded) in items {
+            match transactions.get_mut(transaction.transaction_id.as_str()) {
+                Some((existing, existing_is_upgraded)) => {
+                    if existing.cancellation_date.is_none() {
+                        *existing = transaction;
+                    }
+                    *existing_is_upgraded |= is_upgraded;
+                }
+                None => {
+                    transactions.insert(&transaction.transaction_id, (transaction, is_upgraded));
+                }
+            }
+        }
+
+        // Oldest first, so the original purchase of a non-consumable is the granted one.
+        let mut transactions = transactions.into_values().collect::<Vec<_>>();
+        transactions.sort_by(|(a, _), (b, _)| {
+            (a.purchase_date, &a.transaction_id).cmp(&(b.purchase_date, &b.transaction_id))
+        });
+
+        transactions
+            .into_iter()
+            .map(|(transaction, is_upgraded)| {
+                let is_revoked = transaction.cancellation_date.is_some() && !is_upgraded;
+                let ledger_key = self.ledger_key(transaction);
+                let kind = apply(store, ledger_key, is_revoked)?;
+                Ok(PurchaseEvent {
+                    kind,
+                    ledger_key: ledger_key.to_owned(),
+                    transaction_id: transaction.transaction_id.to_owned(),
+                    original_transaction_id: transaction.original_transaction_id.to_owned(),
+                    product_id: transaction.product_id.to_owned(),
+                    product_type: self.product_type(&transaction.product_id),
+                    quantity: transaction.quantity,
+                    purchase_date: transaction.purchase_date,
+                    cancellation_date: transaction.cancellation_date,
+                })
+            })
+            .collect()
+    }
+}
+
+// Entries only move forward, Granted -> Revoked, so a failed write tells the state.
+fn apply<S: PurchaseLedgerStore>(
+    store: &mut S,
+    key: &str,
+    is_revoked: bool,
+) -> Result<PurchaseEventKind, S::Error> {
+    if !is_revoked {
+        if store.insert_if_absent(key, LedgerEntryState::Granted)? {
+            return Ok(PurchaseEventKind::NewlyGranted);
+        }
+        return Ok(match store.get(key)? {
+            Some(LedgerEntryState::Revoked { .. }) => PurchaseEventKind::AlreadyRevoked,
+            Some(LedgerEntryState::Granted) | None => PurchaseEventKind::AlreadyGranted,
+        });
+    }
+
+    let revoked = LedgerEntryState::Revoked { was_granted: false };
+    if store.insert_if_absent(key, revoked)? {
+        return Ok(PurchaseEventKind::Revoked { was_granted: false });
+    }
+    let revoked = LedgerEntryState::Revoked { was_granted: true };
+    if store.compare_and_swap(key, LedgerEntryState::Granted, revoked)? {
+        return Ok(PurchaseEventKind::Revoked { was_granted: true });
+    }
+    Ok(PurchaseEventKind::AlreadyRevoked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::entitlement::InMemoryPurchaseLedgerStore;
+
+    fn body(value: &serde_json::Value) -> Result<ResponseBody, Box<dyn error::Error>> {
+        Ok(serde_json::from_value(value.to_owned())?)
+    }
+
+    fn kinds(events: &[PurchaseEvent]) -> Vec<(&str, PurchaseEventKind)> {
+        events
+            .iter()
+            .map(|x| (x.transaction_id.as_str(), x.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_response_body() -> Result<(), Box<dyn error::Error>> {
+        let mut value: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/entitlement_files/subscriptions.json"
+        ))?;
+        // A consumable, in in_app only
+        let mut coins = value["receipt"]["in_app"][7].clone();
+        coins["product_id"] = "com.example.app.coins100".into();
+        coins["transaction_id"] = "1000000000000070".into();
+        coins["original_transaction_id"] = "1000000000000070".into();
+        coins["quantity"] = "2".into();
+        coins["purchase_date_ms"] = "1736848800000".into();
+        value["receipt"]["in_app"]
+            .as_array_mut()
+            .expect("")
+            .push(coins.clone());
+
+        let mut ledger = PurchaseLedger::new();
+        ledger
+            .register_product("com.example.app.coins100", ProductType::Consumable)
+            .register_product("com.example.app.pro", ProductType::NonConsumable);
+        let mut store = InMemoryPurchaseLedgerStore::new();
+
+        let events = ledger.apply_response_body(&body(&value)?, &mut store)?;
+        assert_eq!(
+            kinds(&events),
+            vec![
+                (
+                    "1000000000000031",
+                    PurchaseEventKind::Revoked { was_granted: false }
+                ),
+                ("1000000000000051", PurchaseEventKind::NewlyGranted),
+                ("1000000000000061", PurchaseEventKind::NewlyGranted),
+                ("1000000000000011", PurchaseEventKind::NewlyGranted),
+                ("1000000000000012", PurchaseEventKind::NewlyGranted),
+                ("1000000000000041", PurchaseEventKind::NewlyGranted),
+                ("1000000000000021", PurchaseEventKind::NewlyGranted),
+                ("1000000000000022", PurchaseEventKind::NewlyGranted),
+                ("1000000000000013", PurchaseEventKind::NewlyGranted),
+                ("1000000000000070", PurchaseEventKind::NewlyGranted),
+            ]
+        );
+        let coins_event = events.last().expect("");
+        assert_eq!(coins_event.product_type, Some(ProductType::Consumable));
+        assert_eq!(coins_event.quantity, 2);
+        assert_eq!(events[1].product_type, Some(ProductType::NonConsumable));
+        assert_eq!(events[2].product_type, None);
+
+        // Idempotent
+        let events = ledger.apply_response_body(&body(&value)?, &mut store)?;
+        assert!(events.iter().all(|x| matches!(
+            x.kind,
+            PurchaseEventKind::AlreadyGranted | PurchaseEventKind::AlreadyRevoked
+        )));
+
+        // The consumable refunded later
+        coins["cancellation_date_ms"] = "1736899200000".into();
+        value["receipt"]["in_app"]
+            .as_array_mut()
+            .expect("")
+            .push(coins);
+        let events = ledger.apply_response_body(&body(&value)?, &mut store)?;
+        assert_eq!(
+            events
+                .iter()
+                .find(|x| x.transaction_id == "1000000000000070")
+                .map(|x| x.kind),
+            Some(PurchaseEventKind::Revoked { was_granted: true })
+        );
+        assert_eq!(
+            store.get("1000000000000070")?,
+            Some(LedgerEntryState::Revoked { was_granted: true })
+        );
+
+        // Another process sharing the store
+        assert!(PurchaseLedger::new()
+            .apply_response_body(&body(&value)?, &mut store)?
+            .iter()
+            .all(|x| x.kind != PurchaseEventKind::NewlyGranted));
+
+        // A restored non-consumable is keyed by original_transaction_id, not credited again
+        let in_app = value["receipt"]["in_app"].as_array_mut().expect("");
+        let mut restored = in_app
+            .iter()
+            .find(|x| x["transaction_id"] == "1000000000000051")
+            .ok_or("pro missing")?
+            .clone();
+        restored["transaction_id"] = "1000000000000080".into();
+        restored["purchase_date_ms"] = "1736899200000".into();
+        in_app.push(restored);
+        let events = ledger.apply_response_body(&body(&value)?, &mut store)?;
+        let restored_event = events
+            .iter()
+            .find(|x| x.transaction_id == "1000000000000080")
+            .ok_or("restored missing")?;
+        assert_eq!(restored_event.kind, PurchaseEventKind::AlreadyGranted);
+        assert_eq!(restored_event.ledger_key, "1000000000000051");
+        // Unregistered, each transaction is its own entry
+        let events = PurchaseLedger::new().apply_response_body(&body(&value)?, &mut store)?;
+        assert_eq!(
+            events
+                .iter()
+                .find(|x| x.transaction_id == "1000000000000080")
+                .map(|x| x.kind),
+            Some(PurchaseEventKind::NewlyGranted)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    convert::Infallible,
+};
+
+use super::ledger::LedgerEntryState;
+
+/// Where the ledger entries live, e.g. a database table with a unique key.
+///
+/// Both writes must be atomic, so that concurrent processes never grant or revoke twice.
+pub trait PurchaseLedgerStore {
+    type Error;
+
+    /// Stores `state` only when there is no entry for `key` yet, e.g. `INSERT ... ON CONFLICT DO NOTHING`,
+    /// returns whether it was stored.
+    fn insert_if_absent(&mut self, key: &str, state: LedgerEntryState)
+        -> Result<bool, Self::Error>;
+
+    /// Replaces the entry for `key` only when it is still `current`, returns whether it was replaced.
+    fn compare_and_swap(
+        &mut self,
+        key: &str,
+        current: LedgerEntryState,
+        new: LedgerEntryState,
+    ) -> Result<bool, Self::Error>;
+
+    fn get(&self, key: &str) -> Result<Option<LedgerEntryState>, Self::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryPurchaseLedgerStore {
+    inner: BTreeMap<String, LedgerEntryState>,
+}
+
+impl InMemoryPurchaseLedgerStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&str, LedgerEntryState)> {
+        self.inner.iter().map(|(k, v)| (k.as_str(), *v))
+    }
+}
+
+impl PurchaseLedgerStore for InMemoryPurchaseLedgerStore {
+    type Error = Infallible;
+
+    fn insert_if_absent(
+        &mut self,
+        key: &str,
+        state: LedgerEntryState,
+    ) -> Result<bool, Self::Error> {
+        match self.inner.entry(key.to_owned()) {
+            Entry::Vacant(entry) => {
+                entry.insert(state);
+                Ok(true)
+            }
+            Entry::Occupied(_) => Ok(false),
+        }
+    }
+
+    fn compare_and_swap(
+        &mut self,
+        key: &str,
+        current: LedgerEntryState,
+        new: LedgerEntryState,
+    ) -> Result<bool, Self::Error> {
+        match self.inner.get_mut(key) {
+            Some(state) if *state == current => {
+                *state = new;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<LedgerEntryState>, Self::Error> {
+        Ok(self.inner.get(key).copied())
+    }
+}
@@ -0,0 +1,7 @@
+pub mod ledger;
+pub mod ledger_store;
+pub mod subscription;
+
+pub use ledger::{LedgerEntryState, ProductType, PurchaseEvent, PurchaseEventKind, PurchaseLedger};
+pub use ledger_store::{InMemoryPurchaseLedgerStore, PurchaseLedgerStore};
+pub use subscription::{EntitlementState, Entitlements, FamilySharingPolicy, ProductEntitlement};
@@ -0,0 +1,531 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::objects::response_body::{
+    ExpirationIntent, InAppOwnershipType, LatestReceiptInfo, PendingRenewalInfo,
+    ResponseBodyWithSuccess, Transaction,
+};
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntitlementState {
+    Active,
+    Expired,
+    Refunded,
+    /// Family shared, and the policy is `FamilySharingPolicy::Deny`.
+    FamilySharingDenied,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FamilySharingPolicy {
+    #[default]
+    Grant,
+    /// Only the purchaser gets the access, e.g. for products not enabled for Family Sharing.
+    Deny,
+}
+
+impl FamilySharingPolicy {
+    fn granted_state(
+        &self,
+        in_app_ownership_type: Option<&InAppOwnershipType>,
+    ) -> EntitlementState {
+        match (self, in_app_ownership_type) {
+            (Self::Deny, Some(InAppOwnershipType::FamilyShared)) => {
+                EntitlementState::FamilySharingDenied
+            }
+            _ => EntitlementState::Active,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProductEntitlement {
+    pub product_id: String,
+    pub original_transaction_id: String,
+    pub subscription_group_identifier: Option<String>,
+    pub state: EntitlementState,
+    /// None for non-subscription products.
+    pub expires_date: Option<DateTime<Utc>>,
+    pub cancellation_date: Option<DateTime<Utc>>,
+    /// True when the access ended because the user upgraded to another product in the same group.
+    pub is_upgraded: bool,
+    pub latest_transaction_id: String,
+    pub in_app_ownership_type: Option<InAppOwnershipType>,
+
+    // From pending_renewal_info
+    pub auto_renew_status: Option<bool>,
+    pub auto_renew_product_id: Option<String>,
+    pub expiration_intent: Option<ExpirationIntent>,
+    pub is_in_billing_retry_period: bool,
+    pub grace_period_expires_date: Option<DateTime<Utc>>,
+    /// Expired, but the billing grace period still grants access.
+    pub is_in_grace_period: bool,
+}
+
+impl ProductEntitlement {
+    pub fn is_active(&self) -> bool {
+        self.state == EntitlementState::Active
+    }
+
+    pub fn is_family_shared(&self) -> bool {
+        self.in_app_ownership_type == Some(InAppOwnershipType::FamilyShared)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Entitlements {
+    /// Sorted by product_id.
+    pub products: Vec<ProductEntitlement>,
+    pub family_sharing_policy: FamilySharingPolicy,
+}
+
+impl Entitlements {
+    /// Uses `latest_receipt_info`, falls back to `receipt.in_app` when absent,
+    /// then applies `pending_renewal_info`.
+    pub fn from_response_body(body: &ResponseBodyWithSuccess, at: &DateTime<Utc>) -> Self {
+        Self::from_response_body_with_family_sharing_policy(body, at, Default::default())
+    }
+
+    pub fn from_response_body_with_family_sharing_policy(
+        body: &ResponseBodyWithSuccess,
+        at: &DateTime<Utc>,
+        family_sharing_policy: FamilySharingPolicy,
+    ) -> Self {
+        let mut entitlements = match &body.latest_receipt_info {
+            Some(latest_receipt_info) => Self::from_latest_receipt_info_with_family_sharing_policy(
+                latest_receipt_info,
+                at,
+                family_sharing_policy,
+            ),
+            None => Self::from_items(
+                body.receipt
+                    .in_app
+                    .iter()
+                    .flatten()
+                    .map(|x| Item {
+                        transaction: &x.transaction,
+                        subscription_group_identifier: x.subscription_group_identifier.as_deref(),
+                        is_upgraded: false,
+                    })
+                    .collect(),
+                at,
+                family_sharing_policy,
+            ),
+        };
+        if let Some(pending_renewal_info) = &body.pending_renewal_info {
+            entitlements.apply_pending_renewal_info(pending_renewal_info, at);
+        }
+        entitlements
+    }
+
+    pub fn from_latest_receipt_info(
+        latest_receipt_info: &[LatestReceiptInfo],
+        at: &DateTime<Utc>,
+    ) -> Self {
+        Self::from_latest_receipt_info_with_family_sharing_policy(
+            latest_receipt_info,
+            at,
+            Default::default(),
+        )
+    }
+
+    pub fn from_latest_receipt_info_with_family_sharing_policy(
+        latest_receipt_info: &[LatestReceiptInfo],
+        at: &DateTime<Utc>,
+        family_sharing_policy: FamilySharingPolicy,
+    ) -> Self {
+        Self::from_items(
+            latest_receipt_info
+                .iter()
+                .map(|x| Item {
+                    transaction: &x.transaction,
+                    subscription_group_identifier: x.subscription_group_identifier.as_deref(),
+                    is_upgraded: x.is_upgraded == Some(true),
+                })
+                .collect(),
+            at,
+            family_sharing_policy,
+        )
+    }
+
+    fn from_items(
+        items: Vec<Item<'_>>,
+        at: &DateTime<Utc>,
+        family_sharing_policy: FamilySharingPolicy,
+    ) -> Self {
+        // original_transaction_id -> subscription_group_identifier
+        // Only some transactions of a chain may carry the group.
+        let mut groups: BTreeMap<&str, &str> = BTreeMap::new();
+        for item in &items {
+            if let Some(group) = item.subscription_group_identifier {
+                groups.insert(&item.transaction.original_transaction_id, group);
+            }
+        }
+
+        // (original_transaction_id, product_id) -> latest item
+        let mut latest: BTreeMap<(&str, &str), &Item<'_>> = BTreeMap::new();
+        for item in &items {
+            let key = (
+                item.transaction.original_transaction_id.as_str(),
+                item.transaction.product_id.as_str(),
+            );
+            match latest.get(&key) {
+                Some(existing) if existing.sort_key() >= item.sort_key() => {}
+                _ => {
+                    latest.insert(key, item);
+                }
+            }
+        }
+
+        // product_id -> entitlement, the best one wins when a product is in multiple chains
+        let mut products: BTreeMap<&str, ProductEntitlement> = BTreeMap::new();
+        for ((original_transaction_id, product_id), item) in latest {
+            let entitlement = item.to_entitlement(
+                groups.get(original_transaction_id).map(|x| x.to_string()),
+                at,
+                family_sharing_policy,
+            );
+            match products.get(product_id) {
+                Some(existing) if !is_better(&entitlement, existing) => {}
+                _ => {
+                    products.insert(product_id, entitlement);
+                }
+            }
+        }
+
+        Self {
+            products: products.into_values().collect(),
+            family_sharing_policy,
+        }
+    }
+
+    pub fn apply_pending_renewal_info(
+        &mut self,
+        pending_renewal_info: &[PendingRenewalInfo],
+        at: &DateTime<Utc>,
+    ) {
+        let family_sharing_policy = self.family_sharing_policy;
+        for info in pending_renewal_info {
+            let entitlement = match self.products.iter_mut().find(|x| {
+                x.original_transaction_id == info.original_transaction_id
+                    && x.product_id == info.product_id
+                    && !x.is_upgraded
+            }) {
+                Some(x) => x,
+                None => continue,
+            };
+
+            entitlement.auto_renew_status = info.auto_renew_status;
+            entitlement.auto_renew_product_id = info.auto_renew_product_id.to_owned();
+            entitlement.expiration_intent = info.expiration_intent.to_owned();
+            entitlement.is_in_billing_retry_period = info.is_in_billing_retry_period == Some(true);
+            entitlement.grace_period_expires_date = info.grace_period_expires_date;
+
+            if entitlement.state == EntitlementState::Expired {
+                if let Some(grace_period_expires_date) = info.grace_period_expires_date {
+                    if grace_period_expires_date > *at {
+                        entitlement.state = family_sharing_policy
+                            .granted_state(entitlement.in_app_ownership_type.as_ref());
+                        entitlement.is_in_grace_period = true;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, product_id: &str) -> Option<&ProductEntitlement> {
+        self.products.iter().find(|x| x.product_id == product_id)
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &ProductEntitlement> {
+        self.products.iter().filter(|x| x.is_active())
+    }
+
+    pub fn is_product_active(&self, product_id: &str) -> bool {
+        self.get(product_id).map(|x| x.is_active()).unwrap_or(false)
+    }
+
+    pub fn active_in_subscription_group(
+        &self,
+        subscription_group_identifier: &str,
+    ) -> Option<&ProductEntitlement> {
+        self.active().find(|x| {
+            x.subscription_group_identifier.as_deref() == Some(subscription_group_identifier)
+        })
+    }
+}
+
+//
+struct Item<'a> {
+    transaction: &'a Transaction,
+    subscription_group_identifier: Option<&'a str>,
+    is_upgraded: bool,
+}
+
+impl<'a> Item<'a> {
+    fn sort_key(&self) -> (Option<DateTime<Utc>>, DateTime<Utc>) {
+        (
+            self.transaction.expires_date,
+            self.transaction.purchase_date,
+        )
+    }
+
+    fn to_entitlement(
+        &self,
+        subscription_group_identifier: Option<String>,
+        at: &DateTime<Utc>,
+        family_sharing_policy: FamilySharingPolicy,
+    ) -> ProductEntitlement {
+        let transaction = self.transaction;
+
+        // An upgraded transaction is cancelled at the upgrade time, which is not a refund.
+        let state = match (transaction.cancellation_date, self.is_upgraded) {
+            (Some(cancellation_date), false) if cancellation_date <= *at => {
+                EntitlementState::Refunded
+            }
+            (Some(cancellation_date), true) if cancellation_date <= *at => {
+                EntitlementState::Expired
+            }
+            _ => match transaction.expires_date {
+                Some(expires_date) if expires_date <= *at => EntitlementState::Expired,
+                _ => EntitlementState::Active,
+            },
+        };
+        // Applied per chain, so that a purchase by the user still wins over the shared one.
+        let state = match state {
+            EntitlementState::Active => {
+                family_sharing_policy.granted_state(transaction.in_app_ownership_type.as_ref())
+            }
+            state => state,
+        };
+
+        ProductEntitlement {
+            product_id: transaction.product_id.to_owned(),
+            original_transaction_id: transaction.original_transaction_id.to_owned(),
+            subscription_group_identifier,
+            state,
+            expires_date: transaction.expires_date,
+            cancellation_date: transaction.cancellation_date,
+            is_upgraded: self.is_upgraded,
+            latest_transaction_id: transaction.transaction_id.to_owned(),
+            in_app_ownership_type: transaction.in_app_ownership_type.to_owned(),
+            auto_renew_status: None,
+            auto_renew_product_id: None,
+            expiration_intent: None,
+            is_in_billing_retry_period: false,
+            grace_period_expires_date: None,
+            is_in_grace_period: false,
+        }
+    }
+}
+
+fn is_better(a: &ProductEntitlement, b: &ProductEntitlement) -> bool {
+    match (a.is_active(), b.is_active()) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => a.expires_date > b.expires_date,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::objects::response_body::ResponseBody;
+
+    fn body() -> Result<Box<ResponseBodyWithSuccess>, Box<dyn error::Error>> {
+        match serde_json::from_str(include_str!(
+            "../../tests/entitlement_files/subscriptions.json"
+        ))? {
+            ResponseBody::Success(body) => Ok(body),
+            ResponseBody::Error(body) => Err(format!("{body:?}").into()),
+        }
+    }
+
+    #[test]
+    fn test_from_response_body() -> Result<(), Box<dyn error::Error>> {
+        let body = body()?;
+        // 2025-01-15T00:00:00Z
+        let at = DateTime::from_timestamp_millis(1736899200000).unwrap();
+
+        let entitlements = Entitlements::from_response_body(&body, &at);
+        assert_eq!(
+            entitlements
+                .products
+                .iter()
+                .map(|x| (x.product_id.as_str(), x.state))
+                .collect::<Vec<_>>(),
+            vec![
+                ("com.example.app.basic", EntitlementState::Expired),
+                ("com.example.app.monthly", EntitlementState::Active),
+                ("com.example.app.premium", EntitlementState::Active),
+                ("com.example.app.pro", EntitlementState::Active),
+                ("com.example.app.quarterly", EntitlementState::Active),
+                ("com.example.app.weekly", EntitlementState::Expired),
+                ("com.example.app.yearly", EntitlementState::Refunded),
+            ]
+        );
+
+        let monthly = entitlements.get("com.example.app.monthly").unwrap();
+        assert_eq!(monthly.latest_transaction_id, "1000000000000013");
+        assert_eq!(monthly.original_transaction_id, "1000000000000010");
+        assert_eq!(
+            monthly.expires_date.map(|x| x.timestamp_millis()),
+            Some(1738368000000)
+        );
+
+        assert_eq!(monthly.auto_renew_status, Some(true));
+        assert!(!monthly.is_in_grace_period);
+
+        let premium = entitlements.get("com.example.app.premium").unwrap();
+        assert_eq!(
+            premium.auto_renew_product_id,
+            Some("com.example.app.basic".to_owned())
+        );
+
+        let quarterly = entitlements.get("com.example.app.quarterly").unwrap();
+        assert!(quarterly.is_in_grace_period);
+        assert!(quarterly.is_in_billing_retry_period);
+        assert_eq!(
+            quarterly.expiration_intent,
+            Some(ExpirationIntent::BillingError)
+        );
+        assert_eq!(
+            quarterly
+                .grace_period_expires_date
+                .map(|x| x.timestamp_millis()),
+            Some(1738022400000)
+        );
+
+        let weekly = entitlements.get("com.example.app.weekly").unwrap();
+        assert_eq!(weekly.auto_renew_status, Some(false));
+        assert_eq!(
+            weekly.expiration_intent,
+            Some(ExpirationIntent::CustomerCanceled)
+        );
+
+        let basic = entitlements.get("com.example.app.basic").unwrap();
+        assert!(basic.auto_renew_status.is_none());
+        assert!(basic.is_upgraded);
+        assert_eq!(
+            basic.subscription_group_identifier,
+            Some("20000002".to_owned())
+        );
+
+        assert!(entitlements
+            .get("com.example.app.pro")
+            .unwrap()
+            .expires_date
+            .is_none());
+
+        assert_eq!(
+            entitlements
+                .active_in_subscription_group("20000002")
+                .map(|x| x.product_id.as_str()),
+            Some("com.example.app.premium")
+        );
+        assert!(entitlements
+            .active_in_subscription_group("20000003")
+            .is_none());
+        assert!(!entitlements.is_product_active("com.example.app.yearly"));
+        assert_eq!(
+            entitlements
+                .get("com.example.app.yearly")
+                .unwrap()
+                .expiration_intent,
+            Some(ExpirationIntent::Other("9".to_owned()))
+        );
+
+        // 2025-02-15T00:00:00Z
+        let at = DateTime::from_timestamp_millis(1739577600000).unwrap();
+        let entitlements = Entitlements::from_response_body(&body, &at);
+        assert_eq!(
+            entitlements
+                .active()
+                .map(|x| x.product_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["com.example.app.pro"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_response_body_without_latest_receipt_info() -> Result<(), Box<dyn error::Error>> {
+        let mut body = body()?;
+        body.latest_receipt_info = None;
+        body.pending_renewal_info = None;
+        let at = DateTime::from_timestamp_millis(1736899200000).unwrap();
+
+        let entitlements = Entitlements::from_response_body(&body, &at);
+        assert!(entitlements.is_product_active("com.example.app.monthly"));
+        assert!(!entitlements.is_product_active("com.example.app.quarterly"));
+        // Without is_upgraded the cancellation looks like a refund.
+        assert_eq!(
+            entitlements.get("com.example.app.basic").map(|x| x.state),
+            Some(EntitlementState::Refunded)
+        );
+        assert!(entitlements
+            .get("com.example.app.monthly")
+            .unwrap()
+            .subscription_group_identifier
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_family_sharing_policy() -> Result<(), Box<dyn error::Error>> {
+        let mut value: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/entitlement_files/subscriptions.json"
+        ))?;
+        for x in value["latest_receipt_info"]
+            .as_array_mut()
+            .ok_or("latest_receipt_info missing")?
+        {
+            let in_app_ownership_type = match x["product_id"].as_str() {
+                Some("com.example.app.monthly" | "com.example.app.quarterly") => "FAMILY_SHARED",
+                _ => "PURCHASED",
+            };
+            x["in_app_ownership_type"] = in_app_ownership_type.into();
+        }
+        let body = match serde_json::from_value(value)? {
+            ResponseBody::Success(body) => body,
+            ResponseBody::Error(body) => return Err(format!("{body:?}").into()),
+        };
+        let at = DateTime::from_timestamp_millis(1736899200000).unwrap();
+
+        let entitlements = Entitlements::from_response_body(&body, &at);
+        let monthly = entitlements.get("com.example.app.monthly").unwrap();
+        assert!(monthly.is_active());
+        assert!(monthly.is_family_shared());
+        assert!(entitlements.is_product_active("com.example.app.quarterly"));
+
+        let entitlements = Entitlements::from_response_body_with_family_sharing_policy(
+            &body,
+            &at,
+            FamilySharingPolicy::Deny,
+        );
+        assert_eq!(
+            entitlements.get("com.example.app.monthly").map(|x| x.state),
+            Some(EntitlementState::FamilySharingDenied)
+        );
+        // In the grace period
+        assert_eq!(
+            entitlements
+                .get("com.example.app.quarterly")
+                .map(|x| x.state),
+            Some(EntitlementState::FamilySharingDenied)
+        );
+        assert!(entitlements.is_product_active("com.example.app.premium"));
+        assert!(!entitlements
+            .get("com.example.app.premium")
+            .unwrap()
+            .is_family_shared());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,447 @@
+// ref https://developer.apple.com/library/archive/releasenotes/General/ValidateAppStoreReceipt/Chapters/ReceiptFields.html
+
+use chrono::{DateTime, Utc};
+use sha1::{Digest as _, Sha1};
+
+use crate::{
+    local_receipt::pkcs7::{OID_DATA, OID_SIGNED_DATA},
+    objects::response_body::{Receipt, Transaction},
+};
+
+const OPAQUE_VALUE_DEFAULT: [u8; 16] = [
+    0x0f, 0x1e, 0x2d, 0x3c, 0x4b, 0x5a, 0x69, 0x78, 0x87, 0x96, 0xa5, 0xb4, 0xc3, 0xd2, 0xe1, 0xf0,
+];
+
+/// Encodes a `Receipt`, e.g. from `ResponseBodyBuilder`, as the PKCS#7 receipt on the device.
+#[derive(Debug, Clone)]
+pub struct LocalReceiptBuilder {
+    device_identifier: Vec<u8>,
+    opaque_value: Vec<u8>,
+}
+
+impl LocalReceiptBuilder {
+    /// `device_identifier` is what `LocalReceipt::matches_device` is checked against.
+    pub fn new(device_identifier: impl AsRef<[u8]>) -> Self {
+        Self {
+            device_identifier: device_identifier.as_ref().to_vec(),
+            opaque_value: OPAQUE_VALUE_DEFAULT.to_vec(),
+        }
+    }
+
+    pub fn set_opaque_value(&mut self, opaque_value: impl AsRef<[u8]>) -> &mut Self {
+        self.opaque_value = opaque_value.as_ref().to_vec();
+        self
+    }
+
+    /// The content of the PKCS#7 container.
+    pub fn build_payload(&self, receipt: &Receipt) -> Vec<u8> {
+        let bundle_id_data = der_utf8_string(&receipt.bundle_id);
+
+        let mut hasher = Sha1::new();
+        hasher.update(&self.device_identifier);
+        hasher.update(&self.opaque_value);
+        hasher.update(&bundle_id_data);
+        let sha1_hash = hasher.finalize().to_vec();
+
+        let mut attributes = vec![
+            attribute(0, der_utf8_string(&receipt.receipt_type.to_string())),
+            attribute(1, der_integer(receipt.app_item_id as i64)),
+            attribute(2, bundle_id_data),
+            attribute(3, der_utf8_string(&receipt.application_version)),
+            attribute(4, self.opaque_value.to_owned()),
+            attribute(5, sha1_hash),
+            attribute(12, der_date(Some(&receipt.receipt_creation_date))),
+            attribute(16, der_integer(receipt.version_external_identifier as i64)),
+            attribute(18, der_date(Some(&receipt.original_purchase_date))),
+        ];
+        if let Some(download_id) = receipt.download_id {
+            attributes.push(attribute(15, der_integer(download_id as i64)));
+        }
+        if let Some(original_application_version) = &receipt.original_application_version {
+            attributes.push(attribute(19, der_utf8_string(original_application_version)));
+        }
+        if let Some(expiration_date) = &receipt.expiration_date {
+            attributes.push(attribute(21, der_date(Some(expiration_date))));
+        }
+        if let Some(preorder_date) = &receipt.preorder_date {
+            attributes.push(attribute(32, der_date(Some(preorder_date))));
+        }
+        for in_app in receipt.in_app.iter().flatten() {
+            attributes.push(attribute(17, in_app_payload(&in_app.transaction)));
+        }
+
+        der_set_of(attributes)
+    }
+
+    /// Without signer, enough for `LocalReceipt::from_pkcs7_bytes`.
+    pub fn build_unsigned(&self, receipt: &Receipt) -> Vec<u8> {
+        signed_data(&self.build_payload(receipt), vec![], vec![], vec![])
+    }
+
+    /// Verifiable by a `LocalReceiptVerifier` of the root of `certificates`.
+    /// `certificates` are DER encoded, the signer and its intermediate.
+    #[cfg(feature = "with-local-verification")]
+    pub fn build_signed(
+        &self,
+        receipt: &Receipt,
+        signer_key: &rsa::RsaPrivateKey,
+        signer_certificate: &[u8],
+        certificates: &[&[u8]],
+    ) -> Result<Vec<u8>, LocalReceiptBuilderError> {
+        use rsa::Pkcs1v15Sign;
+        use sha2::Sha256;
+        use x509_cert::{
+            der::{Decode as _, Encode as _},
+            Certificate,
+        };
+
+        const OID_SHA256: &str = "2.16.840.1.101.3.4.2.1";
+        const OID_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.1";
+
+        let signer = Certificate::from_der(signer_certificate)
+            .map_err(|err| LocalReceiptBuilderError::CertificateInvalid(err.to_string()))?;
+        let issuer = signer
+            .tbs_certificate
+            .issuer
+            .to_der()
+            .map_err(|err| LocalReceiptBuilderError::CertificateInvalid(err.to_string()))?;
+
+        let payload = self.build_payload(receipt);
+        let signature = signer_key
+            .sign(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(&payload))
+            .map_err(|err| LocalReceiptBuilderError::SignFailed(err.to_string()))?;
+
+        let signer_info = der(
+            TAG_SEQUENCE,
+            [
+                der_integer(1),
+                der(
+                    TAG_SEQUENCE,
+                    [
+                        issuer,
+                        der(
+                            TAG_INTEGER,
+                            signer.tbs_certificate.serial_number.as_bytes().to_vec(),
+                        ),
+                    ]
+                    .concat(),
+                ),
+                der_algorithm_identifier(OID_SHA256),
+                der_algorithm_identifier(OID_RSA_ENCRYPTION),
+                der(TAG_OCTET_STRING, signature),
+            ]
+            .concat(),
+        );
+
+        Ok(signed_data(
+            &payload,
+            vec![der_algorithm_identifier(OID_SHA256)],
+            certificates.iter().map(|x| x.to_vec()).collect(),
+            vec![signer_info],
+        ))
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LocalReceiptBuilderError {
+    #[error("CertificateInvalid {0}")]
+    CertificateInvalid(String),
+    #[error("SignFailed {0}")]
+    SignFailed(String),
+}
+
+//
+//
+//
+fn in_app_payload(transaction: &Transaction) -> Vec<u8> {
+    let web_order_line_item_id = transaction
+        .web_order_line_item_id
+        .as_deref()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(0);
+
+    der_set_of(vec![
+        attribute(1701, der_integer(transaction.quantity as i64)),
+        attribute(1702, der_utf8_string(&transaction.product_id)),
+        attribute(1703, der_utf8_string(&transaction.transaction_id)),
+        attribute(1704, der_date(Some(&transaction.purchase_date))),
+        attribute(1705, der_utf8_string(&transaction.original_transaction_id)),
+        attribute(1706, der_date(Some(&transaction.original_purchase_date))),
+        attribute(1708, der_date(transaction.expires_date.as_ref())),
+        attribute(1711, der_integer(web_order_line_item_id)),
+        attribute(1712, der_date(transaction.cancellation_date.as_ref())),
+        attribute(
+            1713,
+            der_integer(transaction.is_trial_period.unwrap_or_default() as i64),
+        ),
+        attribute(
+            1719,
+            der_integer(transaction.is_in_intro_offer_period.unwrap_or_default() as i64),
+        ),
+        attribute(
+            1721,
+            der_utf8_string(
+                transaction
+                    .promotional_offer_id
+                    .as_deref()
+                    .unwrap_or_default(),
+            ),
+        ),
+    ])
+}
+
+/*
+ReceiptAttribute ::= SEQUENCE {
+    type    INTEGER,
+    version INTEGER,
+    value   OCTET STRING
+}
+*/
+fn attribute(r#type: i64, value: Vec<u8>) -> Vec<u8> {
+    der(
+        TAG_SEQUENCE,
+        [
+            der_integer(r#type),
+            der_integer(1),
+            der(TAG_OCTET_STRING, value),
+        ]
+        .concat(),
+    )
+}
+
+// ContentInfo with SignedData
+fn signed_data(
+    payload: &[u8],
+    digest_algorithms: Vec<Vec<u8>>,
+    certificates: Vec<Vec<u8>>,
+    signer_infos: Vec<Vec<u8>>,
+) -> Vec<u8> {
+    let mut content = vec![
+        der_integer(1),
+        der_set_of(digest_algorithms),
+        der(
+            TAG_SEQUENCE,
+            [
+                der_oid(OID_DATA),
+                der(TAG_CONTEXT_0, der(TAG_OCTET_STRING, payload.to_vec())),
+            ]
+            .concat(),
+        ),
+    ];
+    if !certificates.is_empty() {
+        content.push(der(TAG_CONTEXT_0, certificates.concat()));
+    }
+    content.push(der_set_of(signer_infos));
+
+    der(
+        TAG_SEQUENCE,
+        [
+            der_oid(OID_SIGNED_DATA),
+            der(TAG_CONTEXT_0, der(TAG_SEQUENCE, content.concat())),
+        ]
+        .concat(),
+    )
+}
+
+//
+// DER
+//
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_UTF8_STRING: u8 = 0x0C;
+const TAG_IA5_STRING: u8 = 0x16;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_CONTEXT_0: u8 = 0xA0;
+
+fn der(tag: u8, content: Vec<u8>) -> Vec<u8> {
+    let len = content.len();
+    let mut buf = vec![tag];
+    if len < 0x80 {
+        buf.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let bytes = &bytes[bytes.iter().take_while(|x| **x == 0).count()..];
+        buf.push(0x80 | bytes.len() as u8);
+        buf.extend_from_slice(bytes);
+    }
+    buf.extend(content);
+    buf
+}
+
+fn der_integer(v: i64) -> Vec<u8> {
+    let bytes = v.to_be_bytes();
+    // Minimal two's complement
+    let mut start = 0;
+    while start < bytes.len() - 1
+        && ((bytes[start] == 0x00 && bytes[start + 1] & 0x80 == 0)
+            || (bytes[start] == 0xFF && bytes[start + 1] & 0x80 != 0))
+    {
+        start += 1;
+    }
+    der(TAG_INTEGER, bytes[start..].to_vec())
+}
+
+fn der_utf8_string(s: &str) -> Vec<u8> {
+    der(TAG_UTF8_STRING, s.as_bytes().to_vec())
+}
+
+// Empty when absent, as Apple does.
+fn der_date(dt: Option<&DateTime<Utc>>) -> Vec<u8> {
+    der(
+        TAG_IA5_STRING,
+        dt.map(|x| x.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+            .unwrap_or_default()
+            .into_bytes(),
+    )
+}
+
+fn der_oid(oid: &str) -> Vec<u8> {
+    let arcs = oid
+        .split('.')
+        .map(|x| x.parse::<u64>().expect("invalid oid"))
+        .collect::<Vec<_>>();
+    let mut content = vec![];
+    for arc in [arcs[0] * 40 + arcs[1]]
+        .into_iter()
+        .chain(arcs[2..].iter().copied())
+    {
+        let mut chunk = vec![(arc & 0x7F) as u8];
+        let mut arc = arc >> 7;
+        while arc > 0 {
+            chunk.push((arc & 0x7F) as u8 | 0x80);
+            arc >>= 7;
+        }
+        chunk.reverse();
+        content.extend(chunk);
+    }
+    der(TAG_OID, content)
+}
+
+#[cfg_attr(not(feature = "with-local-verification"), allow(dead_code))]
+fn der_algorithm_identifier(oid: &str) -> Vec<u8> {
+    der(TAG_SEQUENCE, [der_oid(oid), der(TAG_NULL, vec![])].concat())
+}
+
+// DER SET OF is sorted by encoding
+fn der_set_of(mut items: Vec<Vec<u8>>) -> Vec<u8> {
+    items.sort();
+    der(TAG_SET, items.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use chrono::Duration;
+
+    use crate::{
+        fixture::{PurchaseFixture, ResponseBodyBuilder, SubscriptionFixture},
+        local_receipt::LocalReceipt,
+        objects::response_body::{Environment, ReceiptType},
+    };
+
+    const DEVICE_ID: &[u8] = b"device-id-0001";
+
+    fn receipt() -> Result<Receipt, Box<dyn error::Error>> {
+        let start = DateTime::from_timestamp(1733011200, 0).ok_or("")?;
+        let mut builder = ResponseBodyBuilder::new("com.example.app", Environment::Sandbox);
+        let mut monthly = SubscriptionFixture::new(
+            "com.example.app.monthly",
+            "20000001",
+            start,
+            Duration::days(30),
+        );
+        monthly.set_free_trial(true).set_renewal_count(1);
+        builder
+            .add_purchase(PurchaseFixture::new("com.example.app.pro", start))
+            .add_subscription(monthly);
+        Ok(builder.build(start + Duration::days(45)).receipt)
+    }
+
+    #[test]
+    fn test_build_unsigned() -> Result<(), Box<dyn error::Error>> {
+        let receipt = receipt()?;
+        let bytes = LocalReceiptBuilder::new(DEVICE_ID).build_unsigned(&receipt);
+
+        let local_receipt = LocalReceipt::from_pkcs7_bytes(&bytes)?;
+        assert!(local_receipt.matches_device(DEVICE_ID));
+        assert!(!local_receipt.matches_device(b"other"));
+        assert_eq!(
+            local_receipt.receipt.receipt_type,
+            ReceiptType::ProductionSandbox
+        );
+        assert_eq!(
+            local_receipt.receipt.receipt_creation_date,
+            receipt.receipt_creation_date
+        );
+
+        // The order is lost, SET OF is sorted by encoding.
+        let mut in_app = local_receipt.receipt.in_app.ok_or("in_app missing")?;
+        in_app.sort_by(|a, b| {
+            a.transaction
+                .transaction_id
+                .cmp(&b.transaction.transaction_id)
+        });
+        let expected = receipt.in_app.ok_or("in_app missing")?;
+        assert_eq!(in_app.len(), 3);
+        for (a, b) in in_app.iter().zip(&expected) {
+            let (a, b) = (&a.transaction, &b.transaction);
+            assert_eq!(a.transaction_id, b.transaction_id);
+            assert_eq!(a.original_transaction_id, b.original_transaction_id);
+            assert_eq!(a.product_id, b.product_id);
+            assert_eq!(a.purchase_date, b.purchase_date);
+            assert_eq!(a.expires_date, b.expires_date);
+            assert_eq!(a.web_order_line_item_id, b.web_order_line_item_id);
+            assert_eq!(a.is_trial_period, b.is_trial_period);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-local-verification")]
+    #[test]
+    fn test_build_signed() -> Result<(), Box<dyn error::Error>> {
+        use rsa::{pkcs8::DecodePrivateKey as _, RsaPrivateKey};
+
+        use crate::local_receipt::{LocalReceiptVerifier, LocalReceiptVerifyError};
+
+        let receipt = receipt()?;
+        let signer_key = RsaPrivateKey::from_pkcs8_der(include_bytes!(
+            "../../tests/fixture_files/signer_key.der"
+        ))?;
+        let signer = include_bytes!("../../tests/fixture_files/signer.der");
+        let intermediate = include_bytes!("../../tests/fixture_files/intermediate_ca.der");
+
+        let bytes = LocalReceiptBuilder::new(DEVICE_ID).build_signed(
+            &receipt,
+            &signer_key,
+            signer,
+            &[signer, intermediate],
+        )?;
+
+        let verifier =
+            LocalReceiptVerifier::new(include_bytes!("../../tests/fixture_files/root_ca.der"))?;
+        let local_receipt = verifier.verify_pkcs7_bytes(&bytes)?;
+        assert!(local_receipt.matches_device(DEVICE_ID));
+
+        let verifier = LocalReceiptVerifier::new(include_bytes!(
+            "../../tests/local_receipt_files/root_ca.der"
+        ))?;
+        assert_eq!(
+            verifier.verify_pkcs7_bytes(&bytes).err(),
+            Some(LocalReceiptVerifyError::NotIssuedByRootCertificate)
+        );
+
+        assert!(matches!(
+            LocalReceiptBuilder::new(DEVICE_ID).build_signed(&receipt, &signer_key, b"foo", &[]),
+            Err(LocalReceiptBuilderError::CertificateInvalid(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+// Synthetic verifyReceipt responses and receipts (NOT from Apple), to drive tests of downstream code.
+
+pub mod local_receipt;
+pub mod response_body;
+
+pub use local_receipt::{LocalReceiptBuilder, LocalReceiptBuilderError};
+pub use response_body::{PurchaseFixture, ResponseBodyBuilder, SubscriptionFixture};
@@ -0,0 +1,685 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{Map, Value};
+
+use crate::{
+    local_receipt::decode::to_pst_string,
+    objects::response_body::{
+        Environment, ExpirationIntent, InAppOwnershipType, LatestReceiptInfo, PendingRenewalInfo,
+        Receipt, ReceiptInApp, ReceiptType, ResponseBodyWithSuccess, Transaction,
+    },
+    types::status::Status,
+};
+
+pub const TRANSACTION_ID_START: u64 = 1000000000000001;
+pub const WEB_ORDER_LINE_ITEM_ID_START: u64 = 2000000000000001;
+
+/// A consumable or non-consumable purchase.
+#[derive(Debug, Clone)]
+pub struct PurchaseFixture {
+    pub product_id: String,
+    /// Consumables are only in `receipt.in_app`.
+    pub is_consumable: bool,
+    pub quantity: usize,
+    pub purchase_date: DateTime<Utc>,
+    pub cancellation_date: Option<DateTime<Utc>>,
+    pub in_app_ownership_type: InAppOwnershipType,
+}
+
+impl PurchaseFixture {
+    pub fn new(product_id: impl AsRef<str>, purchase_date: DateTime<Utc>) -> Self {
+        Self {
+            product_id: product_id.as_ref().to_owned(),
+            is_consumable: false,
+            quantity: 1,
+            purchase_date,
+            cancellation_date: None,
+            in_app_ownership_type: InAppOwnershipType::Purchased,
+        }
+    }
+
+    pub fn set_consumable(&mut self, is_consumable: bool) -> &mut Self {
+        self.is_consumable = is_consumable;
+        self
+    }
+
+    pub fn set_quantity(&mut self, quantity: usize) -> &mut Self {
+        self.quantity = quantity;
+        self
+    }
+
+    /// Refunded at.
+    pub fn set_cancellation_date(&mut self, cancellation_date: DateTime<Utc>) -> &mut Self {
+        self.cancellation_date = Some(cancellation_date);
+        self
+    }
+
+    pub fn set_in_app_ownership_type(
+        &mut self,
+        in_app_ownership_type: InAppOwnershipType,
+    ) -> &mut Self {
+        self.in_app_ownership_type = in_app_ownership_type;
+        self
+    }
+}
+
+/// An auto-renewable subscription, one transaction per period.
+#[derive(Debug, Clone)]
+pub struct SubscriptionFixture {
+    pub product_id: String,
+    pub subscription_group_identifier: String,
+    pub start_date: DateTime<Utc>,
+    pub period: Duration,
+    pub renewal_count: usize,
+    pub is_free_trial: bool,
+    pub cancellation_date: Option<DateTime<Utc>>,
+    pub auto_renew_status: bool,
+    pub expiration_intent: Option<ExpirationIntent>,
+    pub is_in_billing_retry_period: bool,
+    pub grace_period_expires_date: Option<DateTime<Utc>>,
+    pub in_app_ownership_type: InAppOwnershipType,
+}
+
+impl SubscriptionFixture {
+    pub fn new(
+        product_id: impl AsRef<str>,
+        subscription_group_identifier: impl AsRef<str>,
+        start_date: DateTime<Utc>,
+        period: Duration,
+    ) -> Self {
+        Self {
+            product_id: product_id.as_ref().to_owned(),
+            subscription_group_identifier: subscription_group_identifier.as_ref().to_owned(),
+            start_date,
+            period,
+            renewal_count: 0,
+            is_free_trial: false,
+            cancellation_date: None,
+            auto_renew_status: true,
+            expiration_intent: None,
+            is_in_billing_retry_period: false,
+            grace_period_expires_date: None,
+            in_app_ownership_type: InAppOwnershipType::Purchased,
+        }
+    }
+
+    /// Transactions after the first one.
+    pub fn set_renewal_count(&mut self, renewal_count: usize) -> &mut Self {
+        self.renewal_count = renewal_count;
+        self
+    }
+
+    /// The first period is a free trial.
+    pub fn set_free_trial(&mut self, is_free_trial: bool) -> &mut Self {
+        self.is_free_trial = is_free_trial;
+        self
+    }
+
+    /// The latest transaction refunded at.
+    pub fn set_cancellation_date(&mut self, cancellation_date: DateTime<Utc>) -> &mut Self {
+        self.cancellation_date = Some(cancellation_date);
+        self
+    }
+
+    /// Turned off by the customer, `expiration_intent` set after the expiration.
+    pub fn set_auto_renew_status(
+        &mut self,
+        auto_renew_status: bool,
+        expiration_intent: Option<ExpirationIntent>,
+    ) -> &mut Self {
+        self.auto_renew_status = auto_renew_status;
+        self.expiration_intent = expiration_intent;
+        self
+    }
+
+    /// The renewal failed, with an optional billing grace period.
+    pub fn set_billing_retry(
+        &mut self,
+        grace_period_expires_date: Option<DateTime<Utc>>,
+    ) -> &mut Self {
+        self.is_in_billing_retry_period = true;
+        self.expiration_intent = Some(ExpirationIntent::BillingError);
+        self.grace_period_expires_date = grace_period_expires_date;
+        self
+    }
+
+    pub fn set_in_app_ownership_type(
+        &mut self,
+        in_app_ownership_type: InAppOwnershipType,
+    ) -> &mut Self {
+        self.in_app_ownership_type = in_app_ownership_type;
+        self
+    }
+
+    pub fn expires_date(&self) -> DateTime<Utc> {
+        self.start_date + self.period * (self.renewal_count as i32 + 1)
+    }
+}
+
+/// Builds a `verifyReceipt` success response, transaction ids are assigned by purchase date.
+#[derive(Debug, Clone)]
+pub struct ResponseBodyBuilder {
+    bundle_id: String,
+    environment: Environment,
+    app_item_id: usize,
+    application_version: String,
+    original_application_version: String,
+    original_purchase_date: Option<DateTime<Utc>>,
+    purchases: Vec<PurchaseFixture>,
+    subscriptions: Vec<SubscriptionFixture>,
+}
+
+impl ResponseBodyBuilder {
+    pub fn new(bundle_id: impl AsRef<str>, environment: Environment) -> Self {
+        Self {
+            bundle_id: bundle_id.as_ref().to_owned(),
+            environment,
+            app_item_id: 0,
+            application_version: "1".to_owned(),
+            original_application_version: "1.0".to_owned(),
+            original_purchase_date: None,
+            purchases: vec![],
+            subscriptions: vec![],
+        }
+    }
+
+    /// 0 in sandbox.
+    pub fn set_app_item_id(&mut self, app_item_id: usize) -> &mut Self {
+        self.app_item_id = app_item_id;
+        self
+    }
+
+    pub fn set_application_version(&mut self, application_version: impl AsRef<str>) -> &mut Self {
+        self.application_version = application_version.as_ref().to_owned();
+        self
+    }
+
+    pub fn set_original_application_version(
+        &mut self,
+        original_application_version: impl AsRef<str>,
+    ) -> &mut Self {
+        self.original_application_version = original_application_version.as_ref().to_owned();
+        self
+    }
+
+    /// When the app was first downloaded, defaults to the first purchase.
+    pub fn set_original_purchase_date(
+        &mut self,
+        original_purchase_date: DateTime<Utc>,
+    ) -> &mut Self {
+        self.original_purchase_date = Some(original_purchase_date);
+        self
+    }
+
+    pub fn add_purchase(&mut self, purchase: PurchaseFixture) -> &mut Self {
+        self.purchases.push(purchase);
+        self
+    }
+
+    pub fn add_subscription(&mut self, subscription: SubscriptionFixture) -> &mut Self {
+        self.subscriptions.push(subscription);
+        self
+    }
+
+    /// Transactions purchased after `request_date` are left out.
+    pub fn build(&self, request_date: DateTime<Utc>) -> ResponseBodyWithSuccess {
+        let mut slots = vec![];
+        for purchase in &self.purchases {
+            slots.push(Slot::Purchase(purchase));
+        }
+        for (i, subscription) in self.subscriptions.iter().enumerate() {
+            for index in 0..=subscription.renewal_count {
+                slots.push(Slot::Subscription(i, subscription, index));
+            }
+        }
+        // Stable, so the insertion order breaks the ties.
+        slots.sort_by_key(|x| x.purchase_date());
+        slots.retain(|x| x.purchase_date() <= request_date);
+
+        // The original transaction is the first of the chain, i.e. the first assigned.
+        let mut original_transaction_ids: Vec<Option<String>> =
+            vec![None; self.subscriptions.len()];
+        let mut web_order_line_item_id = WEB_ORDER_LINE_ITEM_ID_START;
+
+        let mut in_app = vec![];
+        let mut latest_receipt_info = vec![];
+        for (i, slot) in slots.iter().enumerate() {
+            let transaction_id = (TRANSACTION_ID_START + i as u64).to_string();
+
+            match slot {
+                Slot::Purchase(purchase) => {
+                    in_app.push(ReceiptInApp {
+                        transaction: purchase_transaction(purchase, transaction_id.to_owned()),
+                        subscription_group_identifier: None,
+                    });
+                    if !purchase.is_consumable {
+                        latest_receipt_info.push(LatestReceiptInfo {
+                            transaction: purchase_transaction(purchase, transaction_id),
+                            subscription_group_identifier: None,
+                            is_upgraded: None,
+                        });
+                    }
+                }
+                Slot::Subscription(subscription_index, subscription, index) => {
+                    let original_transaction_id = original_transaction_ids[*subscription_index]
+                        .get_or_insert_with(|| transaction_id.to_owned())
+                        .to_owned();
+
+                    let transaction = || {
+                        subscription_transaction(
+                            subscription,
+                            *index,
+                            transaction_id.to_owned(),
+                            original_transaction_id.to_owned(),
+                            web_order_line_item_id.to_string(),
+                        )
+                    };
+                    in_app.push(ReceiptInApp {
+                        transaction: transaction(),
+                        subscription_group_identifier: None,
+                    });
+                    latest_receipt_info.push(LatestReceiptInfo {
+                        transaction: transaction(),
+                        subscription_group_identifier: Some(
+                            subscription.subscription_group_identifier.to_owned(),
+                        ),
+                        is_upgraded: None,
+                    });
+                    web_order_line_item_id += 1;
+                }
+            }
+        }
+        // Latest first
+        latest_receipt_info.reverse();
+
+        let pending_renewal_info = self
+            .subscriptions
+            .iter()
+            .zip(&original_transaction_ids)
+            .filter_map(|(subscription, original_transaction_id)| {
+                original_transaction_id
+                    .as_ref()
+                    .map(|x| pending_renewal_info(subscription, x))
+            })
+            .collect::<Vec<_>>();
+
+        let original_purchase_date = self
+            .original_purchase_date
+            .or_else(|| slots.first().map(|x| x.purchase_date()))
+            .unwrap_or(request_date);
+
+        let receipt = Receipt {
+            receipt_type: match self.environment {
+                Environment::Production => ReceiptType::Production,
+                Environment::Sandbox => ReceiptType::ProductionSandbox,
+            },
+            adam_id: self.app_item_id,
+            app_item_id: self.app_item_id,
+            bundle_id: self.bundle_id.to_owned(),
+            application_version: self.application_version.to_owned(),
+            download_id: Some(0),
+            version_external_identifier: 0,
+            receipt_creation_date: request_date,
+            receipt_creation_date_pst: to_pst_string(&request_date),
+            request_date,
+            request_date_pst: to_pst_string(&request_date),
+            original_purchase_date,
+            original_purchase_date_pst: to_pst_string(&original_purchase_date),
+            original_application_version: Some(self.original_application_version.to_owned()),
+            in_app: Some(in_app),
+            expiration_date: None,
+            expiration_date_pst: None,
+            preorder_date: None,
+            preorder_date_pst: None,
+            extra: gmt_strings([
+                ("receipt_creation_date", Some(&request_date)),
+                ("request_date", Some(&request_date)),
+                ("original_purchase_date", Some(&original_purchase_date)),
+            ]),
+        };
+
+        // verifyReceipt only returns them for receipts with auto-renewable subscriptions.
+        let has_subscriptions = !pending_renewal_info.is_empty();
+        ResponseBodyWithSuccess {
+            status: Status::Success,
+            environment: self.environment.to_owned(),
+            receipt,
+            latest_receipt: None,
+            latest_receipt_info: Some(latest_receipt_info).filter(|_| has_subscriptions),
+            pending_renewal_info: Some(pending_renewal_info).filter(|_| has_subscriptions),
+            extra: Default::default(),
+        }
+    }
+}
+
+//
+//
+//
+enum Slot<'a> {
+    Purchase(&'a PurchaseFixture),
+    // (index in subscriptions, subscription, index in the chain)
+    Subscription(usize, &'a SubscriptionFixture, usize),
+}
+
+impl Slot<'_> {
+    fn purchase_date(&self) -> DateTime<Utc> {
+        match self {
+            Self::Purchase(purchase) => purchase.purchase_date,
+            Self::Subscription(_, subscription, index) => {
+                subscription.start_date + subscription.period * *index as i32
+            }
+        }
+    }
+}
+
+fn purchase_transaction(purchase: &PurchaseFixture, transaction_id: String) -> Transaction {
+    let mut transaction = transaction(
+        &purchase.product_id,
+        transaction_id.to_owned(),
+        transaction_id,
+        purchase.purchase_date,
+        purchase.purchase_date,
+        None,
+        purchase.cancellation_date,
+        &purchase.in_app_ownership_type,
+    );
+    transaction.quantity = purchase.quantity;
+    transaction
+}
+
+fn subscription_transaction(
+    subscription: &SubscriptionFixture,
+    index: usize,
+    transaction_id: String,
+    original_transaction_id: String,
+    web_order_line_item_id: String,
+) -> Transaction {
+    let purchase_date = subscription.start_date + subscription.period * index as i32;
+    let is_latest = index == subscription.renewal_count;
+
+    let mut transaction = transaction(
+        &subscription.product_id,
+        transaction_id,
+        original_transaction_id,
+        purchase_date,
+        subscription.start_date,
+        Some(purchase_date + subscription.period),
+        subscription.cancellation_date.filter(|_| is_latest),
+        &subscription.in_app_ownership_type,
+    );
+    transaction.web_order_line_item_id = Some(web_order_line_item_id);
+    transaction.is_trial_period = Some(subscription.is_free_trial && index == 0);
+    transaction.is_in_intro_offer_period = Some(false);
+    transaction
+}
+
+#[allow(clippy::too_many_arguments)]
+fn transaction(
+    product_id: &str,
+    transaction_id: String,
+    original_transaction_id: String,
+    purchase_date: DateTime<Utc>,
+    original_purchase_date: DateTime<Utc>,
+    expires_date: Option<DateTime<Utc>>,
+    cancellation_date: Option<DateTime<Utc>>,
+    in_app_ownership_type: &InAppOwnershipType,
+) -> Transaction {
+    Transaction {
+        quantity: 1,
+        product_id: product_id.to_owned(),
+        transaction_id,
+        original_transaction_id,
+        purchase_date,
+        purchase_date_pst: to_pst_string(&purchase_date),
+        original_purchase_date,
+        original_purchase_date_pst: to_pst_string(&original_purchase_date),
+        expires_date,
+        expires_date_pst: expires_date.as_ref().map(to_pst_string),
+        cancellation_date,
+        cancellation_date_pst: cancellation_date.as_ref().map(to_pst_string),
+        cancellation_reason: cancellation_date.map(|_| "0".to_owned()),
+        web_order_line_item_id: None,
+        is_trial_period: Some(false),
+        promotional_offer_id: None,
+        is_in_intro_offer_period: None,
+        in_app_ownership_type: Some(in_app_ownership_type.to_owned()),
+        offer_code_ref_name: None,
+        app_account_token: None,
+        extra: gmt_strings([
+            ("purchase_date", Some(&purchase_date)),
+            ("original_purchase_date", Some(&original_purchase_date)),
+            ("expires_date", expires_date.as_ref()),
+            ("cancellation_date", cancellation_date.as_ref()),
+        ]),
+    }
+}
+
+fn pending_renewal_info(
+    subscription: &SubscriptionFixture,
+    original_transaction_id: &str,
+) -> PendingRenewalInfo {
+    PendingRenewalInfo {
+        product_id: subscription.product_id.to_owned(),
+        original_transaction_id: original_transaction_id.to_owned(),
+        auto_renew_product_id: Some(subscription.product_id.to_owned()),
+        auto_renew_status: Some(subscription.auto_renew_status),
+        expiration_intent: subscription.expiration_intent.to_owned(),
+        grace_period_expires_date: subscription.grace_period_expires_date,
+        grace_period_expires_date_pst: subscription
+            .grace_period_expires_date
+            .as_ref()
+            .map(to_pst_string),
+        is_in_billing_retry_period: Some(subscription.is_in_billing_retry_period)
+            .filter(|_| subscription.expiration_intent.is_some()),
+        offer_code_ref_name: None,
+        price_consent_status: None,
+        promotional_offer_id: None,
+        extra: gmt_strings([(
+            "grace_period_expires_date",
+            subscription.grace_period_expires_date.as_ref(),
+        )]),
+    }
+}
+
+// e.g. `"purchase_date": "2013-08-01 07:00:00 Etc/GMT"`, which verifyReceipt returns along with the _ms fields.
+fn gmt_strings<'a>(
+    fields: impl IntoIterator<Item = (&'a str, Option<&'a DateTime<Utc>>)>,
+) -> Map<String, Value> {
+    fields
+        .into_iter()
+        .filter_map(|(name, dt)| {
+            dt.map(|dt| {
+                (
+                    name.to_owned(),
+                    Value::String(dt.format("%Y-%m-%d %H:%M:%S Etc/GMT").to_string()),
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::{
+        entitlement::{
+            EntitlementState, Entitlements, InMemoryPurchaseLedgerStore, PurchaseEventKind,
+            PurchaseLedger,
+        },
+        objects::response_body::ResponseBody,
+    };
+
+    fn date(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s)
+            .expect("invalid date")
+            .with_timezone(&Utc)
+    }
+
+    // The builders of tests/fixture_files/response_body_*.json
+    fn sandbox_builder() -> ResponseBodyBuilder {
+        let mut builder = ResponseBodyBuilder::new("com.example.app", Environment::Sandbox);
+        builder.set_application_version("1.0.1");
+
+        let mut coins =
+            PurchaseFixture::new("com.example.app.coins100", date("2025-01-10T10:00:00Z"));
+        coins.set_consumable(true).set_quantity(2);
+        let mut coins_refunded =
+            PurchaseFixture::new("com.example.app.coins100", date("2025-01-11T10:00:00Z"));
+        coins_refunded
+            .set_consumable(true)
+            .set_cancellation_date(date("2025-01-12T10:00:00Z"));
+        let mut pro = PurchaseFixture::new("com.example.app.pro", date("2024-06-01T00:00:00Z"));
+        pro.set_in_app_ownership_type(InAppOwnershipType::FamilyShared);
+
+        let mut monthly = SubscriptionFixture::new(
+            "com.example.app.monthly",
+            "20000001",
+            date("2024-11-01T00:00:00Z"),
+            Duration::days(30),
+        );
+        monthly.set_free_trial(true).set_renewal_count(2);
+        let mut yearly = SubscriptionFixture::new(
+            "com.example.app.yearly",
+            "20000002",
+            date("2024-07-01T00:00:00Z"),
+            Duration::days(365),
+        );
+        yearly
+            .set_cancellation_date(date("2024-08-01T00:00:00Z"))
+            .set_auto_renew_status(false, Some(ExpirationIntent::CustomerCanceled));
+        let mut quarterly = SubscriptionFixture::new(
+            "com.example.app.quarterly",
+            "20000003",
+            date("2024-10-01T00:00:00Z"),
+            Duration::days(90),
+        );
+        quarterly.set_billing_retry(Some(date("2025-01-20T00:00:00Z")));
+
+        builder
+            .add_purchase(coins)
+            .add_purchase(coins_refunded)
+            .add_purchase(pro)
+            .add_subscription(monthly)
+            .add_subscription(yearly)
+            .add_subscription(quarterly);
+        builder
+    }
+
+    fn production_builder() -> ResponseBodyBuilder {
+        let mut builder = ResponseBodyBuilder::new("com.example.app", Environment::Production);
+        builder
+            .set_app_item_id(1234567890)
+            .set_original_purchase_date(date("2023-01-01T00:00:00Z"))
+            .add_purchase(PurchaseFixture::new(
+                "com.example.app.pro",
+                date("2024-06-01T00:00:00Z"),
+            ));
+        builder
+    }
+
+    #[test]
+    fn test_build() -> Result<(), Box<dyn error::Error>> {
+        let request_date = date("2025-01-15T00:00:00Z");
+        let body = sandbox_builder().build(request_date);
+
+        assert_eq!(body.receipt.receipt_type, ReceiptType::ProductionSandbox);
+        let in_app = body.receipt.in_app.as_ref().ok_or("in_app missing")?;
+        assert_eq!(
+            in_app
+                .iter()
+                .map(|x| (
+                    x.transaction.transaction_id.as_str(),
+                    x.transaction.product_id.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("1000000000000001", "com.example.app.pro"),
+                ("1000000000000002", "com.example.app.yearly"),
+                ("1000000000000003", "com.example.app.quarterly"),
+                ("1000000000000004", "com.example.app.monthly"),
+                ("1000000000000005", "com.example.app.monthly"),
+                ("1000000000000006", "com.example.app.monthly"),
+                ("1000000000000007", "com.example.app.coins100"),
+                ("1000000000000008", "com.example.app.coins100"),
+            ]
+        );
+        let latest_receipt_info = body
+            .latest_receipt_info
+            .as_ref()
+            .ok_or("latest_receipt_info missing")?;
+        assert_eq!(latest_receipt_info.len(), 6);
+        assert_eq!(
+            latest_receipt_info[0].transaction.original_transaction_id,
+            "1000000000000004"
+        );
+        assert_eq!(body.pending_renewal_info.as_ref().map(|x| x.len()), Some(3));
+
+        // Drives the entitlement code
+        let entitlements = Entitlements::from_response_body(&body, &request_date);
+        assert_eq!(
+            entitlements
+                .products
+                .iter()
+                .map(|x| (x.product_id.as_str(), x.state))
+                .collect::<Vec<_>>(),
+            vec![
+                ("com.example.app.monthly", EntitlementState::Active),
+                ("com.example.app.pro", EntitlementState::Active),
+                ("com.example.app.quarterly", EntitlementState::Active),
+                ("com.example.app.yearly", EntitlementState::Refunded),
+            ]
+        );
+        assert!(
+            entitlements
+                .get("com.example.app.quarterly")
+                .ok_or("quarterly missing")?
+                .is_in_grace_period
+        );
+
+        let events = PurchaseLedger::new()
+            .apply_response_body_with_success(&body, &mut InMemoryPurchaseLedgerStore::new())?;
+        assert_eq!(
+            events
+                .iter()
+                .filter(|x| x.kind == PurchaseEventKind::NewlyGranted)
+                .count(),
+            6
+        );
+
+        // Before the renewals
+        let body = sandbox_builder().build(date("2024-11-15T00:00:00Z"));
+        assert_eq!(body.receipt.in_app.map(|x| x.len()), Some(4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_files() -> Result<(), Box<dyn error::Error>> {
+        for (builder, request_date, content) in [
+            (
+                sandbox_builder(),
+                "2025-01-15T00:00:00Z",
+                include_str!("../../tests/fixture_files/response_body_sandbox.json"),
+            ),
+            (
+                production_builder(),
+                "2025-01-15T00:00:00Z",
+                include_str!("../../tests/fixture_files/response_body_production.json"),
+            ),
+        ] {
+            let body = builder.build(date(request_date));
+            assert_eq!(
+                serde_json::to_value(&body)?,
+                serde_json::from_str::<Value>(content)?
+            );
+            assert!(matches!(
+                serde_json::from_str(content)?,
+                ResponseBody::Success(_)
+            ));
+        }
+
+        Ok(())
+    }
+}
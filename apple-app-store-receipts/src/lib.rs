@@ -1,5 +1,14 @@
+pub mod checks;
 pub mod endpoints;
+pub mod entitlement;
+#[cfg(any(test, feature = "with-fixture"))]
+pub mod fixture;
+pub mod local_receipt;
 pub mod objects;
+#[cfg(feature = "with-server-api")]
+pub mod server_api;
 pub mod types;
+#[cfg(feature = "with-verification-service")]
+pub mod verification_service;
 
 pub use endpoints::verify_receipt::{ReceiptData, VerifyReceipt};
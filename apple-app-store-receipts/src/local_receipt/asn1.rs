@@ -0,0 +1,289 @@
+// Minimal BER reader, enough for PKCS#7 containers and the receipt payload.
+// Apple receipts may use indefinite length encoding, which strict DER decoders reject.
+
+use std::borrow::Cow;
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OCTET_STRING_CONSTRUCTED: u8 = 0x24;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_UTF8_STRING: u8 = 0x0C;
+pub(crate) const TAG_IA5_STRING: u8 = 0x16;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+pub(crate) const TAG_CONTEXT_0: u8 = 0xA0;
+pub(crate) const TAG_CONTEXT_1: u8 = 0xA1;
+
+// Receipts nest a handful of levels, the input comes from clients so recursion must be bounded.
+pub(crate) const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Asn1Error(pub(crate) &'static str);
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Element<'a> {
+    pub(crate) tag: u8,
+    pub(crate) content: &'a [u8],
+    /// Header and content, without the end-of-contents octets.
+    pub(crate) raw: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Element<'a> {
+    pub(crate) fn is_constructed(&self) -> bool {
+        self.tag & 0x20 != 0
+    }
+
+    pub(crate) fn children(&self) -> Reader<'a> {
+        Reader {
+            data: self.content,
+            depth: self.depth + 1,
+        }
+    }
+
+    pub(crate) fn expect(self, tag: u8) -> Result<Self, Asn1Error> {
+        if self.tag == tag {
+            Ok(self)
+        } else {
+            Err(Asn1Error("unexpected tag"))
+        }
+    }
+
+    /// Primitive and (BER) constructed OCTET STRING.
+    pub(crate) fn octet_string(&self) -> Result<Cow<'a, [u8]>, Asn1Error> {
+        match self.tag {
+            TAG_OCTET_STRING => Ok(Cow::Borrowed(self.content)),
+            TAG_OCTET_STRING_CONSTRUCTED => {
+                let mut buf = vec![];
+                let mut reader = self.children();
+                while let Some(child) = reader.next()? {
+                    buf.extend_from_slice(&child.octet_string()?);
+                }
+                Ok(Cow::Owned(buf))
+            }
+            _ => Err(Asn1Error("not an octet string")),
+        }
+    }
+
+    pub(crate) fn integer(&self) -> Result<i64, Asn1Error> {
+        if self.tag != TAG_INTEGER {
+            return Err(Asn1Error("not an integer"));
+        }
+        if self.content.is_empty() || self.content.len() > 8 {
+            return Err(Asn1Error("integer out of range"));
+        }
+        let mut v: i64 = if self.content[0] & 0x80 != 0 { -1 } else { 0 };
+        for b in self.content {
+            v = (v << 8) | *b as i64;
+        }
+        Ok(v)
+    }
+
+    pub(crate) fn string(&self) -> Result<&'a str, Asn1Error> {
+        match self.tag {
+            TAG_UTF8_STRING | TAG_IA5_STRING => {
+                core::str::from_utf8(self.content).map_err(|_| Asn1Error("invalid string"))
+            }
+            _ => Err(Asn1Error("not a string")),
+        }
+    }
+}
+
+//
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, depth: 0 }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub(crate) fn next(&mut self) -> Result<Option<Element<'a>>, Asn1Error> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let (element, len) = read_element(self.data, self.depth)?;
+        self.data = &self.data[len..];
+        Ok(Some(element))
+    }
+
+    pub(crate) fn next_required(&mut self) -> Result<Element<'a>, Asn1Error> {
+        self.next()?.ok_or(Asn1Error("unexpected end"))
+    }
+}
+
+/// Returns the element and the number of bytes consumed, including end-of-contents octets.
+fn read_element(data: &[u8], depth: usize) -> Result<(Element<'_>, usize), Asn1Error> {
+    if depth > MAX_DEPTH {
+        return Err(Asn1Error("nesting too deep"));
+    }
+    let tag = *data.first().ok_or(Asn1Error("unexpected end"))?;
+    if tag == 0 {
+        // End-of-contents octets only terminate indefinite length content, handled below.
+        return Err(Asn1Error("unexpected end-of-contents"));
+    }
+    if tag & 0x1F == 0x1F {
+        return Err(Asn1Error("high tag number form is not supported"));
+    }
+    let first = *data.get(1).ok_or(Asn1Error("unexpected end"))?;
+
+    if first == 0x80 {
+        if tag & 0x20 == 0 {
+            return Err(Asn1Error("indefinite length on primitive"));
+        }
+        let mut offset = 2;
+        loop {
+            let rest = data.get(offset..).ok_or(Asn1Error("unexpected end"))?;
+            if rest.starts_with(&[0, 0]) {
+                let element = Element {
+                    tag,
+                    content: &data[2..offset],
+                    raw: &data[..offset],
+                    depth,
+                };
+                return Ok((element, offset + 2));
+            }
+            let (_, len) = read_element(rest, depth + 1)?;
+            offset += len;
+        }
+    }
+
+    let (len, header_len) = if first & 0x80 == 0 {
+        (first as usize, 2)
+    } else {
+        let n = (first & 0x7F) as usize;
+        if n == 0 || n > 4 {
+            return Err(Asn1Error("invalid length"));
+        }
+        let bytes = data.get(2..2 + n).ok_or(Asn1Error("unexpected end"))?;
+        let len = bytes
+            .iter()
+            .fold(0_usize, |acc, b| (acc << 8) | *b as usize);
+        (len, 2 + n)
+    };
+
+    let end = header_len
+        .checked_add(len)
+        .filter(|end| *end <= data.len())
+        .ok_or(Asn1Error("length out of range"))?;
+
+    let element = Element {
+        tag,
+        content: &data[header_len..end],
+        raw: &data[..end],
+        depth,
+    };
+    Ok((element, end))
+}
+
+pub(crate) fn decode_oid(content: &[u8]) -> Result<String, Asn1Error> {
+    let mut arcs: Vec<u64> = vec![];
+    let mut v: u64 = 0;
+    for b in content {
+        v = (v << 7) | (*b & 0x7F) as u64;
+        if b & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = if v < 80 { v / 40 } else { 2 };
+                arcs.push(first);
+                arcs.push(v - first * 40);
+            } else {
+                arcs.push(v);
+            }
+            v = 0;
+        }
+    }
+    if arcs.is_empty() {
+        return Err(Asn1Error("invalid oid"));
+    }
+    Ok(arcs
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definite_and_indefinite_length() -> Result<(), Asn1Error> {
+        // SEQUENCE { INTEGER 1, OCTET STRING "ab" }
+        let der = [0x30, 0x07, 0x02, 0x01, 0x01, 0x04, 0x02, b'a', b'b'];
+        // SEQUENCE (indefinite) { INTEGER 1, OCTET STRING (constructed, indefinite) { "a", "b" } }
+        let ber = [
+            0x30, 0x80, 0x02, 0x01, 0x01, 0x24, 0x80, 0x04, 0x01, b'a', 0x04, 0x01, b'b', 0x00,
+            0x00, 0x00, 0x00,
+        ];
+
+        for data in [&der[..], &ber[..]] {
+            let mut reader = Reader::new(data);
+            let seq = reader.next_required()?.expect(TAG_SEQUENCE)?;
+            assert!(reader.next()?.is_none());
+
+            let mut children = seq.children();
+            assert_eq!(children.next_required()?.integer()?, 1);
+            assert_eq!(children.next_required()?.octet_string()?.as_ref(), b"ab");
+            assert!(children.next()?.is_none());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_integer_and_oid() -> Result<(), Asn1Error> {
+        let (element, _) = read_element(&[0x02, 0x02, 0x00, 0xFF], 0)?;
+        assert_eq!(element.integer()?, 255);
+        let (element, _) = read_element(&[0x02, 0x01, 0xFF], 0)?;
+        assert_eq!(element.integer()?, -1);
+
+        assert_eq!(
+            decode_oid(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02])?,
+            "1.2.840.113549.1.7.2"
+        );
+
+        assert!(read_element(&[0x30, 0x05, 0x02], 0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deeply_nested() {
+        // SEQUENCE (indefinite) { SEQUENCE (indefinite) { ... } }, without end-of-contents octets
+        let data = [0x30, 0x80].repeat(1_000_000);
+        assert_eq!(
+            Reader::new(&data).next().err(),
+            Some(Asn1Error("nesting too deep"))
+        );
+
+        // OCTET STRING (constructed, definite) { OCTET STRING (constructed, definite) { ... } }
+        let mut data = vec![0x04, 0x00];
+        for _ in 0..(MAX_DEPTH + 1) {
+            let mut outer = vec![0x24, data.len() as u8];
+            outer.extend_from_slice(&data);
+            data = outer;
+        }
+        let element = Reader::new(&data).next_required().unwrap();
+        assert_eq!(
+            element.octet_string().err(),
+            Some(Asn1Error("nesting too deep"))
+        );
+    }
+
+    #[test]
+    fn test_end_of_contents_in_definite_length() {
+        // SEQUENCE { INTEGER 1, end-of-contents, INTEGER 2 }
+        let data = [0x30, 0x08, 0x02, 0x01, 0x01, 0x00, 0x00, 0x02, 0x01, 0x02];
+        let seq = Reader::new(&data).next_required().unwrap();
+        let mut children = seq.children();
+        assert_eq!(children.next_required().unwrap().integer(), Ok(1));
+        assert_eq!(
+            children.next().err(),
+            Some(Asn1Error("unexpected end-of-contents"))
+        );
+    }
+}
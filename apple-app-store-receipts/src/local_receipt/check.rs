@@ -0,0 +1,135 @@
+// ref https://developer.apple.com/library/archive/releasenotes/General/ValidateAppStoreReceipt/Chapters/ValidateLocally.html
+
+use sha1::{Digest as _, Sha1};
+use subtle::ConstantTimeEq as _;
+
+use super::decode::LocalReceipt;
+
+//
+#[derive(Debug, Clone)]
+pub struct LocalReceiptExpectation {
+    pub bundle_id: String,
+    /// CFBundleVersion on iOS, CFBundleShortVersionString on macOS.
+    pub application_version: Option<String>,
+    /// identifierForVendor bytes on iOS, the MAC address of the primary network interface on macOS.
+    pub device_identifier: Option<Vec<u8>>,
+}
+
+impl LocalReceiptExpectation {
+    pub fn new(bundle_id: impl AsRef<str>) -> Self {
+        Self {
+            bundle_id: bundle_id.as_ref().to_owned(),
+            application_version: None,
+            device_identifier: None,
+        }
+    }
+
+    pub fn set_application_version(&mut self, application_version: impl AsRef<str>) -> &mut Self {
+        self.application_version = Some(application_version.as_ref().to_owned());
+        self
+    }
+
+    pub fn set_device_identifier(&mut self, device_identifier: impl AsRef<[u8]>) -> &mut Self {
+        self.device_identifier = Some(device_identifier.as_ref().to_vec());
+        self
+    }
+}
+
+impl LocalReceipt {
+    /// SHA-1(device identifier + opaque value + bundle id) equals the receipt hash, compared in constant time.
+    pub fn matches_device(&self, device_identifier: &[u8]) -> bool {
+        let mut hasher = Sha1::new();
+        hasher.update(device_identifier);
+        hasher.update(&self.opaque_value);
+        hasher.update(&self.bundle_id_data);
+
+        hasher
+            .finalize()
+            .as_slice()
+            .ct_eq(self.sha1_hash.as_slice())
+            .into()
+    }
+
+    pub fn matches_bundle_id(&self, bundle_id: &str) -> bool {
+        self.receipt.bundle_id == bundle_id
+    }
+
+    pub fn matches_application_version(&self, application_version: &str) -> bool {
+        self.receipt.application_version == application_version
+    }
+
+    pub fn check(
+        &self,
+        expectation: &LocalReceiptExpectation,
+    ) -> Result<(), LocalReceiptCheckError> {
+        if !self.matches_bundle_id(&expectation.bundle_id) {
+            return Err(LocalReceiptCheckError::BundleIdMismatch);
+        }
+        if let Some(application_version) = &expectation.application_version {
+            if !self.matches_application_version(application_version) {
+                return Err(LocalReceiptCheckError::ApplicationVersionMismatch);
+            }
+        }
+        if let Some(device_identifier) = &expectation.device_identifier {
+            if !self.matches_device(device_identifier) {
+                return Err(LocalReceiptCheckError::DeviceMismatch);
+            }
+        }
+        Ok(())
+    }
+}
+
+//
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LocalReceiptCheckError {
+    #[error("BundleIdMismatch")]
+    BundleIdMismatch,
+    #[error("ApplicationVersionMismatch")]
+    ApplicationVersionMismatch,
+    #[error("DeviceMismatch")]
+    DeviceMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    const DEVICE_ID: &[u8] = include_bytes!("../../tests/local_receipt_files/device_id");
+
+    #[test]
+    fn test_check() -> Result<(), Box<dyn error::Error>> {
+        let local_receipt = LocalReceipt::from_pkcs7_bytes(include_bytes!(
+            "../../tests/local_receipt_files/receipt.der"
+        ))?;
+
+        assert!(local_receipt.matches_device(DEVICE_ID));
+        assert!(!local_receipt.matches_device(&[0; 16]));
+
+        let mut expectation = LocalReceiptExpectation::new("com.example.app");
+        expectation
+            .set_application_version("1.0.1")
+            .set_device_identifier(DEVICE_ID);
+        assert_eq!(local_receipt.check(&expectation), Ok(()));
+
+        assert_eq!(
+            local_receipt.check(&LocalReceiptExpectation::new("com.example.other")),
+            Err(LocalReceiptCheckError::BundleIdMismatch)
+        );
+        assert_eq!(
+            local_receipt.check(
+                LocalReceiptExpectation::new("com.example.app").set_application_version("1.0.0")
+            ),
+            Err(LocalReceiptCheckError::ApplicationVersionMismatch)
+        );
+        assert_eq!(
+            local_receipt.check(
+                LocalReceiptExpectation::new("com.example.app").set_device_identifier([0; 16])
+            ),
+            Err(LocalReceiptCheckError::DeviceMismatch)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,528 @@
+// ref https://developer.apple.com/library/archive/releasenotes/General/ValidateAppStoreReceipt/Chapters/ValidateLocally.html
+
+use std::borrow::Cow;
+
+use chrono::{DateTime, Datelike as _, Duration, NaiveDate, Utc};
+
+#[cfg(feature = "with-base64")]
+use crate::endpoints::verify_receipt::decode_base64;
+use crate::{
+    endpoints::verify_receipt::ReceiptData,
+    objects::response_body::{Receipt, ReceiptInApp, ReceiptType, Transaction},
+};
+
+use super::{
+    asn1::{Asn1Error, Element, Reader, TAG_INTEGER, TAG_OCTET_STRING, TAG_SEQUENCE, TAG_SET},
+    pkcs7::{parse_signed_data, SignedDataError},
+};
+
+// Receipt fields
+const ATTR_RECEIPT_TYPE: i64 = 0;
+const ATTR_APP_ITEM_ID: i64 = 1;
+const ATTR_BUNDLE_ID: i64 = 2;
+const ATTR_APPLICATION_VERSION: i64 = 3;
+const ATTR_OPAQUE_VALUE: i64 = 4;
+const ATTR_SHA1_HASH: i64 = 5;
+const ATTR_RECEIPT_CREATION_DATE: i64 = 12;
+const ATTR_DOWNLOAD_ID: i64 = 15;
+const ATTR_VERSION_EXTERNAL_IDENTIFIER: i64 = 16;
+const ATTR_IN_APP: i64 = 17;
+const ATTR_ORIGINAL_PURCHASE_DATE: i64 = 18;
+const ATTR_ORIGINAL_APPLICATION_VERSION: i64 = 19;
+const ATTR_EXPIRATION_DATE: i64 = 21;
+const ATTR_PREORDER_DATE: i64 = 32;
+
+// In-App Purchase Receipt fields
+const ATTR_IAP_QUANTITY: i64 = 1701;
+const ATTR_IAP_PRODUCT_ID: i64 = 1702;
+const ATTR_IAP_TRANSACTION_ID: i64 = 1703;
+const ATTR_IAP_PURCHASE_DATE: i64 = 1704;
+const ATTR_IAP_ORIGINAL_TRANSACTION_ID: i64 = 1705;
+const ATTR_IAP_ORIGINAL_PURCHASE_DATE: i64 = 1706;
+const ATTR_IAP_EXPIRES_DATE: i64 = 1708;
+const ATTR_IAP_WEB_ORDER_LINE_ITEM_ID: i64 = 1711;
+const ATTR_IAP_CANCELLATION_DATE: i64 = 1712;
+const ATTR_IAP_IS_TRIAL_PERIOD: i64 = 1713;
+const ATTR_IAP_IS_IN_INTRO_OFFER_PERIOD: i64 = 1719;
+const ATTR_IAP_PROMOTIONAL_OFFER_ID: i64 = 1721;
+
+//
+#[derive(Debug)]
+pub struct LocalReceipt {
+    pub receipt: Receipt,
+    /// Raw value of the bundle identifier field, used when computing the device hash.
+    pub bundle_id_data: Vec<u8>,
+    pub opaque_value: Vec<u8>,
+    pub sha1_hash: Vec<u8>,
+}
+
+impl LocalReceipt {
+    pub fn from_receipt_data(receipt_data: &ReceiptData) -> Result<Self, LocalReceiptError> {
+        Self::from_pkcs7_bytes(&receipt_data_to_bytes(receipt_data)?)
+    }
+
+    pub fn from_pkcs7_bytes(bytes: &[u8]) -> Result<Self, LocalReceiptError> {
+        let signed_data = parse_signed_data(bytes)?;
+
+        Self::from_payload_bytes(&signed_data.content)
+    }
+
+    /// The receipt payload, i.e. the content of the PKCS#7 container.
+    pub fn from_payload_bytes(bytes: &[u8]) -> Result<Self, LocalReceiptError> {
+        let mut receipt_type = None;
+        let mut app_item_id = None;
+        let mut bundle_id = None;
+        let mut application_version = None;
+        let mut opaque_value = None;
+        let mut sha1_hash = None;
+        let mut receipt_creation_date = None;
+        let mut download_id = None;
+        let mut version_external_identifier = None;
+        let mut in_app = vec![];
+        let mut original_purchase_date = None;
+        let mut original_application_version = None;
+        let mut expiration_date = None;
+        let mut preorder_date = None;
+
+        for_each_attribute(bytes, |r#type, value| {
+            match r#type {
+                ATTR_RECEIPT_TYPE => receipt_type = Some(decode_string(r#type, value)?),
+                ATTR_APP_ITEM_ID => app_item_id = Some(decode_usize(r#type, value)?),
+                ATTR_BUNDLE_ID => {
+                    bundle_id = Some((decode_string(r#type, value)?, value.to_vec()));
+                }
+                ATTR_APPLICATION_VERSION => {
+                    application_version = Some(decode_string(r#type, value)?)
+                }
+                ATTR_OPAQUE_VALUE => opaque_value = Some(value.to_vec()),
+                ATTR_SHA1_HASH => sha1_hash = Some(value.to_vec()),
+                ATTR_RECEIPT_CREATION_DATE => {
+                    receipt_creation_date = decode_date(r#type, value)?;
+                }
+                ATTR_DOWNLOAD_ID => download_id = Some(decode_usize(r#type, value)?),
+                ATTR_VERSION_EXTERNAL_IDENTIFIER => {
+                    version_external_identifier = Some(decode_usize(r#type, value)?)
+                }
+                ATTR_IN_APP => in_app.push(decode_in_app(value)?),
+                ATTR_ORIGINAL_PURCHASE_DATE => {
+                    original_purchase_date = decode_date(r#type, value)?;
+                }
+                ATTR_ORIGINAL_APPLICATION_VERSION => {
+                    original_application_version = Some(decode_string(r#type, value)?)
+                }
+                ATTR_EXPIRATION_DATE => expiration_date = decode_date(r#type, value)?,
+                ATTR_PREORDER_DATE => preorder_date = decode_date(r#type, value)?,
+                _ => {}
+            }
+            Ok(())
+        })?;
+
+        let (bundle_id, bundle_id_data) =
+            bundle_id.ok_or(LocalReceiptError::MissingAttribute(ATTR_BUNDLE_ID))?;
+        let receipt_creation_date = receipt_creation_date.ok_or(
+            LocalReceiptError::MissingAttribute(ATTR_RECEIPT_CREATION_DATE),
+        )?;
+        let original_purchase_date = original_purchase_date.unwrap_or(receipt_creation_date);
+        let app_item_id = app_item_id.unwrap_or(0);
+
+        // There is no request when decoding locally, the creation date is used as request date.
+        let receipt = Receipt {
+            receipt_type: receipt_type
+                .map(|x| x.parse().unwrap_or(ReceiptType::Other(x)))
+                .unwrap_or_else(|| ReceiptType::Other(Default::default())),
+            adam_id: app_item_id,
+            app_item_id,
+            bundle_id,
+            application_version: application_version.ok_or(LocalReceiptError::MissingAttribute(
+                ATTR_APPLICATION_VERSION,
+            ))?,
+            download_id,
+            version_external_identifier: version_external_identifier.unwrap_or(0),
+            receipt_creation_date,
+            receipt_creation_date_pst: to_pst_string(&receipt_creation_date),
+            request_date: receipt_creation_date,
+            request_date_pst: to_pst_string(&receipt_creation_date),
+            original_purchase_date,
+            original_purchase_date_pst: to_pst_string(&original_purchase_date),
+            original_application_version,
+            in_app: Some(in_app),
+            expiration_date,
+            expiration_date_pst: expiration_date.as_ref().map(to_pst_string),
+            preorder_date,
+            preorder_date_pst: preorder_date.as_ref().map(to_pst_string),
+            extra: Default::default(),
+        };
+
+        Ok(Self {
+            receipt,
+            bundle_id_data,
+            opaque_value: opaque_value
+                .ok_or(LocalReceiptError::MissingAttribute(ATTR_OPAQUE_VALUE))?,
+            sha1_hash: sha1_hash.ok_or(LocalReceiptError::MissingAttribute(ATTR_SHA1_HASH))?,
+        })
+    }
+}
+
+pub(crate) fn receipt_data_to_bytes(
+    receipt_data: &ReceiptData,
+) -> Result<Cow<'_, [u8]>, LocalReceiptError> {
+    match receipt_data {
+        #[cfg(feature = "with-base64")]
+        ReceiptData::Bytes(bytes) => Ok(Cow::Borrowed(bytes)),
+        #[cfg(feature = "with-base64")]
+        ReceiptData::Base64String(string) => decode_base64(string)
+            .map(Cow::Owned)
+            .map_err(LocalReceiptError::Base64DecodeFailed),
+        #[cfg(not(feature = "with-base64"))]
+        ReceiptData::Base64String(_) => Err(LocalReceiptError::Base64Unsupported),
+    }
+}
+
+fn decode_in_app(bytes: &[u8]) -> Result<ReceiptInApp, LocalReceiptError> {
+    let mut quantity = None;
+    let mut product_id = None;
+    let mut transaction_id = None;
+    let mut original_transaction_id = None;
+    let mut purchase_date = None;
+    let mut original_purchase_date = None;
+    let mut expires_date = None;
+    let mut cancellation_date = None;
+    let mut web_order_line_item_id = None;
+    let mut is_trial_period = None;
+    let mut is_in_intro_offer_period = None;
+    let mut promotional_offer_id = None;
+
+    for_each_attribute(bytes, |r#type, value| {
+        match r#type {
+            ATTR_IAP_QUANTITY => quantity = Some(decode_usize(r#type, value)?),
+            ATTR_IAP_PRODUCT_ID => product_id = Some(decode_string(r#type, value)?),
+            ATTR_IAP_TRANSACTION_ID => transaction_id = Some(decode_string(r#type, value)?),
+            ATTR_IAP_PURCHASE_DATE => purchase_date = decode_date(r#type, value)?,
+            ATTR_IAP_ORIGINAL_TRANSACTION_ID => {
+                original_transaction_id = Some(decode_string(r#type, value)?)
+            }
+            ATTR_IAP_ORIGINAL_PURCHASE_DATE => {
+                original_purchase_date = decode_date(r#type, value)?;
+            }
+            ATTR_IAP_EXPIRES_DATE => expires_date = decode_date(r#type, value)?,
+            ATTR_IAP_WEB_ORDER_LINE_ITEM_ID => {
+                web_order_line_item_id = Some(decode_usize(r#type, value)?)
+            }
+            ATTR_IAP_CANCELLATION_DATE => cancellation_date = decode_date(r#type, value)?,
+            ATTR_IAP_IS_TRIAL_PERIOD => is_trial_period = Some(decode_integer(r#type, value)? != 0),
+            ATTR_IAP_IS_IN_INTRO_OFFER_PERIOD => {
+                is_in_intro_offer_period = Some(decode_integer(r#type, value)? != 0)
+            }
+            ATTR_IAP_PROMOTIONAL_OFFER_ID => {
+                promotional_offer_id =
+                    Some(decode_string(r#type, value)?).filter(|x: &String| !x.is_empty())
+            }
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    let transaction_id =
+        transaction_id.ok_or(LocalReceiptError::MissingAttribute(ATTR_IAP_TRANSACTION_ID))?;
+    let purchase_date =
+        purchase_date.ok_or(LocalReceiptError::MissingAttribute(ATTR_IAP_PURCHASE_DATE))?;
+    let original_purchase_date = original_purchase_date.unwrap_or(purchase_date);
+
+    let transaction = Transaction {
+        quantity: quantity.unwrap_or(1),
+        product_id: product_id.ok_or(LocalReceiptError::MissingAttribute(ATTR_IAP_PRODUCT_ID))?,
+        original_transaction_id: original_transaction_id
+            .unwrap_or_else(|| transaction_id.to_owned()),
+        transaction_id,
+        purchase_date,
+        purchase_date_pst: to_pst_string(&purchase_date),
+        original_purchase_date,
+        original_purchase_date_pst: to_pst_string(&original_purchase_date),
+        expires_date,
+        expires_date_pst: expires_date.as_ref().map(to_pst_string),
+        cancellation_date,
+        cancellation_date_pst: cancellation_date.as_ref().map(to_pst_string),
+        cancellation_reason: None,
+        web_order_line_item_id: web_order_line_item_id
+            .filter(|x| *x != 0)
+            .map(|x| x.to_string()),
+        is_trial_period,
+        promotional_offer_id,
+        is_in_intro_offer_period,
+        in_app_ownership_type: None,
+        offer_code_ref_name: None,
+        app_account_token: None,
+        extra: Default::default(),
+    };
+
+    Ok(ReceiptInApp {
+        transaction,
+        subscription_group_identifier: None,
+    })
+}
+
+//
+//
+//
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LocalReceiptError {
+    #[cfg(feature = "with-base64")]
+    #[error("Base64DecodeFailed {0}")]
+    Base64DecodeFailed(base64::DecodeError),
+    #[cfg(not(feature = "with-base64"))]
+    #[error("Base64Unsupported")]
+    Base64Unsupported,
+    #[error("Asn1DecodeFailed {0}")]
+    Asn1DecodeFailed(&'static str),
+    #[error("NotSignedData")]
+    NotSignedData,
+    #[error("MissingAttribute {0}")]
+    MissingAttribute(i64),
+    #[error("InvalidAttribute {0}")]
+    InvalidAttribute(i64),
+}
+
+impl From<Asn1Error> for LocalReceiptError {
+    fn from(err: Asn1Error) -> Self {
+        Self::Asn1DecodeFailed(err.0)
+    }
+}
+
+impl From<SignedDataError> for LocalReceiptError {
+    fn from(err: SignedDataError) -> Self {
+        match err {
+            SignedDataError::Asn1(err) => err.into(),
+            SignedDataError::NotSignedData | SignedDataError::NotData => Self::NotSignedData,
+        }
+    }
+}
+
+//
+//
+//
+/*
+ReceiptAttribute ::= SEQUENCE {
+    type    INTEGER,
+    version INTEGER,
+    value   OCTET STRING
+}
+
+Payload ::= SET OF ReceiptAttribute
+*/
+fn for_each_attribute<F>(bytes: &[u8], mut f: F) -> Result<(), LocalReceiptError>
+where
+    F: FnMut(i64, &[u8]) -> Result<(), LocalReceiptError>,
+{
+    let set = Reader::new(bytes).next_required()?.expect(TAG_SET)?;
+    let mut attributes = set.children();
+    while let Some(attribute) = attributes.next()? {
+        let mut reader = attribute.expect(TAG_SEQUENCE)?.children();
+        let r#type = reader.next_required()?.integer()?;
+        let _version = reader.next_required()?.expect(TAG_INTEGER)?;
+        let value = reader.next_required()?;
+        if value.tag != TAG_OCTET_STRING && !value.is_constructed() {
+            return Err(LocalReceiptError::InvalidAttribute(r#type));
+        }
+        f(r#type, &value.octet_string()?)?;
+    }
+    Ok(())
+}
+
+fn decode_value(r#type: i64, value: &[u8]) -> Result<Element<'_>, LocalReceiptError> {
+    Reader::new(value)
+        .next_required()
+        .map_err(|_| LocalReceiptError::InvalidAttribute(r#type))
+}
+
+fn decode_string(r#type: i64, value: &[u8]) -> Result<String, LocalReceiptError> {
+    decode_value(r#type, value)?
+        .string()
+        .map(ToOwned::to_owned)
+        .map_err(|_| LocalReceiptError::InvalidAttribute(r#type))
+}
+
+fn decode_integer(r#type: i64, value: &[u8]) -> Result<i64, LocalReceiptError> {
+    decode_value(r#type, value)?
+        .integer()
+        .map_err(|_| LocalReceiptError::InvalidAttribute(r#type))
+}
+
+fn decode_usize(r#type: i64, value: &[u8]) -> Result<usize, LocalReceiptError> {
+    usize::try_from(decode_integer(r#type, value)?)
+        .map_err(|_| LocalReceiptError::InvalidAttribute(r#type))
+}
+
+fn decode_date(r#type: i64, value: &[u8]) -> Result<Option<DateTime<Utc>>, LocalReceiptError> {
+    let s = decode_string(r#type, value)?;
+    if s.is_empty() {
+        return Ok(None);
+    }
+    DateTime::parse_from_rfc3339(&s)
+        .map(|x| Some(x.with_timezone(&Utc)))
+        .map_err(|_| LocalReceiptError::InvalidAttribute(r#type))
+}
+
+/// Same format as the `*_pst` fields of verifyReceipt, e.g. `2013-08-01 00:00:00 America/Los_Angeles`.
+pub(crate) fn to_pst_string(dt: &DateTime<Utc>) -> String {
+    fn nth_sunday(year: i32, month: u32, nth: u32) -> NaiveDate {
+        let first = NaiveDate::from_ymd_opt(year, month, 1).expect("");
+        let offset = (7 - first.weekday().num_days_from_sunday()) % 7;
+        first + Duration::days((offset + (nth - 1) * 7) as i64)
+    }
+
+    // Since 2007, DST starts at 2:00 PST on the second Sunday in March
+    // and ends at 2:00 PDT on the first Sunday in November.
+    let year = dt.year();
+    let dst_start = nth_sunday(year, 3, 2).and_hms_opt(10, 0, 0).expect("");
+    let dst_end = nth_sunday(year, 11, 1).and_hms_opt(9, 0, 0).expect("");
+    let naive = dt.naive_utc();
+    let offset_hours = if naive >= dst_start && naive < dst_end {
+        -7
+    } else {
+        -8
+    };
+
+    format!(
+        "{} America/Los_Angeles",
+        (naive + Duration::hours(offset_hours)).format("%Y-%m-%d %H:%M:%S")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_from_pkcs7_bytes() -> Result<(), Box<dyn error::Error>> {
+        let bytes = include_bytes!("../../tests/local_receipt_files/receipt.der");
+
+        let local_receipt = LocalReceipt::from_pkcs7_bytes(bytes)?;
+        let receipt = &local_receipt.receipt;
+        assert_eq!(receipt.receipt_type, ReceiptType::ProductionSandbox);
+        assert_eq!(receipt.bundle_id, "com.example.app");
+        assert_eq!(receipt.application_version, "1.0.1");
+        assert_eq!(receipt.original_application_version, Some("1.0".to_owned()));
+        assert_eq!(
+            receipt.receipt_creation_date.timestamp_millis(),
+            1736928000000
+        );
+        assert_eq!(
+            receipt.receipt_creation_date_pst,
+            "2025-01-15 00:00:00 America/Los_Angeles"
+        );
+        assert_eq!(
+            receipt.original_purchase_date_pst,
+            "2013-08-01 00:00:00 America/Los_Angeles"
+        );
+        assert!(receipt.expiration_date.is_none());
+
+        assert_eq!(local_receipt.bundle_id_data, b"\x0c\x0fcom.example.app");
+        assert_eq!(local_receipt.opaque_value.len(), 16);
+        assert_eq!(local_receipt.sha1_hash.len(), 20);
+
+        let in_app = receipt.in_app.as_ref().unwrap();
+        assert_eq!(in_app.len(), 3);
+
+        let coins = in_app
+            .iter()
+            .find(|x| x.transaction.product_id == "com.example.app.coins100")
+            .unwrap();
+        assert_eq!(coins.transaction.quantity, 1);
+        assert_eq!(coins.transaction.transaction_id, "1000000000000001");
+        assert!(coins.transaction.expires_date.is_none());
+        assert!(coins.transaction.web_order_line_item_id.is_none());
+
+        let monthly = in_app
+            .iter()
+            .find(|x| x.transaction.product_id == "com.example.app.monthly")
+            .unwrap();
+        assert_eq!(
+            monthly
+                .transaction
+                .expires_date
+                .map(|x| x.timestamp_millis()),
+            Some(1737374400000)
+        );
+        assert_eq!(
+            monthly.transaction.web_order_line_item_id,
+            Some("2000000000000002".to_owned())
+        );
+        assert_eq!(monthly.transaction.is_trial_period, Some(true));
+        assert_eq!(monthly.transaction.is_in_intro_offer_period, Some(false));
+        assert!(monthly.transaction.cancellation_date.is_none());
+        assert!(monthly.transaction.promotional_offer_id.is_none());
+
+        let yearly = in_app
+            .iter()
+            .find(|x| x.transaction.product_id == "com.example.app.yearly")
+            .unwrap();
+        assert_eq!(
+            yearly.transaction.cancellation_date_pst,
+            Some("2024-07-31 17:00:00 America/Los_Angeles".to_owned())
+        );
+        assert_eq!(yearly.transaction.is_in_intro_offer_period, Some(true));
+        assert_eq!(
+            yearly.transaction.promotional_offer_id,
+            Some("yearly_promo".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-base64")]
+    #[test]
+    fn test_from_receipt_data() -> Result<(), Box<dyn error::Error>> {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let bytes = include_bytes!("../../tests/local_receipt_files/receipt.der");
+
+        let local_receipt = LocalReceipt::from_receipt_data(&ReceiptData::Base64String(
+            general_purpose::STANDARD.encode(bytes),
+        ))?;
+        assert_eq!(local_receipt.receipt.bundle_id, "com.example.app");
+
+        let local_receipt = LocalReceipt::from_receipt_data(&ReceiptData::Bytes(bytes.to_vec()))?;
+        assert_eq!(local_receipt.receipt.bundle_id, "com.example.app");
+
+        match LocalReceipt::from_pkcs7_bytes(&bytes[..100]) {
+            Err(LocalReceiptError::Asn1DecodeFailed(_)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_negative_integer_attribute() {
+        // SET { SEQUENCE { INTEGER 1701, INTEGER 1, OCTET STRING { INTEGER -1 } } }
+        let bytes = [
+            0x31, 0x0E, 0x30, 0x0C, 0x02, 0x02, 0x06, 0xA5, 0x02, 0x01, 0x01, 0x04, 0x03, 0x02,
+            0x01, 0xFF,
+        ];
+        match decode_in_app(&bytes) {
+            Err(LocalReceiptError::InvalidAttribute(ATTR_IAP_QUANTITY)) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_to_pst_string() {
+        for (ms, pst) in [
+            (1375340400000_i64, "2013-08-01 00:00:00 America/Los_Angeles"),
+            (1357027200000, "2013-01-01 00:00:00 America/Los_Angeles"),
+            // 2024-03-10 09:59:59 UTC / 10:00:00 UTC
+            (1710064799000, "2024-03-10 01:59:59 America/Los_Angeles"),
+            (1710064800000, "2024-03-10 03:00:00 America/Los_Angeles"),
+            // 2024-11-03 08:59:59 UTC / 09:00:00 UTC
+            (1730624399000, "2024-11-03 01:59:59 America/Los_Angeles"),
+            (1730624400000, "2024-11-03 01:00:00 America/Los_Angeles"),
+        ] {
+            assert_eq!(
+                to_pst_string(&DateTime::from_timestamp_millis(ms).unwrap()),
+                pst
+            );
+        }
+    }
+}
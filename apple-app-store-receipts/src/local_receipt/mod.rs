@@ -0,0 +1,13 @@
+// ref https://developer.apple.com/library/archive/releasenotes/General/ValidateAppStoreReceipt/Chapters/ReceiptFields.html
+
+pub(crate) mod asn1;
+pub mod check;
+pub mod decode;
+pub(crate) mod pkcs7;
+#[cfg(feature = "with-local-verification")]
+pub mod verify;
+
+pub use check::{LocalReceiptCheckError, LocalReceiptExpectation};
+pub use decode::{LocalReceipt, LocalReceiptError};
+#[cfg(feature = "with-local-verification")]
+pub use verify::{CertificateKind, LocalReceiptVerifier, LocalReceiptVerifyError};
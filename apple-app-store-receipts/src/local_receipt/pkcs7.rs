@@ -0,0 +1,184 @@
+// ref https://www.rfc-editor.org/rfc/rfc2315 SignedData
+
+use std::borrow::Cow;
+
+use super::asn1::{
+    decode_oid, Asn1Error, Element, Reader, TAG_CONTEXT_0, TAG_CONTEXT_1, TAG_INTEGER, TAG_OID,
+    TAG_SEQUENCE, TAG_SET,
+};
+
+pub(crate) const OID_DATA: &str = "1.2.840.113549.1.7.1";
+pub(crate) const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+
+#[derive(Debug)]
+#[cfg_attr(not(feature = "with-local-verification"), allow(dead_code))]
+pub(crate) struct SignedData<'a> {
+    pub(crate) content: Cow<'a, [u8]>,
+    /// DER encoded certificates.
+    pub(crate) certificates: Vec<&'a [u8]>,
+    pub(crate) signer_infos: Vec<SignerInfo<'a>>,
+}
+
+/*
+SignerInfo ::= SEQUENCE {
+    version CMSVersion,
+    sid SignerIdentifier,
+    digestAlgorithm DigestAlgorithmIdentifier,
+    signedAttrs [0] IMPLICIT SignedAttributes OPTIONAL,
+    signatureAlgorithm SignatureAlgorithmIdentifier,
+    signature SignatureValue,
+    unsignedAttrs [1] IMPLICIT UnsignedAttributes OPTIONAL
+}
+*/
+#[derive(Debug)]
+#[cfg_attr(not(feature = "with-local-verification"), allow(dead_code))]
+pub(crate) struct SignerInfo<'a> {
+    /// DER encoded issuer Name.
+    pub(crate) issuer: &'a [u8],
+    /// Content of the serial number INTEGER.
+    pub(crate) serial_number: &'a [u8],
+    pub(crate) digest_algorithm: String,
+    /// DER encoded signed attributes, re-tagged as SET OF as required for the signature.
+    pub(crate) signed_attrs: Option<Vec<u8>>,
+    pub(crate) signed_attrs_elements: Vec<(String, Element<'a>)>,
+    pub(crate) signature_algorithm: String,
+    pub(crate) signature: Cow<'a, [u8]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SignedDataError {
+    Asn1(Asn1Error),
+    NotSignedData,
+    NotData,
+}
+
+impl From<Asn1Error> for SignedDataError {
+    fn from(err: Asn1Error) -> Self {
+        Self::Asn1(err)
+    }
+}
+
+/// Only checks the ContentInfo header, the content is not parsed.
+#[cfg(feature = "with-base64")]
+pub(crate) fn is_pkcs7_signed_data(bytes: &[u8]) -> bool {
+    let sniff = || -> Result<bool, Asn1Error> {
+        let content_info = Reader::new(bytes).next_required()?.expect(TAG_SEQUENCE)?;
+        let content_type = content_info.children().next_required()?.expect(TAG_OID)?;
+        Ok(decode_oid(content_type.content)? == OID_SIGNED_DATA)
+    };
+    sniff().unwrap_or(false)
+}
+
+pub(crate) fn parse_signed_data(bytes: &[u8]) -> Result<SignedData<'_>, SignedDataError> {
+    // ContentInfo
+    let content_info = Reader::new(bytes).next_required()?.expect(TAG_SEQUENCE)?;
+    let mut reader = content_info.children();
+    let content_type = reader.next_required()?.expect(TAG_OID)?;
+    if decode_oid(content_type.content)? != OID_SIGNED_DATA {
+        return Err(SignedDataError::NotSignedData);
+    }
+    let explicit = reader.next_required()?.expect(TAG_CONTEXT_0)?;
+
+    // SignedData
+    let signed_data = explicit.children().next_required()?.expect(TAG_SEQUENCE)?;
+    let mut reader = signed_data.children();
+    let _version = reader.next_required()?.integer()?;
+    let _digest_algorithms = reader.next_required()?.expect(TAG_SET)?;
+
+    // EncapsulatedContentInfo
+    let encap_content_info = reader.next_required()?.expect(TAG_SEQUENCE)?;
+    let mut encap_reader = encap_content_info.children();
+    let e_content_type = encap_reader.next_required()?.expect(TAG_OID)?;
+    if decode_oid(e_content_type.content)? != OID_DATA {
+        return Err(SignedDataError::NotData);
+    }
+    let content = match encap_reader.next()? {
+        Some(explicit) => explicit
+            .expect(TAG_CONTEXT_0)?
+            .children()
+            .next_required()?
+            .octet_string()?,
+        None => Cow::Borrowed(&[][..]),
+    };
+
+    let mut certificates = vec![];
+    let mut signer_infos = vec![];
+    while let Some(element) = reader.next()? {
+        match element.tag {
+            // [0] IMPLICIT CertificateSet
+            TAG_CONTEXT_0 => {
+                let mut certs = element.children();
+                while let Some(cert) = certs.next()? {
+                    certificates.push(cert.raw);
+                }
+            }
+            // [1] IMPLICIT RevocationInfoChoices
+            TAG_CONTEXT_1 => {}
+            TAG_SET => {
+                let mut infos = element.children();
+                while let Some(info) = infos.next()? {
+                    signer_infos.push(parse_signer_info(info)?);
+                }
+            }
+            _ => return Err(Asn1Error("unexpected tag in SignedData").into()),
+        }
+    }
+
+    Ok(SignedData {
+        content,
+        certificates,
+        signer_infos,
+    })
+}
+
+fn parse_signer_info(element: Element<'_>) -> Result<SignerInfo<'_>, Asn1Error> {
+    let mut reader = element.expect(TAG_SEQUENCE)?.children();
+    let _version = reader.next_required()?.integer()?;
+
+    // Only IssuerAndSerialNumber, which is what Apple uses.
+    let sid = reader.next_required()?.expect(TAG_SEQUENCE)?;
+    let mut sid_reader = sid.children();
+    let issuer = sid_reader.next_required()?.expect(TAG_SEQUENCE)?.raw;
+    let serial_number = sid_reader.next_required()?.expect(TAG_INTEGER)?.content;
+
+    let digest_algorithm = parse_algorithm_identifier(reader.next_required()?)?;
+
+    let mut element = reader.next_required()?;
+    let mut signed_attrs = None;
+    let mut signed_attrs_elements = vec![];
+    if element.tag == TAG_CONTEXT_0 {
+        let mut buf = element.raw.to_vec();
+        buf[0] = TAG_SET;
+        signed_attrs = Some(buf);
+
+        let mut attrs = element.children();
+        while let Some(attr) = attrs.next()? {
+            let mut attr_reader = attr.expect(TAG_SEQUENCE)?.children();
+            let oid = decode_oid(attr_reader.next_required()?.expect(TAG_OID)?.content)?;
+            let values = attr_reader.next_required()?.expect(TAG_SET)?;
+            if let Some(value) = values.children().next()? {
+                signed_attrs_elements.push((oid, value));
+            }
+        }
+
+        element = reader.next_required()?;
+    }
+
+    let signature_algorithm = parse_algorithm_identifier(element)?;
+    let signature = reader.next_required()?.octet_string()?;
+
+    Ok(SignerInfo {
+        issuer,
+        serial_number,
+        digest_algorithm,
+        signed_attrs,
+        signed_attrs_elements,
+        signature_algorithm,
+        signature,
+    })
+}
+
+fn parse_algorithm_identifier(element: Element<'_>) -> Result<String, Asn1Error> {
+    let mut reader = element.expect(TAG_SEQUENCE)?.children();
+    decode_oid(reader.next_required()?.expect(TAG_OID)?.content)
+}
@@ -0,0 +1,478 @@
+// ref https://developer.apple.com/library/archive/releasenotes/General/ValidateAppStoreReceipt/Chapters/ValidateLocally.html
+
+use chrono::{DateTime, Utc};
+use rsa::{pkcs8::DecodePublicKey as _, Pkcs1v15Sign, RsaPublicKey};
+use sha1::Sha1;
+use sha2::{Digest as _, Sha256, Sha384, Sha512};
+use x509_cert::{
+    der::{Decode as _, Encode as _},
+    ext::pkix::BasicConstraints,
+    Certificate,
+};
+
+use crate::endpoints::verify_receipt::ReceiptData;
+
+use super::{
+    decode::{receipt_data_to_bytes, LocalReceipt, LocalReceiptError},
+    pkcs7::{parse_signed_data, SignedData, SignerInfo},
+};
+
+pub use crate::types::certificate_kind::{
+    CertificateKind, OID_APPLE_RECEIPT_SIGNING, OID_APPLE_WWDR_INTERMEDIATE,
+};
+
+const OID_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.1";
+const OID_SHA1_WITH_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.5";
+const OID_SHA256_WITH_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.11";
+const OID_SHA384_WITH_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.12";
+const OID_SHA512_WITH_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.13";
+const OID_SHA1: &str = "1.3.14.3.2.26";
+const OID_SHA256: &str = "2.16.840.1.101.3.4.2.1";
+const OID_SHA384: &str = "2.16.840.1.101.3.4.2.2";
+const OID_SHA512: &str = "2.16.840.1.101.3.4.2.3";
+const OID_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+
+//
+#[derive(Debug, Clone)]
+pub struct LocalReceiptVerifier {
+    root_certificate: Certificate,
+}
+
+impl LocalReceiptVerifier {
+    /// `root_certificate` is the DER encoded Apple Root CA certificate,
+    /// e.g. https://www.apple.com/appleca/AppleIncRootCertificate.cer
+    pub fn new(root_certificate: &[u8]) -> Result<Self, LocalReceiptVerifyError> {
+        let root_certificate = Certificate::from_der(root_certificate)
+            .map_err(|err| LocalReceiptVerifyError::RootCertificateInvalid(err.to_string()))?;
+
+        Ok(Self { root_certificate })
+    }
+
+    pub fn verify_receipt_data(
+        &self,
+        receipt_data: &ReceiptData,
+    ) -> Result<LocalReceipt, LocalReceiptVerifyError> {
+        let bytes =
+            receipt_data_to_bytes(receipt_data).map_err(LocalReceiptVerifyError::DecodeFailed)?;
+        self.verify_pkcs7_bytes(&bytes)
+    }
+
+    pub fn verify_pkcs7_bytes(
+        &self,
+        bytes: &[u8],
+    ) -> Result<LocalReceipt, LocalReceiptVerifyError> {
+        let signed_data = parse_signed_data(bytes)
+            .map_err(|err| LocalReceiptVerifyError::DecodeFailed(err.into()))?;
+
+        let local_receipt = LocalReceipt::from_payload_bytes(&signed_data.content)
+            .map_err(LocalReceiptVerifyError::DecodeFailed)?;
+
+        self.verify_signed_data(&signed_data, &local_receipt.receipt.receipt_creation_date)?;
+
+        Ok(local_receipt)
+    }
+
+    fn verify_signed_data(
+        &self,
+        signed_data: &SignedData<'_>,
+        at: &DateTime<Utc>,
+    ) -> Result<(), LocalReceiptVerifyError> {
+        let certificates = signed_data
+            .certificates
+            .iter()
+            .map(|x| Certificate::from_der(x))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| LocalReceiptVerifyError::CertificateDecodeFailed(err.to_string()))?;
+
+        let signer_info = match signed_data.signer_infos.as_slice() {
+            [signer_info] => signer_info,
+            [] => return Err(LocalReceiptVerifyError::SignerInfoMissing),
+            _ => return Err(LocalReceiptVerifyError::MultipleSignerInfos),
+        };
+
+        // Signer
+        let signer = certificates
+            .iter()
+            .find(|cert| {
+                cert.tbs_certificate.serial_number.as_bytes() == signer_info.serial_number
+                    && cert.tbs_certificate.issuer.to_der().ok().as_deref()
+                        == Some(signer_info.issuer)
+            })
+            .ok_or(LocalReceiptVerifyError::SignerCertificateNotFound)?;
+        if !has_extension(signer, OID_APPLE_RECEIPT_SIGNING) {
+            return Err(LocalReceiptVerifyError::SignerCertificateOidMissing);
+        }
+        if is_ca(signer) {
+            return Err(LocalReceiptVerifyError::SignerCertificateIsCa);
+        }
+        check_validity(signer, at, CertificateKind::Signer)?;
+
+        // Intermediate, e.g. Apple Worldwide Developer Relations Certification Authority
+        let intermediate = certificates
+            .iter()
+            .find(|cert| {
+                cert.tbs_certificate.subject == signer.tbs_certificate.issuer
+                    && cert.tbs_certificate.subject != cert.tbs_certificate.issuer
+            })
+            .ok_or(LocalReceiptVerifyError::IntermediateCertificateNotFound)?;
+        if !has_extension(intermediate, OID_APPLE_WWDR_INTERMEDIATE) {
+            return Err(LocalReceiptVerifyError::IntermediateCertificateOidMissing);
+        }
+        if !is_ca(intermediate) {
+            return Err(LocalReceiptVerifyError::IntermediateCertificateNotCa);
+        }
+        check_validity(intermediate, at, CertificateKind::Intermediate)?;
+        verify_certificate_signature(signer, intermediate, CertificateKind::Signer)?;
+
+        // Root
+        if intermediate.tbs_certificate.issuer != self.root_certificate.tbs_certificate.subject {
+            return Err(LocalReceiptVerifyError::NotIssuedByRootCertificate);
+        }
+        check_validity(&self.root_certificate, at, CertificateKind::Root)?;
+        verify_certificate_signature(
+            intermediate,
+            &self.root_certificate,
+            CertificateKind::Intermediate,
+        )?;
+
+        verify_signer_info(signer_info, &signed_data.content, signer)
+    }
+}
+
+//
+//
+//
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LocalReceiptVerifyError {
+    #[error("DecodeFailed {0}")]
+    DecodeFailed(LocalReceiptError),
+    #[error("RootCertificateInvalid {0}")]
+    RootCertificateInvalid(String),
+    #[error("CertificateDecodeFailed {0}")]
+    CertificateDecodeFailed(String),
+    #[error("SignerInfoMissing")]
+    SignerInfoMissing,
+    #[error("MultipleSignerInfos")]
+    MultipleSignerInfos,
+    #[error("SignerCertificateNotFound")]
+    SignerCertificateNotFound,
+    #[error("SignerCertificateOidMissing")]
+    SignerCertificateOidMissing,
+    #[error("SignerCertificateIsCa")]
+    SignerCertificateIsCa,
+    #[error("IntermediateCertificateNotFound")]
+    IntermediateCertificateNotFound,
+    #[error("IntermediateCertificateOidMissing")]
+    IntermediateCertificateOidMissing,
+    #[error("IntermediateCertificateNotCa")]
+    IntermediateCertificateNotCa,
+    #[error("NotIssuedByRootCertificate")]
+    NotIssuedByRootCertificate,
+    #[error("CertificateNotYetValid {0}")]
+    CertificateNotYetValid(CertificateKind),
+    #[error("CertificateExpired {0}")]
+    CertificateExpired(CertificateKind),
+    #[error("CertificateSignatureInvalid {0}")]
+    CertificateSignatureInvalid(CertificateKind),
+    #[error("UnsupportedAlgorithm {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("SignatureAlgorithmMismatch {0}")]
+    SignatureAlgorithmMismatch(String),
+    #[error("MessageDigestMismatch")]
+    MessageDigestMismatch,
+    #[error("SignatureInvalid")]
+    SignatureInvalid,
+}
+
+//
+//
+//
+fn check_validity(
+    cert: &Certificate,
+    at: &DateTime<Utc>,
+    kind: CertificateKind,
+) -> Result<(), LocalReceiptVerifyError> {
+    let validity = &cert.tbs_certificate.validity;
+    let at = at.timestamp();
+    if at < validity.not_before.to_unix_duration().as_secs() as i64 {
+        return Err(LocalReceiptVerifyError::CertificateNotYetValid(kind));
+    }
+    if at > validity.not_after.to_unix_duration().as_secs() as i64 {
+        return Err(LocalReceiptVerifyError::CertificateExpired(kind));
+    }
+    Ok(())
+}
+
+fn has_extension(cert: &Certificate, oid: &str) -> bool {
+    cert.tbs_certificate
+        .extensions
+        .as_ref()
+        .map(|extensions| extensions.iter().any(|x| x.extn_id.to_string() == oid))
+        .unwrap_or(false)
+}
+
+fn is_ca(cert: &Certificate) -> bool {
+    cert.tbs_certificate
+        .get::<BasicConstraints>()
+        .ok()
+        .flatten()
+        .map(|(_, x)| x.ca)
+        .unwrap_or(false)
+}
+
+fn public_key(cert: &Certificate) -> Result<RsaPublicKey, LocalReceiptVerifyError> {
+    let spki = &cert.tbs_certificate.subject_public_key_info;
+    if spki.algorithm.oid.to_string() != OID_RSA_ENCRYPTION {
+        return Err(LocalReceiptVerifyError::UnsupportedAlgorithm(
+            spki.algorithm.oid.to_string(),
+        ));
+    }
+    spki.to_der()
+        .ok()
+        .and_then(|der| RsaPublicKey::from_public_key_der(&der).ok())
+        .ok_or_else(|| {
+            LocalReceiptVerifyError::UnsupportedAlgorithm(spki.algorithm.oid.to_string())
+        })
+}
+
+fn verify_certificate_signature(
+    cert: &Certificate,
+    issuer: &Certificate,
+    kind: CertificateKind,
+) -> Result<(), LocalReceiptVerifyError> {
+    let oid = cert.signature_algorithm.oid.to_string();
+    let digest_algorithm = DigestAlgorithm::from_signature_algorithm(&oid)
+        .ok_or(LocalReceiptVerifyError::UnsupportedAlgorithm(oid))?;
+    let tbs = cert
+        .tbs_certificate
+        .to_der()
+        .map_err(|_| LocalReceiptVerifyError::CertificateSignatureInvalid(kind))?;
+    let signature = cert
+        .signature
+        .as_bytes()
+        .ok_or(LocalReceiptVerifyError::CertificateSignatureInvalid(kind))?;
+
+    digest_algorithm
+        .verify(&public_key(issuer)?, &tbs, signature)
+        .map_err(|_| LocalReceiptVerifyError::CertificateSignatureInvalid(kind))
+}
+
+fn verify_signer_info(
+    signer_info: &SignerInfo<'_>,
+    content: &[u8],
+    signer: &Certificate,
+) -> Result<(), LocalReceiptVerifyError> {
+    let digest_algorithm = match signer_info.digest_algorithm.as_str() {
+        OID_SHA1 => DigestAlgorithm::Sha1,
+        OID_SHA256 => DigestAlgorithm::Sha256,
+        OID_SHA384 => DigestAlgorithm::Sha384,
+        OID_SHA512 => DigestAlgorithm::Sha512,
+        oid => {
+            return Err(LocalReceiptVerifyError::UnsupportedAlgorithm(
+                oid.to_owned(),
+            ))
+        }
+    };
+    // rsaEncryption leaves the digest to digestAlgorithm, *WithRSAEncryption must agree with it.
+    match signer_info.signature_algorithm.as_str() {
+        OID_RSA_ENCRYPTION => {}
+        oid => match DigestAlgorithm::from_signature_algorithm(oid) {
+            Some(x) if x == digest_algorithm => {}
+            Some(_) => {
+                return Err(LocalReceiptVerifyError::SignatureAlgorithmMismatch(
+                    oid.to_owned(),
+                ))
+            }
+            None => {
+                return Err(LocalReceiptVerifyError::UnsupportedAlgorithm(
+                    oid.to_owned(),
+                ))
+            }
+        },
+    }
+
+    // Without signed attributes the signature covers the content,
+    // otherwise the signed attributes, which carry the content digest.
+    let signed = match &signer_info.signed_attrs {
+        Some(signed_attrs) => {
+            let message_digest = signer_info
+                .signed_attrs_elements
+                .iter()
+                .find(|(oid, _)| oid == OID_MESSAGE_DIGEST)
+                .and_then(|(_, value)| value.octet_string().ok())
+                .ok_or(LocalReceiptVerifyError::MessageDigestMismatch)?;
+            if message_digest.as_ref() != digest_algorithm.digest(content).as_slice() {
+                return Err(LocalReceiptVerifyError::MessageDigestMismatch);
+            }
+            signed_attrs.as_slice()
+        }
+        None => content,
+    };
+
+    digest_algorithm
+        .verify(&public_key(signer)?, signed, &signer_info.signature)
+        .map_err(|_| LocalReceiptVerifyError::SignatureInvalid)
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DigestAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    fn from_signature_algorithm(oid: &str) -> Option<Self> {
+        match oid {
+            OID_SHA1_WITH_RSA_ENCRYPTION => Some(Self::Sha1),
+            OID_SHA256_WITH_RSA_ENCRYPTION => Some(Self::Sha256),
+            OID_SHA384_WITH_RSA_ENCRYPTION => Some(Self::Sha384),
+            OID_SHA512_WITH_RSA_ENCRYPTION => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha1 => Sha1::digest(data).to_vec(),
+            Self::Sha256 => Sha256::digest(data).to_vec(),
+            Self::Sha384 => Sha384::digest(data).to_vec(),
+            Self::Sha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+
+    fn verify(&self, key: &RsaPublicKey, data: &[u8], signature: &[u8]) -> rsa::Result<()> {
+        let scheme = match self {
+            Self::Sha1 => Pkcs1v15Sign::new::<Sha1>(),
+            Self::Sha256 => Pkcs1v15Sign::new::<Sha256>(),
+            Self::Sha384 => Pkcs1v15Sign::new::<Sha384>(),
+            Self::Sha512 => Pkcs1v15Sign::new::<Sha512>(),
+        };
+        key.verify(scheme, &self.digest(data), signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    const ROOT_CA: &[u8] = include_bytes!("../../tests/local_receipt_files/root_ca.der");
+    const RECEIPT: &[u8] = include_bytes!("../../tests/local_receipt_files/receipt.der");
+
+    #[test]
+    fn test_verify() -> Result<(), Box<dyn error::Error>> {
+        let verifier = LocalReceiptVerifier::new(ROOT_CA)?;
+
+        let local_receipt = verifier.verify_pkcs7_bytes(RECEIPT)?;
+        assert_eq!(local_receipt.receipt.bundle_id, "com.example.app");
+
+        let local_receipt = verifier.verify_pkcs7_bytes(include_bytes!(
+            "../../tests/local_receipt_files/receipt_signed_attrs.der"
+        ))?;
+        assert_eq!(local_receipt.receipt.bundle_id, "com.example.app");
+
+        #[cfg(feature = "with-base64")]
+        {
+            let local_receipt =
+                verifier.verify_receipt_data(&ReceiptData::Bytes(RECEIPT.to_vec()))?;
+            assert_eq!(local_receipt.receipt.bundle_id, "com.example.app");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_failed() -> Result<(), Box<dyn error::Error>> {
+        let verifier = LocalReceiptVerifier::new(ROOT_CA)?;
+
+        // Tampered payload
+        let mut bytes = RECEIPT.to_vec();
+        let i = bytes
+            .windows(15)
+            .position(|x| x == b"com.example.app")
+            .unwrap();
+        bytes[i + 14] = b'q';
+        assert_eq!(
+            verifier.verify_pkcs7_bytes(&bytes).err(),
+            Some(LocalReceiptVerifyError::SignatureInvalid)
+        );
+
+        // Tampered signed attributes payload
+        let mut bytes =
+            include_bytes!("../../tests/local_receipt_files/receipt_signed_attrs.der").to_vec();
+        let i = bytes
+            .windows(15)
+            .position(|x| x == b"com.example.app")
+            .unwrap();
+        bytes[i + 14] = b'q';
+        assert_eq!(
+            verifier.verify_pkcs7_bytes(&bytes).err(),
+            Some(LocalReceiptVerifyError::MessageDigestMismatch)
+        );
+
+        assert_eq!(
+            verifier
+                .verify_pkcs7_bytes(include_bytes!(
+                    "../../tests/local_receipt_files/receipt_expired_signer.der"
+                ))
+                .err(),
+            Some(LocalReceiptVerifyError::CertificateExpired(
+                CertificateKind::Signer
+            ))
+        );
+
+        // signatureAlgorithm of the signer info rsaEncryption -> sha1WithRSAEncryption, digestAlgorithm is SHA-256
+        let mut bytes = RECEIPT.to_vec();
+        let oid = b"\x06\x09\x2a\x86\x48\x86\xf7\x0d\x01\x01\x01";
+        let i = bytes.windows(oid.len()).rposition(|x| x == oid).unwrap();
+        bytes[i + oid.len() - 1] = 0x05;
+        assert_eq!(
+            verifier.verify_pkcs7_bytes(&bytes).err(),
+            Some(LocalReceiptVerifyError::SignatureAlgorithmMismatch(
+                "1.2.840.113549.1.1.5".to_owned()
+            ))
+        );
+
+        assert_eq!(
+            verifier
+                .verify_pkcs7_bytes(include_bytes!(
+                    "../../tests/local_receipt_files/receipt_signer_without_oid.der"
+                ))
+                .err(),
+            Some(LocalReceiptVerifyError::SignerCertificateOidMissing)
+        );
+
+        assert_eq!(
+            verifier
+                .verify_pkcs7_bytes(include_bytes!(
+                    "../../tests/local_receipt_files/receipt_ca_signer.der"
+                ))
+                .err(),
+            Some(LocalReceiptVerifyError::SignerCertificateIsCa)
+        );
+
+        let verifier = LocalReceiptVerifier::new(include_bytes!(
+            "../../tests/local_receipt_files/other_root_ca.der"
+        ))?;
+        assert_eq!(
+            verifier.verify_pkcs7_bytes(RECEIPT).err(),
+            Some(LocalReceiptVerifyError::NotIssuedByRootCertificate)
+        );
+
+        match LocalReceiptVerifier::new(b"foo") {
+            Err(LocalReceiptVerifyError::RootCertificateInvalid(_)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match verifier.verify_pkcs7_bytes(&RECEIPT[..100]) {
+            Err(LocalReceiptVerifyError::DecodeFailed(_)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,14 @@
 // ref https://developer.apple.com/documentation/appstorereceipts/requestbody
 
 use chrono::{DateTime, Utc};
-use serde::{de, Deserialize, Deserializer};
+use std::fmt;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_aux::field_attributes::{
     deserialize_bool_from_anything, deserialize_datetime_utc_from_milliseconds,
     deserialize_number_from_string,
 };
-use serde_enum_str::Deserialize_enum_str;
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
 use serde_json::{Map, Value};
 
 use crate::types::status::Status;
@@ -16,6 +18,18 @@ pub enum ResponseBody {
     Success(Box<ResponseBodyWithSuccess>),
     Error(ResponseBodyWithError),
 }
+impl Serialize for ResponseBody {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Success(body) => body.serialize(serializer),
+            Self::Error(body) => body.serialize(serializer),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for ResponseBody {
     fn deserialize<D>(deserializer: D) -> Result<ResponseBody, D::Error>
     where
@@ -41,30 +55,45 @@ impl<'de> Deserialize<'de> for ResponseBody {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct ResponseBodyWithSuccess {
     pub status: Status,
     pub environment: Environment,
     pub receipt: Receipt,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub latest_receipt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub latest_receipt_info: Option<Vec<LatestReceiptInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_renewal_info: Option<Vec<PendingRenewalInfo>>,
+
+    // Unrecognized fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct ResponseBodyWithError {
     pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub environment: Option<Environment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub is_retryable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub exception: Option<String>,
+
+    // Unrecognized fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
 pub enum Environment {
     Sandbox,
     Production,
 }
 
-#[derive(Deserialize_enum_str, Debug, PartialEq, Eq)]
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, PartialEq, Eq, Clone)]
 pub enum ReceiptType {
     Production,
     #[allow(clippy::upper_case_acronyms)]
@@ -76,7 +105,7 @@ pub enum ReceiptType {
     Other(String),
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Receipt {
     pub receipt_type: ReceiptType,
 
@@ -84,55 +113,74 @@ pub struct Receipt {
     pub app_item_id: usize,
     pub bundle_id: String,
     pub application_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub download_id: Option<usize>,
 
     pub version_external_identifier: usize,
 
     #[serde(
-        rename(deserialize = "receipt_creation_date_ms"),
-        deserialize_with = "deserialize_datetime_utc_from_milliseconds"
+        rename = "receipt_creation_date_ms",
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds",
+        serialize_with = "serialize_datetime_utc_to_milliseconds"
     )]
     pub receipt_creation_date: DateTime<Utc>,
     pub receipt_creation_date_pst: String,
 
     #[serde(
-        rename(deserialize = "request_date_ms"),
-        deserialize_with = "deserialize_datetime_utc_from_milliseconds"
+        rename = "request_date_ms",
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds",
+        serialize_with = "serialize_datetime_utc_to_milliseconds"
     )]
     pub request_date: DateTime<Utc>,
     pub request_date_pst: String,
 
     #[serde(
-        rename(deserialize = "original_purchase_date_ms"),
-        deserialize_with = "deserialize_datetime_utc_from_milliseconds"
+        rename = "original_purchase_date_ms",
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds",
+        serialize_with = "serialize_datetime_utc_to_milliseconds"
     )]
     pub original_purchase_date: DateTime<Utc>,
     pub original_purchase_date_pst: String,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub original_application_version: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub in_app: Option<Vec<ReceiptInApp>>,
 
     #[serde(
-        rename(deserialize = "expiration_date_ms"),
+        rename = "expiration_date_ms",
         default,
-        deserialize_with = "deserialize_datetime_utc_from_milliseconds_option"
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds_option",
+        serialize_with = "serialize_datetime_utc_to_milliseconds_option",
+        skip_serializing_if = "Option::is_none"
     )]
     pub expiration_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub expiration_date_pst: Option<String>,
 
     #[serde(
-        rename(deserialize = "preorder_date_ms"),
+        rename = "preorder_date_ms",
         default,
-        deserialize_with = "deserialize_datetime_utc_from_milliseconds_option"
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds_option",
+        serialize_with = "serialize_datetime_utc_to_milliseconds_option",
+        skip_serializing_if = "Option::is_none"
     )]
     pub preorder_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub preorder_date_pst: Option<String>,
+
+    // Unrecognized fields, e.g. receipt_creation_date
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Transaction {
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(
+        deserialize_with = "deserialize_number_from_string",
+        serialize_with = "serialize_to_string"
+    )]
     pub quantity: usize,
 
     pub product_id: String,
@@ -141,63 +189,199 @@ pub struct Transaction {
     pub original_transaction_id: String,
 
     #[serde(
-        rename(deserialize = "purchase_date_ms"),
-        deserialize_with = "deserialize_datetime_utc_from_milliseconds"
+        rename = "purchase_date_ms",
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds",
+        serialize_with = "serialize_datetime_utc_to_milliseconds"
     )]
     pub purchase_date: DateTime<Utc>,
     pub purchase_date_pst: String,
 
     #[serde(
-        rename(deserialize = "original_purchase_date_ms"),
-        deserialize_with = "deserialize_datetime_utc_from_milliseconds"
+        rename = "original_purchase_date_ms",
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds",
+        serialize_with = "serialize_datetime_utc_to_milliseconds"
     )]
     pub original_purchase_date: DateTime<Utc>,
     pub original_purchase_date_pst: String,
 
     #[serde(
-        rename(deserialize = "expires_date_ms"),
+        rename = "expires_date_ms",
         default,
-        deserialize_with = "deserialize_datetime_utc_from_milliseconds_option"
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds_option",
+        serialize_with = "serialize_datetime_utc_to_milliseconds_option",
+        skip_serializing_if = "Option::is_none"
     )]
     pub expires_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_date_pst: Option<String>,
 
     #[serde(
-        rename(deserialize = "cancellation_date_ms"),
+        rename = "cancellation_date_ms",
         default,
-        deserialize_with = "deserialize_datetime_utc_from_milliseconds_option"
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds_option",
+        serialize_with = "serialize_datetime_utc_to_milliseconds_option",
+        skip_serializing_if = "Option::is_none"
     )]
     pub cancellation_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cancellation_date_pst: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cancellation_reason: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub web_order_line_item_id: Option<String>,
 
-    #[serde(default, deserialize_with = "deserialize_bool_from_anything_option")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_from_anything_option",
+        serialize_with = "serialize_bool_to_string_option",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub is_trial_period: Option<bool>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub promotional_offer_id: Option<String>,
 
-    #[serde(default, deserialize_with = "deserialize_bool_from_anything_option")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_from_anything_option",
+        serialize_with = "serialize_bool_to_string_option",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub is_in_intro_offer_period: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_app_ownership_type: Option<InAppOwnershipType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_code_ref_name: Option<String>,
+
+    /// The UUID set by the app with `applicationUsername` / `appAccountToken`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_account_token: Option<String>,
+
+    // Unrecognized fields, e.g. purchase_date
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct LatestReceiptInfo {
     #[serde(flatten)]
     pub transaction: Transaction,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub subscription_group_identifier: Option<String>,
 
-    #[serde(default, deserialize_with = "deserialize_bool_from_anything_option")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_from_anything_option",
+        serialize_with = "serialize_bool_to_string_option",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub is_upgraded: Option<bool>,
 }
 
-#[derive(Deserialize, Debug)]
+// https://developer.apple.com/documentation/appstorereceipts/responsebody/pending_renewal_info
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PendingRenewalInfo {
+    pub product_id: String,
+    pub original_transaction_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_renew_product_id: Option<String>,
+
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_from_anything_option",
+        serialize_with = "serialize_bool_to_number_string_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub auto_renew_status: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_intent: Option<ExpirationIntent>,
+
+    #[serde(
+        rename = "grace_period_expires_date_ms",
+        default,
+        deserialize_with = "deserialize_datetime_utc_from_milliseconds_option",
+        serialize_with = "serialize_datetime_utc_to_milliseconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub grace_period_expires_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grace_period_expires_date_pst: Option<String>,
+
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_from_anything_option",
+        serialize_with = "serialize_bool_to_number_string_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub is_in_billing_retry_period: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_code_ref_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_consent_status: Option<PriceConsentStatus>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promotional_offer_id: Option<String>,
+
+    // Unrecognized fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// https://developer.apple.com/documentation/appstorereceipts/expiration_intent
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, PartialEq, Eq, Clone)]
+pub enum ExpirationIntent {
+    #[serde(rename = "1")]
+    CustomerCanceled,
+    #[serde(rename = "2")]
+    BillingError,
+    #[serde(rename = "3")]
+    PriceIncreaseNotConsented,
+    #[serde(rename = "4")]
+    ProductNotAvailable,
+    #[serde(rename = "5")]
+    Unknown,
+    #[serde(other)]
+    Other(String),
+}
+
+// https://developer.apple.com/documentation/appstorereceipts/price_consent_status
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, PartialEq, Eq, Clone)]
+pub enum PriceConsentStatus {
+    #[serde(rename = "0")]
+    NotConsented,
+    #[serde(rename = "1")]
+    Consented,
+    #[serde(other)]
+    Other(String),
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct ReceiptInApp {
     #[serde(flatten)]
     pub transaction: Transaction,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_group_identifier: Option<String>,
+}
+
+// https://developer.apple.com/documentation/appstorereceipts/in_app_ownership_type
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum InAppOwnershipType {
+    #[serde(rename = "PURCHASED")]
+    Purchased,
+    #[serde(rename = "FAMILY_SHARED")]
+    FamilyShared,
+    #[serde(other)]
+    Other(String),
 }
 
 //
@@ -219,6 +403,66 @@ where
     deserialize_datetime_utc_from_milliseconds(deserializer).map(Some)
 }
 
+// Apple sends numbers, booleans and timestamps as strings.
+fn serialize_to_string<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: fmt::Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+fn serialize_datetime_utc_to_milliseconds<S>(
+    value: &DateTime<Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(&value.timestamp_millis())
+}
+
+fn serialize_datetime_utc_to_milliseconds_option<S>(
+    value: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => serialize_datetime_utc_to_milliseconds(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+// "true" / "false"
+fn serialize_bool_to_string_option<S>(
+    value: &Option<bool>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => serializer.collect_str(value),
+        None => serializer.serialize_none(),
+    }
+}
+
+// "1" / "0"
+fn serialize_bool_to_number_string_option<S>(
+    value: &Option<bool>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => serializer.serialize_str(if *value { "1" } else { "0" }),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,4 +507,121 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn de_pending_renewal_info() -> Result<(), Box<dyn error::Error>> {
+        let info: PendingRenewalInfo = serde_json::from_str(
+            r#"{"expiration_intent":"2","grace_period_expires_date":"2025-01-28 00:00:00 Etc/GMT","grace_period_expires_date_ms":"1738022400000","grace_period_expires_date_pst":"2025-01-27 16:00:00 America/Los_Angeles","product_id":"com.example.app.quarterly","is_in_billing_retry_period":"1","auto_renew_product_id":"com.example.app.quarterly","original_transaction_id":"1000000000000060","auto_renew_status":"1","price_consent_status":"0"}"#,
+        )?;
+        assert_eq!(info.expiration_intent, Some(ExpirationIntent::BillingError));
+        assert_eq!(
+            info.grace_period_expires_date.map(|x| x.timestamp_millis()),
+            Some(1738022400000)
+        );
+        assert_eq!(info.is_in_billing_retry_period, Some(true));
+        assert_eq!(info.auto_renew_status, Some(true));
+        assert_eq!(
+            info.price_consent_status,
+            Some(PriceConsentStatus::NotConsented)
+        );
+
+        let info: PendingRenewalInfo = serde_json::from_str(
+            r#"{"product_id":"com.example.app.monthly","original_transaction_id":"1000000000000010","auto_renew_status":"0","expiration_intent":"9"}"#,
+        )?;
+        assert_eq!(info.auto_renew_status, Some(false));
+        assert_eq!(
+            info.expiration_intent,
+            Some(ExpirationIntent::Other("9".to_owned()))
+        );
+        assert_eq!(info.is_in_billing_retry_period, None);
+        assert_eq!(info.grace_period_expires_date, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn de_receipt_in_app() -> Result<(), Box<dyn error::Error>> {
+        let in_app: ReceiptInApp = serde_json::from_str(
+            r#"{
+                "quantity": "1",
+                "product_id": "com.example.app.monthly",
+                "transaction_id": "1000000000000011",
+                "original_transaction_id": "1000000000000010",
+                "purchase_date_ms": "1733011200000",
+                "purchase_date_pst": "2024-11-30 16:00:00 America/Los_Angeles",
+                "original_purchase_date_ms": "1733011200000",
+                "original_purchase_date_pst": "2024-11-30 16:00:00 America/Los_Angeles",
+                "in_app_ownership_type": "FAMILY_SHARED",
+                "offer_code_ref_name": "WINTER",
+                "app_account_token": "7e3fb20b-4cdb-47cc-936d-99d65f608138",
+                "subscription_group_identifier": "20000001"
+            }"#,
+        )?;
+        assert_eq!(
+            in_app.transaction.in_app_ownership_type,
+            Some(InAppOwnershipType::FamilyShared)
+        );
+        assert_eq!(
+            in_app.transaction.offer_code_ref_name,
+            Some("WINTER".to_owned())
+        );
+        assert_eq!(
+            in_app.transaction.app_account_token,
+            Some("7e3fb20b-4cdb-47cc-936d-99d65f608138".to_owned())
+        );
+        assert_eq!(
+            in_app.subscription_group_identifier,
+            Some("20000001".to_owned())
+        );
+        assert!(in_app.transaction.extra.is_empty());
+
+        assert_eq!(
+            serde_json::from_str::<InAppOwnershipType>(r#""FAMILY_SHARED_V2""#)?,
+            InAppOwnershipType::Other("FAMILY_SHARED_V2".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn error::Error>> {
+        let content = include_str!("../../tests/entitlement_files/subscriptions.json");
+        let value: Value = serde_json::from_str(content)?;
+
+        let body: ResponseBody = serde_json::from_str(content)?;
+        match &body {
+            ResponseBody::Success(body) => {
+                assert!(body.extra.is_empty());
+                assert_eq!(
+                    body.receipt.extra.get("receipt_creation_date"),
+                    Some(&Value::from("2025-01-14 12:00:00 Etc/GMT"))
+                );
+                let latest_receipt_info = body.latest_receipt_info.as_ref().unwrap();
+                assert!(latest_receipt_info[0]
+                    .transaction
+                    .extra
+                    .contains_key("purchase_date"));
+                assert!(!latest_receipt_info[0]
+                    .transaction
+                    .extra
+                    .contains_key("subscription_group_identifier"));
+            }
+            ResponseBody::Error(_) => panic!(),
+        }
+        assert_eq!(serde_json::to_value(&body)?, value);
+
+        for content in [
+            r#"{"status":21007}"#,
+            r#"{"status":21104,"environment":"Production","is_retryable":true,"exception":"foo"}"#,
+            r#"{"status":21010,"environment":"Sandbox","foo":"bar"}"#,
+        ] {
+            let body: ResponseBody = serde_json::from_str(content)?;
+            assert_eq!(
+                serde_json::to_value(&body)?,
+                serde_json::from_str::<Value>(content)?
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,180 @@
+// https://developer.apple.com/documentation/appstoreserverapi/generating_json_web_tokens_for_api_requests
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use p256::{
+    ecdsa::{signature::Signer as _, Signature, SigningKey},
+    pkcs8::DecodePrivateKey as _,
+};
+use serde::Serialize;
+
+pub const AUDIENCE: &str = "appstoreconnect-v1";
+// Apple rejects tokens with an expiration time more than 60 minutes after the issued at time.
+pub const TTL_MAX_SECS: i64 = 60 * 60;
+
+//
+#[derive(Debug, Clone)]
+pub struct BearerTokenSigner {
+    issuer_id: Box<str>,
+    key_id: Box<str>,
+    bundle_id: Box<str>,
+    signing_key: SigningKey,
+    ttl_secs: i64,
+}
+
+impl BearerTokenSigner {
+    /// `private_key` is the content of the In-App Purchase key file, i.e. SubscriptionKey_XXXXXXXXXX.p8
+    pub fn new(
+        issuer_id: impl AsRef<str>,
+        key_id: impl AsRef<str>,
+        bundle_id: impl AsRef<str>,
+        private_key: impl AsRef<str>,
+    ) -> Result<Self, SignError> {
+        Ok(Self {
+            issuer_id: issuer_id.as_ref().into(),
+            key_id: key_id.as_ref().into(),
+            bundle_id: bundle_id.as_ref().into(),
+            signing_key: signing_key_from_pem(private_key.as_ref())?,
+            ttl_secs: TTL_MAX_SECS,
+        })
+    }
+
+    pub fn set_ttl_secs(&mut self, ttl_secs: i64) -> &mut Self {
+        self.ttl_secs = ttl_secs.clamp(1, TTL_MAX_SECS);
+        self
+    }
+
+    pub fn sign(&self, now: DateTime<Utc>) -> Result<String, SignError> {
+        let header = JwsHeader {
+            alg: "ES256",
+            kid: Some(&self.key_id),
+            typ: Some("JWT"),
+        };
+        let claims = Claims {
+            iss: &self.issuer_id,
+            iat: now.timestamp(),
+            exp: (now + Duration::seconds(self.ttl_secs)).timestamp(),
+            aud: AUDIENCE,
+            bid: &self.bundle_id,
+        };
+
+        sign_jws_compact(&header, &claims, &self.signing_key)
+    }
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    iat: i64,
+    exp: i64,
+    aud: &'a str,
+    bid: &'a str,
+}
+
+//
+//
+//
+#[derive(Serialize, Debug)]
+pub(crate) struct JwsHeader<'a> {
+    pub(crate) alg: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) kid: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) typ: Option<&'a str>,
+}
+
+pub(crate) fn signing_key_from_pem(pem: &str) -> Result<SigningKey, SignError> {
+    SigningKey::from_pkcs8_pem(pem.trim())
+        .map_err(|err| SignError::PrivateKeyInvalid(err.to_string()))
+}
+
+pub(crate) fn sign_jws_compact<H, C>(
+    header: &H,
+    claims: &C,
+    signing_key: &SigningKey,
+) -> Result<String, SignError>
+where
+    H: Serialize,
+    C: Serialize,
+{
+    let header = serde_json::to_vec(header).map_err(SignError::SerFailed)?;
+    let claims = serde_json::to_vec(claims).map_err(SignError::SerFailed)?;
+
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header),
+        URL_SAFE_NO_PAD.encode(claims)
+    );
+    let signature: Signature = signing_key.sign(signing_input.as_bytes());
+
+    Ok(format!(
+        "{signing_input}.{}",
+        URL_SAFE_NO_PAD.encode(signature.to_bytes())
+    ))
+}
+
+//
+#[derive(thiserror::Error, Debug)]
+pub enum SignError {
+    #[error("PrivateKeyInvalid {0}")]
+    PrivateKeyInvalid(String),
+    #[error("SerFailed {0}")]
+    SerFailed(serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use p256::ecdsa::{signature::Verifier as _, VerifyingKey};
+    use serde_json::Value;
+
+    #[test]
+    fn test_sign() -> Result<(), Box<dyn error::Error>> {
+        let private_key = include_str!("../../tests/server_api_files/AuthKey_TEST.p8");
+        let signer = BearerTokenSigner::new(
+            "57246542-96fe-1a63-e053-0824d011072a",
+            "2X9R4HXF34",
+            "com.example.app",
+            private_key,
+        )?;
+
+        let now = DateTime::from_timestamp(1736899200, 0).unwrap();
+        let token = signer.sign(now)?;
+        // ES256 with RFC 6979 nonces is deterministic.
+        assert_eq!(token, signer.sign(now)?);
+
+        let parts = token.split('.').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 3);
+
+        let header: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[0])?)?;
+        assert_eq!(
+            header,
+            serde_json::json!({"alg": "ES256", "kid": "2X9R4HXF34", "typ": "JWT"})
+        );
+        let claims: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[1])?)?;
+        assert_eq!(
+            claims,
+            serde_json::json!({
+                "iss": "57246542-96fe-1a63-e053-0824d011072a",
+                "iat": 1736899200,
+                "exp": 1736902800,
+                "aud": "appstoreconnect-v1",
+                "bid": "com.example.app"
+            })
+        );
+
+        let verifying_key = VerifyingKey::from(&signing_key_from_pem(private_key)?);
+        let signature = Signature::from_slice(&URL_SAFE_NO_PAD.decode(parts[2])?)?;
+        verifying_key.verify(format!("{}.{}", parts[0], parts[1]).as_bytes(), &signature)?;
+
+        match BearerTokenSigner::new("", "", "", "foo") {
+            Err(SignError::PrivateKeyInvalid(_)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+use http::{
+    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT},
+    Error as HttpError, Method, StatusCode, Version,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Error as SerdeJsonError;
+use url::{ParseError as UrlParseError, Url};
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{objects::ErrorResponse, BASE_URL_PRODUCTION, BASE_URL_SANDBOX},
+};
+
+pub const MAX_RETRY_COUNT_DEFAULT: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RetryReason {
+    RateLimitExceeded,
+    ServerError(StatusCode),
+}
+
+pub(crate) fn base_url(environment: &Environment) -> &'static str {
+    match environment {
+        Environment::Production => BASE_URL_PRODUCTION,
+        Environment::Sandbox => BASE_URL_SANDBOX,
+    }
+}
+
+pub(crate) fn make_url<'a>(
+    base_url: &str,
+    path_segments: impl IntoIterator<Item = &'a str>,
+) -> Result<Url, ServerApiEndpointError> {
+    let mut url = Url::parse(base_url).map_err(ServerApiEndpointError::MakeRequestUrlFailed)?;
+    url.path_segments_mut()
+        .map_err(|_| {
+            ServerApiEndpointError::MakeRequestUrlFailed(
+                UrlParseError::RelativeUrlWithCannotBeABaseBase,
+            )
+        })?
+        .pop_if_empty()
+        .extend(path_segments);
+    Ok(url)
+}
+
+pub(crate) fn render_get_request(
+    url: Url,
+    bearer_token: &str,
+) -> Result<Request<Body>, ServerApiEndpointError> {
+    render_request::<()>(Method::GET, url, bearer_token, None)
+}
+
+pub(crate) fn render_request<T>(
+    method: Method,
+    url: Url,
+    bearer_token: &str,
+    body: Option<&T>,
+) -> Result<Request<Body>, ServerApiEndpointError>
+where
+    T: Serialize,
+{
+    let builder = Request::builder()
+        .method(method)
+        .uri(url.as_str())
+        .version(Version::HTTP_11)
+        .header(USER_AGENT, "curl/7.80.0")
+        .header(AUTHORIZATION, format!("Bearer {bearer_token}"))
+        .header(ACCEPT, "application/json");
+
+    match body {
+        Some(body) => {
+            let body_bytes =
+                serde_json::to_vec(body).map_err(ServerApiEndpointError::SerRequestBodyFailed)?;
+            builder
+                .header(CONTENT_TYPE, "application/json")
+                .body(body_bytes)
+        }
+        None => builder.body(vec![]),
+    }
+    .map_err(ServerApiEndpointError::MakeRequestFailed)
+}
+
+pub(crate) fn parse_response<T>(
+    response: Response<Body>,
+    retry: Option<&RetryableEndpointRetry<RetryReason>>,
+    max_retry_count: usize,
+) -> Result<Result<T, RetryReason>, ServerApiEndpointError>
+where
+    T: DeserializeOwned,
+{
+    if let Err(retry_reason) = check_response_status(&response, retry, max_retry_count)? {
+        return Ok(Err(retry_reason));
+    }
+
+    serde_json::from_slice(response.body())
+        .map(Ok)
+        .map_err(ServerApiEndpointError::DeResponseBodyFailed)
+}
+
+// For the endpoints that respond without a body, e.g. 202 Accepted.
+pub(crate) fn check_response_status(
+    response: &Response<Body>,
+    retry: Option<&RetryableEndpointRetry<RetryReason>>,
+    max_retry_count: usize,
+) -> Result<Result<(), RetryReason>, ServerApiEndpointError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(Ok(()));
+    }
+
+    let error_response = serde_json::from_slice::<ErrorResponse>(response.body()).ok();
+
+    let retry_reason = if status == StatusCode::TOO_MANY_REQUESTS {
+        Some(RetryReason::RateLimitExceeded)
+    } else if status.is_server_error()
+        || error_response.as_ref().map(|x| x.is_retryable()) == Some(true)
+    {
+        Some(RetryReason::ServerError(status))
+    } else {
+        None
+    };
+    if let Some(retry_reason) = retry_reason {
+        if retry.map(|x| x.count).unwrap_or_default() < max_retry_count {
+            return Ok(Err(retry_reason));
+        }
+    }
+
+    Err(ServerApiEndpointError::StatusMismatch(
+        status,
+        error_response,
+    ))
+}
+
+//
+//
+//
+#[derive(thiserror::Error, Debug)]
+pub enum ServerApiEndpointError {
+    #[error("MakeRequestUrlFailed {0}")]
+    MakeRequestUrlFailed(UrlParseError),
+    #[error("ExtendByDaysInvalid {0}")]
+    ExtendByDaysInvalid(u8),
+    #[error("SerRequestBodyFailed {0}")]
+    SerRequestBodyFailed(SerdeJsonError),
+    #[error("MakeRequestFailed {0}")]
+    MakeRequestFailed(HttpError),
+    #[error("StatusMismatch {0} {1:?}")]
+    StatusMismatch(StatusCode, Option<ErrorResponse>),
+    #[error("DeResponseBodyFailed {0}")]
+    DeResponseBodyFailed(SerdeJsonError),
+}
+
+impl ServerApiEndpointError {
+    pub fn error_response(&self) -> Option<&ErrorResponse> {
+        match self {
+            Self::StatusMismatch(_, x) => x.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::server_api::objects::TransactionInfoResponse;
+
+    #[test]
+    fn test_make_url() -> Result<(), Box<dyn error::Error>> {
+        assert_eq!(
+            make_url(BASE_URL_SANDBOX, ["inApps", "v1", "lookup", "MK/5TR"])?.as_str(),
+            "https://api.storekit-sandbox.itunes.apple.com/inApps/v1/lookup/MK%2F5TR"
+        );
+        assert_eq!(
+            make_url("http://127.0.0.1:8080/", ["inApps"])?.as_str(),
+            "http://127.0.0.1:8080/inApps"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_response() -> Result<(), Box<dyn error::Error>> {
+        let response = |status: u16, body: &str| {
+            Response::builder()
+                .status(status)
+                .body(body.as_bytes().to_vec())
+                .unwrap()
+        };
+
+        let res: TransactionInfoResponse = parse_response(
+            response(200, r#"{"signedTransactionInfo":"a.b.c"}"#),
+            None,
+            MAX_RETRY_COUNT_DEFAULT,
+        )?
+        .unwrap();
+        assert_eq!(res.signed_transaction_info, "a.b.c");
+
+        assert_eq!(
+            parse_response::<TransactionInfoResponse>(
+                response(
+                    429,
+                    r#"{"errorCode":4290000,"errorMessage":"Rate limit exceeded."}"#
+                ),
+                None,
+                MAX_RETRY_COUNT_DEFAULT,
+            )?
+            .err(),
+            Some(RetryReason::RateLimitExceeded)
+        );
+        assert_eq!(
+            parse_response::<TransactionInfoResponse>(
+                response(500, ""),
+                Some(&RetryableEndpointRetry::new(
+                    2,
+                    RetryReason::ServerError(StatusCode::INTERNAL_SERVER_ERROR)
+                )),
+                MAX_RETRY_COUNT_DEFAULT,
+            )?
+            .err(),
+            Some(RetryReason::ServerError(StatusCode::INTERNAL_SERVER_ERROR))
+        );
+
+        match parse_response::<TransactionInfoResponse>(
+            response(500, ""),
+            Some(&RetryableEndpointRetry::new(
+                3,
+                RetryReason::ServerError(StatusCode::INTERNAL_SERVER_ERROR),
+            )),
+            MAX_RETRY_COUNT_DEFAULT,
+        ) {
+            Err(ServerApiEndpointError::StatusMismatch(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                None,
+            )) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match parse_response::<TransactionInfoResponse>(
+            response(
+                404,
+                r#"{"errorCode":4040010,"errorMessage":"Transaction id not found."}"#,
+            ),
+            Some(&RetryableEndpointRetry::new(
+                3,
+                RetryReason::ServerError(StatusCode::NOT_FOUND),
+            )),
+            MAX_RETRY_COUNT_DEFAULT,
+        ) {
+            Err(err @ ServerApiEndpointError::StatusMismatch(StatusCode::NOT_FOUND, _)) => {
+                assert_eq!(err.error_response().map(|x| x.error_code), Some(4040010));
+            }
+            x => panic!("{x:?}"),
+        }
+
+        match parse_response::<TransactionInfoResponse>(
+            response(401, ""),
+            None,
+            MAX_RETRY_COUNT_DEFAULT,
+        ) {
+            Err(ServerApiEndpointError::StatusMismatch(StatusCode::UNAUTHORIZED, None)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+}
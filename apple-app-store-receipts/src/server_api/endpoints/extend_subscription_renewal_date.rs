@@ -0,0 +1,171 @@
+// https://developer.apple.com/documentation/appstoreserverapi/extend_a_subscription_renewal_date
+
+use http::Method;
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{
+        objects::{
+            extend_renewal_date::EXTEND_BY_DAYS_MAX, ExtendRenewalDateRequest,
+            ExtendRenewalDateResponse,
+        },
+        BASE_URL_PRODUCTION,
+    },
+};
+
+use super::common::{
+    base_url, make_url, parse_response, render_request, RetryReason, ServerApiEndpointError,
+    MAX_RETRY_COUNT_DEFAULT,
+};
+
+#[derive(Debug, Clone)]
+pub struct ExtendSubscriptionRenewalDate {
+    bearer_token: String,
+    original_transaction_id: String,
+    extend_renewal_date_request: ExtendRenewalDateRequest,
+    base_url: String,
+    max_retry_count: usize,
+}
+impl ExtendSubscriptionRenewalDate {
+    pub fn new(
+        bearer_token: impl Into<String>,
+        original_transaction_id: impl Into<String>,
+        extend_renewal_date_request: ExtendRenewalDateRequest,
+    ) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            original_transaction_id: original_transaction_id.into(),
+            extend_renewal_date_request,
+            base_url: BASE_URL_PRODUCTION.to_owned(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) -> &mut Self {
+        self.base_url = base_url(&environment).to_owned();
+        self
+    }
+
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Retrying is safe, Apple dedupes by `request_identifier`.
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+}
+
+impl RetryableEndpoint for ExtendSubscriptionRenewalDate {
+    type RetryReason = RetryReason;
+
+    type RenderRequestError = ServerApiEndpointError;
+
+    type ParseResponseOutput = ExtendRenewalDateResponse;
+    type ParseResponseError = ServerApiEndpointError;
+
+    fn render_request(
+        &self,
+        _retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Request<Body>, Self::RenderRequestError> {
+        let extend_by_days = self.extend_renewal_date_request.extend_by_days;
+        if extend_by_days == 0 || extend_by_days > EXTEND_BY_DAYS_MAX {
+            return Err(ServerApiEndpointError::ExtendByDaysInvalid(extend_by_days));
+        }
+
+        let url = make_url(
+            &self.base_url,
+            [
+                "inApps",
+                "v1",
+                "subscriptions",
+                "extend",
+                &self.original_transaction_id,
+            ],
+        )?;
+
+        render_request(
+            Method::PUT,
+            url,
+            &self.bearer_token,
+            Some(&self.extend_renewal_date_request),
+        )
+    }
+
+    fn parse_response(
+        &self,
+        response: Response<Body>,
+        retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
+    {
+        parse_response(response, retry, self.max_retry_count)
+    }
+
+    fn max_retry_count(&self) -> usize {
+        self.max_retry_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use chrono::DateTime;
+
+    use crate::server_api::objects::ExtendReasonCode;
+
+    #[test]
+    fn test_render_request_and_parse_response() -> Result<(), Box<dyn error::Error>> {
+        let endpoint = ExtendSubscriptionRenewalDate::new(
+            "TOKEN",
+            "1000000000000010",
+            ExtendRenewalDateRequest {
+                extend_by_days: 7,
+                extend_reason_code: ExtendReasonCode::ServiceIssueOrOutage,
+                request_identifier: "6f6a3d2b-8f0c-4b6e-9a8d-0d1c2b3a4f5e".to_owned(),
+            },
+        );
+
+        let req = endpoint.render_request(None)?;
+        assert_eq!(req.method(), Method::PUT);
+        assert_eq!(
+            req.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v1/subscriptions/extend/1000000000000010"
+        );
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(req.body())?,
+            serde_json::json!({
+                "extendByDays": 7,
+                "extendReasonCode": 3,
+                "requestIdentifier": "6f6a3d2b-8f0c-4b6e-9a8d-0d1c2b3a4f5e"
+            })
+        );
+
+        let res = endpoint.parse_response(
+            Response::new(
+                br#"{"originalTransactionId":"1000000000000010","webOrderLineItemId":"2000000000000012","success":true,"effectiveDate":1739016000000}"#
+                    .to_vec(),
+            ),
+            None,
+        )?
+        .unwrap();
+        assert!(res.success);
+        assert_eq!(res.effective_date, DateTime::from_timestamp(1739016000, 0));
+
+        let mut request = endpoint.extend_renewal_date_request.clone();
+        request.extend_by_days = 91;
+        match ExtendSubscriptionRenewalDate::new("TOKEN", "1000000000000010", request)
+            .render_request(None)
+        {
+            Err(ServerApiEndpointError::ExtendByDaysInvalid(91)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+// https://developer.apple.com/documentation/appstoreserverapi/get_all_subscription_statuses
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{
+        objects::{StatusResponse, SubscriptionStatus},
+        BASE_URL_PRODUCTION,
+    },
+};
+
+use super::common::{
+    base_url, make_url, parse_response, render_get_request, RetryReason, ServerApiEndpointError,
+    MAX_RETRY_COUNT_DEFAULT,
+};
+
+#[derive(Debug, Clone)]
+pub struct GetAllSubscriptionStatuses {
+    bearer_token: String,
+    transaction_id: String,
+    status: Vec<SubscriptionStatus>,
+    base_url: String,
+    max_retry_count: usize,
+}
+impl GetAllSubscriptionStatuses {
+    /// `transaction_id` can be any transaction id of the customer's subscriptions.
+    pub fn new(bearer_token: impl Into<String>, transaction_id: impl Into<String>) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            transaction_id: transaction_id.into(),
+            status: vec![],
+            base_url: BASE_URL_PRODUCTION.to_owned(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    /// Only subscriptions in these statuses, all if empty.
+    pub fn set_status(
+        &mut self,
+        status: impl IntoIterator<Item = SubscriptionStatus>,
+    ) -> &mut Self {
+        self.status = status.into_iter().collect();
+        self
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) -> &mut Self {
+        self.base_url = base_url(&environment).to_owned();
+        self
+    }
+
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+}
+
+impl RetryableEndpoint for GetAllSubscriptionStatuses {
+    type RetryReason = RetryReason;
+
+    type RenderRequestError = ServerApiEndpointError;
+
+    type ParseResponseOutput = StatusResponse;
+    type ParseResponseError = ServerApiEndpointError;
+
+    fn render_request(
+        &self,
+        _retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Request<Body>, Self::RenderRequestError> {
+        let mut url = make_url(
+            &self.base_url,
+            ["inApps", "v1", "subscriptions", &self.transaction_id],
+        )?;
+        if !self.status.is_empty() {
+            let mut query_pairs = url.query_pairs_mut();
+            for status in &self.status {
+                query_pairs.append_pair("status", &status.code().to_string());
+            }
+        }
+
+        render_get_request(url, &self.bearer_token)
+    }
+
+    fn parse_response(
+        &self,
+        response: Response<Body>,
+        retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
+    {
+        parse_response(response, retry, self.max_retry_count)
+    }
+
+    fn max_retry_count(&self) -> usize {
+        self.max_retry_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_render_request() -> Result<(), Box<dyn error::Error>> {
+        let mut endpoint = GetAllSubscriptionStatuses::new("TOKEN", "1000000000000010");
+        assert_eq!(
+            endpoint.render_request(None)?.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v1/subscriptions/1000000000000010"
+        );
+
+        endpoint.set_status([
+            SubscriptionStatus::Active,
+            SubscriptionStatus::BillingGracePeriod,
+        ]);
+        assert_eq!(
+            endpoint.render_request(None)?.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v1/subscriptions/1000000000000010?status=1&status=4"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+// https://developer.apple.com/documentation/appstoreserverapi/get_status_of_subscription_renewal_date_extensions
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{objects::MassExtendRenewalDateStatusResponse, BASE_URL_PRODUCTION},
+};
+
+use super::common::{
+    base_url, make_url, parse_response, render_get_request, RetryReason, ServerApiEndpointError,
+    MAX_RETRY_COUNT_DEFAULT,
+};
+
+#[derive(Debug, Clone)]
+pub struct GetMassExtendRenewalDateStatus {
+    bearer_token: String,
+    product_id: String,
+    request_identifier: String,
+    base_url: String,
+    max_retry_count: usize,
+}
+impl GetMassExtendRenewalDateStatus {
+    pub fn new(
+        bearer_token: impl Into<String>,
+        product_id: impl Into<String>,
+        request_identifier: impl Into<String>,
+    ) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            product_id: product_id.into(),
+            request_identifier: request_identifier.into(),
+            base_url: BASE_URL_PRODUCTION.to_owned(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) -> &mut Self {
+        self.base_url = base_url(&environment).to_owned();
+        self
+    }
+
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+}
+
+impl RetryableEndpoint for GetMassExtendRenewalDateStatus {
+    type RetryReason = RetryReason;
+
+    type RenderRequestError = ServerApiEndpointError;
+
+    type ParseResponseOutput = MassExtendRenewalDateStatusResponse;
+    type ParseResponseError = ServerApiEndpointError;
+
+    fn render_request(
+        &self,
+        _retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Request<Body>, Self::RenderRequestError> {
+        let url = make_url(
+            &self.base_url,
+            [
+                "inApps",
+                "v1",
+                "subscriptions",
+                "extend",
+                "mass",
+                &self.product_id,
+                &self.request_identifier,
+            ],
+        )?;
+
+        render_get_request(url, &self.bearer_token)
+    }
+
+    fn parse_response(
+        &self,
+        response: Response<Body>,
+        retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
+    {
+        parse_response(response, retry, self.max_retry_count)
+    }
+
+    fn max_retry_count(&self) -> usize {
+        self.max_retry_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_render_request_and_parse_response() -> Result<(), Box<dyn error::Error>> {
+        let endpoint = GetMassExtendRenewalDateStatus::new(
+            "TOKEN",
+            "com.example.app.monthly",
+            "b1d1b7e4-6a0c-4b8e-8f5e-3c2a1d0e9f8a",
+        );
+        assert_eq!(
+            endpoint.render_request(None)?.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v1/subscriptions/extend/mass/com.example.app.monthly/b1d1b7e4-6a0c-4b8e-8f5e-3c2a1d0e9f8a"
+        );
+
+        let res = endpoint.parse_response(
+            Response::new(
+                br#"{"requestIdentifier":"b1d1b7e4-6a0c-4b8e-8f5e-3c2a1d0e9f8a","complete":false}"#
+                    .to_vec(),
+            ),
+            None,
+        )?
+        .unwrap();
+        assert!(!res.complete);
+        assert_eq!(res.complete_date, None);
+        assert_eq!(res.succeeded_count, None);
+
+        let res = endpoint.parse_response(
+            Response::new(
+                br#"{"requestIdentifier":"b1d1b7e4-6a0c-4b8e-8f5e-3c2a1d0e9f8a","complete":true,"completeDate":1736899200000,"succeededCount":30,"failedCount":2}"#
+                    .to_vec(),
+            ),
+            None,
+        )?
+        .unwrap();
+        assert!(res.complete);
+        assert_eq!(res.succeeded_count, Some(30));
+        assert_eq!(res.failed_count, Some(2));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+// https://developer.apple.com/documentation/appstoreserverapi/get_refund_history
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{objects::RefundHistoryResponse, BASE_URL_PRODUCTION},
+};
+
+use super::common::{
+    base_url, make_url, parse_response, render_get_request, RetryReason, ServerApiEndpointError,
+    MAX_RETRY_COUNT_DEFAULT,
+};
+
+#[derive(Debug, Clone)]
+pub struct GetRefundHistory {
+    bearer_token: String,
+    transaction_id: String,
+    revision: Option<String>,
+    base_url: String,
+    max_retry_count: usize,
+}
+impl GetRefundHistory {
+    pub fn new(bearer_token: impl Into<String>, transaction_id: impl Into<String>) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            transaction_id: transaction_id.into(),
+            revision: None,
+            base_url: BASE_URL_PRODUCTION.to_owned(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    pub fn set_revision(&mut self, revision: impl Into<String>) -> &mut Self {
+        self.revision = Some(revision.into());
+        self
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) -> &mut Self {
+        self.base_url = base_url(&environment).to_owned();
+        self
+    }
+
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+
+    /// The endpoint for the next page, `None` if there is no more.
+    pub fn next_page(&self, response: &RefundHistoryResponse) -> Option<Self> {
+        if !response.has_more {
+            return None;
+        }
+        let mut endpoint = self.clone();
+        endpoint.set_revision(&response.revision);
+        Some(endpoint)
+    }
+}
+
+impl RetryableEndpoint for GetRefundHistory {
+    type RetryReason = RetryReason;
+
+    type RenderRequestError = ServerApiEndpointError;
+
+    type ParseResponseOutput = RefundHistoryResponse;
+    type ParseResponseError = ServerApiEndpointError;
+
+    fn render_request(
+        &self,
+        _retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Request<Body>, Self::RenderRequestError> {
+        let mut url = make_url(
+            &self.base_url,
+            ["inApps", "v2", "refund", "lookup", &self.transaction_id],
+        )?;
+        if let Some(revision) = &self.revision {
+            url.query_pairs_mut().append_pair("revision", revision);
+        }
+
+        render_get_request(url, &self.bearer_token)
+    }
+
+    fn parse_response(
+        &self,
+        response: Response<Body>,
+        retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
+    {
+        parse_response(response, retry, self.max_retry_count)
+    }
+
+    fn max_retry_count(&self) -> usize {
+        self.max_retry_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_pagination() -> Result<(), Box<dyn error::Error>> {
+        let endpoint = GetRefundHistory::new("TOKEN", "1000000000000030");
+        assert_eq!(
+            endpoint.render_request(None)?.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v2/refund/lookup/1000000000000030"
+        );
+
+        let res = endpoint
+            .parse_response(
+                Response::new(
+                    br#"{"signedTransactions":["a.b.c"],"revision":"rev_1","hasMore":true}"#
+                        .to_vec(),
+                ),
+                None,
+            )?
+            .unwrap();
+        let next = endpoint.next_page(&res).expect("has more");
+        assert_eq!(
+            next.render_request(None)?.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v2/refund/lookup/1000000000000030?revision=rev_1"
+        );
+
+        let res = next
+            .parse_response(
+                Response::new(
+                    br#"{"signedTransactions":[],"revision":"rev_2","hasMore":false}"#.to_vec(),
+                ),
+                None,
+            )?
+            .unwrap();
+        assert!(next.next_page(&res).is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+// https://developer.apple.com/documentation/appstoreserverapi/get_transaction_history
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{
+        objects::{HistoryResponse, InAppOwnershipType},
+        BASE_URL_PRODUCTION,
+    },
+};
+
+use super::common::{
+    base_url, make_url, parse_response, render_get_request, RetryReason, ServerApiEndpointError,
+    MAX_RETRY_COUNT_DEFAULT,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistoryVersion {
+    /// Deprecated by Apple, it ignores the revision for non-auto-renewable purchases.
+    V1,
+    #[default]
+    V2,
+}
+
+impl HistoryVersion {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+        }
+    }
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/producttype
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProductType {
+    AutoRenewable,
+    NonRenewable,
+    Consumable,
+    NonConsumable,
+}
+
+impl ProductType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::AutoRenewable => "AUTO_RENEWABLE",
+            Self::NonRenewable => "NON_RENEWABLE",
+            Self::Consumable => "CONSUMABLE",
+            Self::NonConsumable => "NON_CONSUMABLE",
+        }
+    }
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    Ascending,
+    Descending,
+}
+
+impl Sort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ascending => "ASCENDING",
+            Self::Descending => "DESCENDING",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TransactionHistoryQuery {
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub product_id: Vec<String>,
+    pub product_type: Vec<ProductType>,
+    pub sort: Option<Sort>,
+    pub subscription_group_identifier: Vec<String>,
+    pub in_app_ownership_type: Option<InAppOwnershipType>,
+    pub revoked: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetTransactionHistory {
+    bearer_token: String,
+    transaction_id: String,
+    version: HistoryVersion,
+    revision: Option<String>,
+    query: TransactionHistoryQuery,
+    base_url: String,
+    max_retry_count: usize,
+}
+impl GetTransactionHistory {
+    pub fn new(bearer_token: impl Into<String>, transaction_id: impl Into<String>) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            transaction_id: transaction_id.into(),
+            version: Default::default(),
+            revision: None,
+            query: Default::default(),
+            base_url: BASE_URL_PRODUCTION.to_owned(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    pub fn set_version(&mut self, version: HistoryVersion) -> &mut Self {
+        self.version = version;
+        self
+    }
+
+    pub fn set_revision(&mut self, revision: impl Into<String>) -> &mut Self {
+        self.revision = Some(revision.into());
+        self
+    }
+
+    pub fn set_query(&mut self, query: TransactionHistoryQuery) -> &mut Self {
+        self.query = query;
+        self
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) -> &mut Self {
+        self.base_url = base_url(&environment).to_owned();
+        self
+    }
+
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+
+    /// The endpoint for the next page, `None` if there is no more.
+    pub fn next_page(&self, response: &HistoryResponse) -> Option<Self> {
+        if !response.has_more {
+            return None;
+        }
+        let mut endpoint = self.clone();
+        endpoint.set_revision(&response.revision);
+        Some(endpoint)
+    }
+}
+
+impl RetryableEndpoint for GetTransactionHistory {
+    type RetryReason = RetryReason;
+
+    type RenderRequestError = ServerApiEndpointError;
+
+    type ParseResponseOutput = HistoryResponse;
+    type ParseResponseError = ServerApiEndpointError;
+
+    fn render_request(
+        &self,
+        _retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Request<Body>, Self::RenderRequestError> {
+        let mut url = make_url(
+            &self.base_url,
+            [
+                "inApps",
+                self.version.as_str(),
+                "history",
+                &self.transaction_id,
+            ],
+        )?;
+
+        {
+            let query = &self.query;
+            let mut query_pairs = url.query_pairs_mut();
+            if let Some(revision) = &self.revision {
+                query_pairs.append_pair("revision", revision);
+            }
+            if let Some(start_date) = &query.start_date {
+                query_pairs.append_pair("startDate", &start_date.timestamp_millis().to_string());
+            }
+            if let Some(end_date) = &query.end_date {
+                query_pairs.append_pair("endDate", &end_date.timestamp_millis().to_string());
+            }
+            for product_id in &query.product_id {
+                query_pairs.append_pair("productId", product_id);
+            }
+            for product_type in &query.product_type {
+                query_pairs.append_pair("productType", product_type.as_str());
+            }
+            if let Some(sort) = &query.sort {
+                query_pairs.append_pair("sort", sort.as_str());
+            }
+            for subscription_group_identifier in &query.subscription_group_identifier {
+                query_pairs
+                    .append_pair("subscriptionGroupIdentifier", subscription_group_identifier);
+            }
+            if let Some(in_app_ownership_type) = &query.in_app_ownership_type {
+                query_pairs.append_pair("inAppOwnershipType", &in_app_ownership_type.to_string());
+            }
+            if let Some(revoked) = &query.revoked {
+                query_pairs.append_pair("revoked", &revoked.to_string());
+            }
+        }
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+
+        render_get_request(url, &self.bearer_token)
+    }
+
+    fn parse_response(
+        &self,
+        response: Response<Body>,
+        retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
+    {
+        parse_response(response, retry, self.max_retry_count)
+    }
+
+    fn max_retry_count(&self) -> usize {
+        self.max_retry_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_render_request() -> Result<(), Box<dyn error::Error>> {
+        let mut endpoint = GetTransactionHistory::new("TOKEN", "1000000000000010");
+        assert_eq!(
+            endpoint.render_request(None)?.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v2/history/1000000000000010"
+        );
+
+        endpoint
+            .set_version(HistoryVersion::V1)
+            .set_environment(Environment::Sandbox)
+            .set_query(TransactionHistoryQuery {
+                start_date: DateTime::from_timestamp(1735689600, 0),
+                product_id: vec!["com.example.app.monthly".to_owned()],
+                product_type: vec![ProductType::AutoRenewable, ProductType::NonConsumable],
+                sort: Some(Sort::Descending),
+                in_app_ownership_type: Some(InAppOwnershipType::FamilyShared),
+                revoked: Some(false),
+                ..Default::default()
+            });
+        assert_eq!(
+            endpoint.render_request(None)?.uri(),
+            "https://api.storekit-sandbox.itunes.apple.com/inApps/v1/history/1000000000000010?startDate=1735689600000&productId=com.example.app.monthly&productType=AUTO_RENEWABLE&productType=NON_CONSUMABLE&sort=DESCENDING&inAppOwnershipType=FAMILY_SHARED&revoked=false"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pagination() -> Result<(), Box<dyn error::Error>> {
+        let endpoint = GetTransactionHistory::new("TOKEN", "1000000000000010");
+
+        let res = endpoint
+            .parse_response(
+                Response::new(
+                    include_bytes!("../../../tests/server_api_files/history_response.json")
+                        .to_vec(),
+                ),
+                None,
+            )?
+            .unwrap();
+        let next = endpoint.next_page(&res).expect("has more");
+        assert_eq!(
+            next.render_request(None)?.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v2/history/1000000000000010?revision=1736899200000_1000000000000013"
+        );
+
+        Ok(())
+    }
+}
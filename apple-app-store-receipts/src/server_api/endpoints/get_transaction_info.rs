@@ -0,0 +1,107 @@
+// https://developer.apple.com/documentation/appstoreserverapi/get_transaction_info
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{objects::TransactionInfoResponse, BASE_URL_PRODUCTION},
+};
+
+use super::common::{
+    base_url, make_url, parse_response, render_get_request, RetryReason, ServerApiEndpointError,
+    MAX_RETRY_COUNT_DEFAULT,
+};
+
+#[derive(Debug, Clone)]
+pub struct GetTransactionInfo {
+    bearer_token: String,
+    transaction_id: String,
+    base_url: String,
+    max_retry_count: usize,
+}
+impl GetTransactionInfo {
+    pub fn new(bearer_token: impl Into<String>, transaction_id: impl Into<String>) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            transaction_id: transaction_id.into(),
+            base_url: BASE_URL_PRODUCTION.to_owned(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) -> &mut Self {
+        self.base_url = base_url(&environment).to_owned();
+        self
+    }
+
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+}
+
+impl RetryableEndpoint for GetTransactionInfo {
+    type RetryReason = RetryReason;
+
+    type RenderRequestError = ServerApiEndpointError;
+
+    type ParseResponseOutput = TransactionInfoResponse;
+    type ParseResponseError = ServerApiEndpointError;
+
+    fn render_request(
+        &self,
+        _retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Request<Body>, Self::RenderRequestError> {
+        let url = make_url(
+            &self.base_url,
+            ["inApps", "v1", "transactions", &self.transaction_id],
+        )?;
+
+        render_get_request(url, &self.bearer_token)
+    }
+
+    fn parse_response(
+        &self,
+        response: Response<Body>,
+        retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
+    {
+        parse_response(response, retry, self.max_retry_count)
+    }
+
+    fn max_retry_count(&self) -> usize {
+        self.max_retry_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_render_request() -> Result<(), Box<dyn error::Error>> {
+        let mut endpoint = GetTransactionInfo::new("TOKEN", "1000000000000001");
+        let req = endpoint.render_request(None)?;
+        assert_eq!(req.method(), http::Method::GET);
+        assert_eq!(
+            req.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v1/transactions/1000000000000001"
+        );
+        assert_eq!(req.headers()["authorization"], "Bearer TOKEN");
+
+        endpoint.set_environment(Environment::Sandbox);
+        let req = endpoint.render_request(None)?;
+        assert_eq!(
+            req.uri(),
+            "https://api.storekit-sandbox.itunes.apple.com/inApps/v1/transactions/1000000000000001"
+        );
+
+        Ok(())
+    }
+}
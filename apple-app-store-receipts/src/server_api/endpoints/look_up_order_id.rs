@@ -0,0 +1,107 @@
+// https://developer.apple.com/documentation/appstoreserverapi/look_up_order_id
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{objects::OrderLookupResponse, BASE_URL_PRODUCTION},
+};
+
+use super::common::{
+    base_url, make_url, parse_response, render_get_request, RetryReason, ServerApiEndpointError,
+    MAX_RETRY_COUNT_DEFAULT,
+};
+
+#[derive(Debug, Clone)]
+pub struct LookUpOrderId {
+    bearer_token: String,
+    order_id: String,
+    base_url: String,
+    max_retry_count: usize,
+}
+impl LookUpOrderId {
+    /// `order_id` is the order ID from the customer's email receipt.
+    pub fn new(bearer_token: impl Into<String>, order_id: impl Into<String>) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            order_id: order_id.into(),
+            base_url: BASE_URL_PRODUCTION.to_owned(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) -> &mut Self {
+        self.base_url = base_url(&environment).to_owned();
+        self
+    }
+
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+}
+
+impl RetryableEndpoint for LookUpOrderId {
+    type RetryReason = RetryReason;
+
+    type RenderRequestError = ServerApiEndpointError;
+
+    type ParseResponseOutput = OrderLookupResponse;
+    type ParseResponseError = ServerApiEndpointError;
+
+    fn render_request(
+        &self,
+        _retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Request<Body>, Self::RenderRequestError> {
+        let url = make_url(&self.base_url, ["inApps", "v1", "lookup", &self.order_id])?;
+
+        render_get_request(url, &self.bearer_token)
+    }
+
+    fn parse_response(
+        &self,
+        response: Response<Body>,
+        retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
+    {
+        parse_response(response, retry, self.max_retry_count)
+    }
+
+    fn max_retry_count(&self) -> usize {
+        self.max_retry_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::server_api::objects::OrderLookupStatus;
+
+    #[test]
+    fn test_render_request_and_parse_response() -> Result<(), Box<dyn error::Error>> {
+        let endpoint = LookUpOrderId::new("TOKEN", "MK5TTTVWJH");
+        let req = endpoint.render_request(None)?;
+        assert_eq!(
+            req.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v1/lookup/MK5TTTVWJH"
+        );
+
+        let res = endpoint
+            .parse_response(
+                Response::new(br#"{"status":0,"signedTransactions":["a.b.c"]}"#.to_vec()),
+                None,
+            )?
+            .unwrap();
+        assert_eq!(res.status, OrderLookupStatus::Valid);
+        assert_eq!(res.signed_transactions, vec!["a.b.c".to_owned()]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+// https://developer.apple.com/documentation/appstoreserverapi/extend_subscription_renewal_dates_for_all_active_subscribers
+
+use http::Method;
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{
+        objects::{
+            extend_renewal_date::EXTEND_BY_DAYS_MAX, MassExtendRenewalDateRequest,
+            MassExtendRenewalDateResponse,
+        },
+        BASE_URL_PRODUCTION,
+    },
+};
+
+use super::common::{
+    base_url, make_url, parse_response, render_request, RetryReason, ServerApiEndpointError,
+    MAX_RETRY_COUNT_DEFAULT,
+};
+
+#[derive(Debug, Clone)]
+pub struct MassExtendSubscriptionRenewalDate {
+    bearer_token: String,
+    mass_extend_renewal_date_request: MassExtendRenewalDateRequest,
+    base_url: String,
+    max_retry_count: usize,
+}
+impl MassExtendSubscriptionRenewalDate {
+    /// Poll the result with `GetMassExtendRenewalDateStatus`.
+    pub fn new(
+        bearer_token: impl Into<String>,
+        mass_extend_renewal_date_request: MassExtendRenewalDateRequest,
+    ) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            mass_extend_renewal_date_request,
+            base_url: BASE_URL_PRODUCTION.to_owned(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) -> &mut Self {
+        self.base_url = base_url(&environment).to_owned();
+        self
+    }
+
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Retrying is safe, Apple dedupes by `request_identifier`.
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+}
+
+impl RetryableEndpoint for MassExtendSubscriptionRenewalDate {
+    type RetryReason = RetryReason;
+
+    type RenderRequestError = ServerApiEndpointError;
+
+    type ParseResponseOutput = MassExtendRenewalDateResponse;
+    type ParseResponseError = ServerApiEndpointError;
+
+    fn render_request(
+        &self,
+        _retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Request<Body>, Self::RenderRequestError> {
+        let extend_by_days = self.mass_extend_renewal_date_request.extend_by_days;
+        if extend_by_days == 0 || extend_by_days > EXTEND_BY_DAYS_MAX {
+            return Err(ServerApiEndpointError::ExtendByDaysInvalid(extend_by_days));
+        }
+
+        let url = make_url(
+            &self.base_url,
+            ["inApps", "v1", "subscriptions", "extend", "mass"],
+        )?;
+
+        render_request(
+            Method::POST,
+            url,
+            &self.bearer_token,
+            Some(&self.mass_extend_renewal_date_request),
+        )
+    }
+
+    fn parse_response(
+        &self,
+        response: Response<Body>,
+        retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
+    {
+        parse_response(response, retry, self.max_retry_count)
+    }
+
+    fn max_retry_count(&self) -> usize {
+        self.max_retry_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::server_api::objects::ExtendReasonCode;
+
+    #[test]
+    fn test_render_request() -> Result<(), Box<dyn error::Error>> {
+        let endpoint = MassExtendSubscriptionRenewalDate::new(
+            "TOKEN",
+            MassExtendRenewalDateRequest {
+                extend_by_days: 3,
+                extend_reason_code: ExtendReasonCode::ServiceIssueOrOutage,
+                request_identifier: "b1d1b7e4-6a0c-4b8e-8f5e-3c2a1d0e9f8a".to_owned(),
+                product_id: "com.example.app.monthly".to_owned(),
+                storefront_country_codes: vec!["USA".to_owned(), "CAN".to_owned()],
+            },
+        );
+
+        let req = endpoint.render_request(None)?;
+        assert_eq!(req.method(), Method::POST);
+        assert_eq!(
+            req.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v1/subscriptions/extend/mass"
+        );
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(req.body())?,
+            serde_json::json!({
+                "extendByDays": 3,
+                "extendReasonCode": 3,
+                "requestIdentifier": "b1d1b7e4-6a0c-4b8e-8f5e-3c2a1d0e9f8a",
+                "productId": "com.example.app.monthly",
+                "storefrontCountryCodes": ["USA", "CAN"]
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+pub mod common;
+pub mod extend_subscription_renewal_date;
+pub mod get_all_subscription_statuses;
+pub mod get_mass_extend_renewal_date_status;
+pub mod get_refund_history;
+pub mod get_transaction_history;
+pub mod get_transaction_info;
+pub mod look_up_order_id;
+pub mod mass_extend_subscription_renewal_date;
+pub mod send_consumption_information;
+
+pub use common::{RetryReason, ServerApiEndpointError};
+pub use extend_subscription_renewal_date::ExtendSubscriptionRenewalDate;
+pub use get_all_subscription_statuses::GetAllSubscriptionStatuses;
+pub use get_mass_extend_renewal_date_status::GetMassExtendRenewalDateStatus;
+pub use get_refund_history::GetRefundHistory;
+pub use get_transaction_history::GetTransactionHistory;
+pub use get_transaction_info::GetTransactionInfo;
+pub use look_up_order_id::LookUpOrderId;
+pub use mass_extend_subscription_renewal_date::MassExtendSubscriptionRenewalDate;
+pub use send_consumption_information::SendConsumptionInformation;
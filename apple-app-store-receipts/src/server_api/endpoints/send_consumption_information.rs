@@ -0,0 +1,148 @@
+// https://developer.apple.com/documentation/appstoreserverapi/send_consumption_information
+
+use http::Method;
+
+use crate::{
+    endpoints::endpoint_prelude::*,
+    objects::response_body::Environment,
+    server_api::{objects::ConsumptionRequest, BASE_URL_PRODUCTION},
+};
+
+use super::common::{
+    base_url, check_response_status, make_url, render_request, RetryReason, ServerApiEndpointError,
+    MAX_RETRY_COUNT_DEFAULT,
+};
+
+#[derive(Debug, Clone)]
+pub struct SendConsumptionInformation {
+    bearer_token: String,
+    transaction_id: String,
+    consumption_request: ConsumptionRequest,
+    base_url: String,
+    max_retry_count: usize,
+}
+impl SendConsumptionInformation {
+    /// `transaction_id` is from the CONSUMPTION_REQUEST notification.
+    pub fn new(
+        bearer_token: impl Into<String>,
+        transaction_id: impl Into<String>,
+        consumption_request: ConsumptionRequest,
+    ) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            transaction_id: transaction_id.into(),
+            consumption_request,
+            base_url: BASE_URL_PRODUCTION.to_owned(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+        }
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) -> &mut Self {
+        self.base_url = base_url(&environment).to_owned();
+        self
+    }
+
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+}
+
+impl RetryableEndpoint for SendConsumptionInformation {
+    type RetryReason = RetryReason;
+
+    type RenderRequestError = ServerApiEndpointError;
+
+    type ParseResponseOutput = ();
+    type ParseResponseError = ServerApiEndpointError;
+
+    fn render_request(
+        &self,
+        _retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Request<Body>, Self::RenderRequestError> {
+        let url = make_url(
+            &self.base_url,
+            [
+                "inApps",
+                "v1",
+                "transactions",
+                "consumption",
+                &self.transaction_id,
+            ],
+        )?;
+
+        render_request(
+            Method::PUT,
+            url,
+            &self.bearer_token,
+            Some(&self.consumption_request),
+        )
+    }
+
+    fn parse_response(
+        &self,
+        response: Response<Body>,
+        retry: Option<&RetryableEndpointRetry<Self::RetryReason>>,
+    ) -> Result<Result<Self::ParseResponseOutput, Self::RetryReason>, Self::ParseResponseError>
+    {
+        check_response_status(&response, retry, self.max_retry_count)
+    }
+
+    fn max_retry_count(&self) -> usize {
+        self.max_retry_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::server_api::objects::{
+        AccountTenure, ConsumptionStatus, DeliveryStatus, LifetimeDollars, Platform, PlayTime,
+        UserStatus,
+    };
+
+    #[test]
+    fn test_render_request_and_parse_response() -> Result<(), Box<dyn error::Error>> {
+        let endpoint = SendConsumptionInformation::new(
+            "TOKEN",
+            "1000000000000001",
+            ConsumptionRequest {
+                customer_consented: true,
+                consumption_status: ConsumptionStatus::FullyConsumed,
+                platform: Platform::Apple,
+                sample_content_provided: true,
+                delivery_status: DeliveryStatus::DeliveredAndWorkingProperly,
+                app_account_token: "".to_owned(),
+                account_tenure: AccountTenure::Undeclared,
+                play_time: PlayTime::Undeclared,
+                lifetime_dollars_refunded: LifetimeDollars::Undeclared,
+                lifetime_dollars_purchased: LifetimeDollars::Undeclared,
+                user_status: UserStatus::Undeclared,
+                refund_preference: None,
+            },
+        );
+
+        let req = endpoint.render_request(None)?;
+        assert_eq!(req.method(), Method::PUT);
+        assert_eq!(
+            req.uri(),
+            "https://api.storekit.itunes.apple.com/inApps/v1/transactions/consumption/1000000000000001"
+        );
+        assert_eq!(req.headers()["content-type"], "application/json");
+        let body: serde_json::Value = serde_json::from_slice(req.body())?;
+        assert_eq!(body["consumptionStatus"], 3);
+
+        let res = Response::builder().status(202).body(vec![])?;
+        endpoint.parse_response(res, None)?.unwrap();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,463 @@
+// https://developer.apple.com/documentation/appstoreserverapi/jwstransaction
+// https://developer.apple.com/documentation/appstoreserverapi/jwsrenewalinfo
+
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine as _,
+};
+use chrono::{DateTime, Utc};
+use p256::ecdsa::signature::{hazmat::PrehashVerifier as _, Verifier as _};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Error as SerdeJsonError;
+use sha2::{Digest as _, Sha256, Sha384};
+use x509_cert::{
+    der::{asn1::ObjectIdentifier, Decode as _, Encode as _},
+    ext::pkix::BasicConstraints,
+    Certificate,
+};
+
+use crate::types::certificate_kind::CertificateKind;
+
+use super::objects::{
+    timestamp_millis, JwsRenewalInfoDecodedPayload, JwsTransactionDecodedPayload,
+};
+
+const OID_EC_PUBLIC_KEY: &str = "1.2.840.10045.2.1";
+const OID_SECP256R1: &str = "1.2.840.10045.3.1.7";
+const OID_SECP384R1: &str = "1.3.132.0.34";
+const OID_ECDSA_WITH_SHA256: &str = "1.2.840.10045.4.3.2";
+const OID_ECDSA_WITH_SHA384: &str = "1.2.840.10045.4.3.3";
+
+pub use crate::types::certificate_kind::{OID_APPLE_RECEIPT_SIGNING, OID_APPLE_WWDR_INTERMEDIATE};
+
+//
+#[derive(Debug, Clone)]
+pub struct SignedDataVerifier {
+    root_certificates: Vec<Certificate>,
+}
+
+impl SignedDataVerifier {
+    /// `root_certificate` is the DER encoded Apple Root CA certificate,
+    /// e.g. https://www.apple.com/certificateauthority/AppleRootCA-G3.cer
+    pub fn new(root_certificate: &[u8]) -> Result<Self, JwsVerifyError> {
+        let mut this = Self {
+            root_certificates: vec![],
+        };
+        this.add_root_certificate(root_certificate)?;
+        Ok(this)
+    }
+
+    pub fn add_root_certificate(
+        &mut self,
+        root_certificate: &[u8],
+    ) -> Result<&mut Self, JwsVerifyError> {
+        let root_certificate = Certificate::from_der(root_certificate)
+            .map_err(|err| JwsVerifyError::RootCertificateInvalid(err.to_string()))?;
+        self.root_certificates.push(root_certificate);
+        Ok(self)
+    }
+
+    pub fn verify_and_decode_transaction(
+        &self,
+        signed_transaction_info: &str,
+    ) -> Result<JwsTransactionDecodedPayload, JwsVerifyError> {
+        self.verify_and_decode(signed_transaction_info)
+    }
+
+    pub fn verify_and_decode_renewal_info(
+        &self,
+        signed_renewal_info: &str,
+    ) -> Result<JwsRenewalInfoDecodedPayload, JwsVerifyError> {
+        self.verify_and_decode(signed_renewal_info)
+    }
+
+    /// The certificates are checked at the `signedDate` of the payload.
+    pub fn verify_and_decode<T>(&self, jws: &str) -> Result<T, JwsVerifyError>
+    where
+        T: DeserializeOwned,
+    {
+        let (signing_input, signature) = jws
+            .trim()
+            .rsplit_once('.')
+            .ok_or(JwsVerifyError::MalformedJws)?;
+        let (header, payload) = signing_input
+            .split_once('.')
+            .filter(|(_, payload)| !payload.contains('.'))
+            .ok_or(JwsVerifyError::MalformedJws)?;
+
+        let header: JwsHeader = serde_json::from_slice(&decode_base64url(header)?)
+            .map_err(JwsVerifyError::DeHeaderFailed)?;
+        if header.alg != "ES256" {
+            return Err(JwsVerifyError::UnsupportedAlgorithm(header.alg));
+        }
+
+        let payload = decode_base64url(payload)?;
+        let signed_date = serde_json::from_slice::<SignedDate>(&payload)
+            .map_err(JwsVerifyError::DePayloadFailed)?
+            .signed_date
+            .ok_or(JwsVerifyError::SignedDateMissing)?;
+
+        let leaf = self.verify_certificate_chain(&header.x5c, &signed_date)?;
+
+        let key = match ec_public_key(&leaf)? {
+            EcPublicKey::P256(key) => key,
+            EcPublicKey::P384(_) => {
+                return Err(JwsVerifyError::UnsupportedAlgorithm(
+                    OID_SECP384R1.to_owned(),
+                ))
+            }
+        };
+        let signature = p256::ecdsa::Signature::from_slice(&decode_base64url(signature)?)
+            .map_err(|_| JwsVerifyError::SignatureInvalid)?;
+        key.verify(signing_input.as_bytes(), &signature)
+            .map_err(|_| JwsVerifyError::SignatureInvalid)?;
+
+        serde_json::from_slice(&payload).map_err(JwsVerifyError::DePayloadFailed)
+    }
+
+    // x5c is [leaf, intermediate, root], the root must be one of ours.
+    fn verify_certificate_chain(
+        &self,
+        x5c: &[String],
+        at: &DateTime<Utc>,
+    ) -> Result<Certificate, JwsVerifyError> {
+        if x5c.len() != 3 {
+            return Err(JwsVerifyError::CertificateChainLengthMismatch(x5c.len()));
+        }
+        let chain = x5c
+            .iter()
+            .map(|x| {
+                let der = STANDARD
+                    .decode(x)
+                    .map_err(JwsVerifyError::Base64DecodeFailed)?;
+                Certificate::from_der(&der)
+                    .map_err(|err| JwsVerifyError::CertificateDecodeFailed(err.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let [leaf, intermediate, _]: [Certificate; 3] = chain
+            .try_into()
+            .map_err(|x: Vec<_>| JwsVerifyError::CertificateChainLengthMismatch(x.len()))?;
+
+        if !has_extension(&leaf, OID_APPLE_RECEIPT_SIGNING) {
+            return Err(JwsVerifyError::LeafCertificateOidMissing);
+        }
+        if is_ca(&leaf) {
+            return Err(JwsVerifyError::LeafCertificateIsCa);
+        }
+        if !has_extension(&intermediate, OID_APPLE_WWDR_INTERMEDIATE) {
+            return Err(JwsVerifyError::IntermediateCertificateOidMissing);
+        }
+        if !is_ca(&intermediate) {
+            return Err(JwsVerifyError::IntermediateCertificateNotCa);
+        }
+
+        let root = self
+            .root_certificates
+            .iter()
+            .find(|root| {
+                root.tbs_certificate.subject == intermediate.tbs_certificate.issuer
+                    && verify_certificate_signature(
+                        &intermediate,
+                        root,
+                        CertificateKind::Intermediate,
+                    )
+                    .is_ok()
+            })
+            .ok_or(JwsVerifyError::NotIssuedByRootCertificate)?;
+        verify_certificate_signature(&leaf, &intermediate, CertificateKind::Signer)?;
+
+        check_validity(root, at, CertificateKind::Root)?;
+        check_validity(&intermediate, at, CertificateKind::Intermediate)?;
+        check_validity(&leaf, at, CertificateKind::Signer)?;
+
+        Ok(leaf)
+    }
+}
+
+#[derive(Deserialize)]
+struct JwsHeader {
+    alg: String,
+    #[serde(default)]
+    x5c: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SignedDate {
+    #[serde(rename = "signedDate", default, with = "timestamp_millis::option")]
+    signed_date: Option<DateTime<Utc>>,
+}
+
+//
+//
+//
+#[derive(thiserror::Error, Debug)]
+pub enum JwsVerifyError {
+    #[error("RootCertificateInvalid {0}")]
+    RootCertificateInvalid(String),
+    #[error("MalformedJws")]
+    MalformedJws,
+    #[error("Base64DecodeFailed {0}")]
+    Base64DecodeFailed(base64::DecodeError),
+    #[error("DeHeaderFailed {0}")]
+    DeHeaderFailed(SerdeJsonError),
+    #[error("DePayloadFailed {0}")]
+    DePayloadFailed(SerdeJsonError),
+    #[error("UnsupportedAlgorithm {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("SignedDateMissing")]
+    SignedDateMissing,
+    #[error("CertificateChainLengthMismatch {0}")]
+    CertificateChainLengthMismatch(usize),
+    #[error("CertificateDecodeFailed {0}")]
+    CertificateDecodeFailed(String),
+    #[error("LeafCertificateOidMissing")]
+    LeafCertificateOidMissing,
+    #[error("LeafCertificateIsCa")]
+    LeafCertificateIsCa,
+    #[error("IntermediateCertificateOidMissing")]
+    IntermediateCertificateOidMissing,
+    #[error("IntermediateCertificateNotCa")]
+    IntermediateCertificateNotCa,
+    #[error("NotIssuedByRootCertificate")]
+    NotIssuedByRootCertificate,
+    #[error("CertificateNotYetValid {0}")]
+    CertificateNotYetValid(CertificateKind),
+    #[error("CertificateExpired {0}")]
+    CertificateExpired(CertificateKind),
+    #[error("CertificateSignatureInvalid {0}")]
+    CertificateSignatureInvalid(CertificateKind),
+    #[error("SignatureInvalid")]
+    SignatureInvalid,
+}
+
+//
+//
+//
+fn decode_base64url(s: &str) -> Result<Vec<u8>, JwsVerifyError> {
+    URL_SAFE_NO_PAD
+        .decode(s.trim_end_matches('='))
+        .map_err(JwsVerifyError::Base64DecodeFailed)
+}
+
+fn has_extension(cert: &Certificate, oid: &str) -> bool {
+    cert.tbs_certificate
+        .extensions
+        .as_ref()
+        .map(|extensions| extensions.iter().any(|x| x.extn_id.to_string() == oid))
+        .unwrap_or(false)
+}
+
+fn is_ca(cert: &Certificate) -> bool {
+    cert.tbs_certificate
+        .get::<BasicConstraints>()
+        .ok()
+        .flatten()
+        .map(|(_, x)| x.ca)
+        .unwrap_or(false)
+}
+
+fn check_validity(
+    cert: &Certificate,
+    at: &DateTime<Utc>,
+    kind: CertificateKind,
+) -> Result<(), JwsVerifyError> {
+    let validity = &cert.tbs_certificate.validity;
+    let at = at.timestamp();
+    if at < validity.not_before.to_unix_duration().as_secs() as i64 {
+        return Err(JwsVerifyError::CertificateNotYetValid(kind));
+    }
+    if at > validity.not_after.to_unix_duration().as_secs() as i64 {
+        return Err(JwsVerifyError::CertificateExpired(kind));
+    }
+    Ok(())
+}
+
+enum EcPublicKey {
+    P256(p256::ecdsa::VerifyingKey),
+    P384(p384::ecdsa::VerifyingKey),
+}
+
+fn ec_public_key(cert: &Certificate) -> Result<EcPublicKey, JwsVerifyError> {
+    let spki = &cert.tbs_certificate.subject_public_key_info;
+    if spki.algorithm.oid.to_string() != OID_EC_PUBLIC_KEY {
+        return Err(JwsVerifyError::UnsupportedAlgorithm(
+            spki.algorithm.oid.to_string(),
+        ));
+    }
+    let curve = spki
+        .algorithm
+        .parameters
+        .as_ref()
+        .and_then(|x| x.decode_as::<ObjectIdentifier>().ok())
+        .map(|x| x.to_string())
+        .unwrap_or_default();
+    let point = spki.subject_public_key.raw_bytes();
+    let unsupported = || JwsVerifyError::UnsupportedAlgorithm(curve.to_owned());
+
+    match curve.as_str() {
+        OID_SECP256R1 => p256::ecdsa::VerifyingKey::from_sec1_bytes(point)
+            .map(EcPublicKey::P256)
+            .map_err(|_| unsupported()),
+        OID_SECP384R1 => p384::ecdsa::VerifyingKey::from_sec1_bytes(point)
+            .map(EcPublicKey::P384)
+            .map_err(|_| unsupported()),
+        _ => Err(unsupported()),
+    }
+}
+
+fn verify_certificate_signature(
+    cert: &Certificate,
+    issuer: &Certificate,
+    kind: CertificateKind,
+) -> Result<(), JwsVerifyError> {
+    let tbs = cert
+        .tbs_certificate
+        .to_der()
+        .map_err(|_| JwsVerifyError::CertificateSignatureInvalid(kind))?;
+    let prehash = match cert.signature_algorithm.oid.to_string().as_str() {
+        OID_ECDSA_WITH_SHA256 => Sha256::digest(tbs).to_vec(),
+        OID_ECDSA_WITH_SHA384 => Sha384::digest(tbs).to_vec(),
+        oid => return Err(JwsVerifyError::UnsupportedAlgorithm(oid.to_owned())),
+    };
+    let signature = cert.signature.raw_bytes();
+
+    let ok = match ec_public_key(issuer)? {
+        EcPublicKey::P256(key) => p256::ecdsa::Signature::from_der(signature)
+            .map(|signature| key.verify_prehash(&prehash, &signature).is_ok())
+            .unwrap_or(false),
+        EcPublicKey::P384(key) => p384::ecdsa::Signature::from_der(signature)
+            .map(|signature| key.verify_prehash(&prehash, &signature).is_ok())
+            .unwrap_or(false),
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(JwsVerifyError::CertificateSignatureInvalid(kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::server_api::objects::{
+        Environment, InAppOwnershipType, OfferType, TransactionReason, TransactionType,
+    };
+
+    const ROOT_CA: &[u8] = include_bytes!("../../tests/server_api_files/jws/root_ca.der");
+    const TRANSACTION: &str = include_str!("../../tests/server_api_files/jws/transaction.jws");
+    const RENEWAL_INFO: &str = include_str!("../../tests/server_api_files/jws/renewal_info.jws");
+
+    #[test]
+    fn test_verify_and_decode() -> Result<(), Box<dyn error::Error>> {
+        let verifier = SignedDataVerifier::new(ROOT_CA)?;
+
+        let transaction = verifier.verify_and_decode_transaction(TRANSACTION)?;
+        assert_eq!(transaction.transaction_id, "1000000000000012");
+        assert_eq!(transaction.original_transaction_id, "1000000000000010");
+        assert_eq!(
+            transaction.r#type,
+            TransactionType::AutoRenewableSubscription
+        );
+        assert_eq!(
+            transaction.in_app_ownership_type,
+            InAppOwnershipType::Purchased
+        );
+        assert_eq!(transaction.environment, Environment::Sandbox);
+        assert_eq!(transaction.offer_type, Some(OfferType::Promotional));
+        assert_eq!(
+            transaction.transaction_reason,
+            Some(TransactionReason::Renewal)
+        );
+        assert_eq!(transaction.price, Some(4990));
+        assert!(transaction.extra.contains_key("someFutureField"));
+
+        let latest_receipt_info = transaction.to_latest_receipt_info();
+        assert_eq!(
+            latest_receipt_info.subscription_group_identifier.as_deref(),
+            Some("20000001")
+        );
+        assert_eq!(
+            latest_receipt_info
+                .transaction
+                .promotional_offer_id
+                .as_deref(),
+            Some("monthly_promo")
+        );
+        assert_eq!(
+            latest_receipt_info.transaction.expires_date,
+            DateTime::from_timestamp(1738411200, 0)
+        );
+
+        let renewal_info = verifier.verify_and_decode_renewal_info(RENEWAL_INFO)?;
+        assert_eq!(renewal_info.auto_renew_status, 1);
+        let pending_renewal_info = renewal_info.to_pending_renewal_info();
+        assert_eq!(
+            pending_renewal_info.auto_renew_product_id.as_deref(),
+            Some("com.example.app.yearly")
+        );
+        assert_eq!(pending_renewal_info.auto_renew_status, Some(true));
+        assert_eq!(pending_renewal_info.is_in_billing_retry_period, Some(true));
+        assert_eq!(
+            pending_renewal_info.grace_period_expires_date,
+            DateTime::from_timestamp(1739793600, 0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_failed() -> Result<(), Box<dyn error::Error>> {
+        let verifier = SignedDataVerifier::new(ROOT_CA)?;
+
+        // Tampered payload
+        let mut parts = TRANSACTION.split('.').collect::<Vec<_>>();
+        let mut payload: serde_json::Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[1])?)?;
+        payload["productId"] = "com.example.app.yearly".into();
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&payload)?);
+        parts[1] = &payload;
+        match verifier.verify_and_decode_transaction(&parts.join(".")) {
+            Err(JwsVerifyError::SignatureInvalid) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match verifier.verify_and_decode_transaction(include_str!(
+            "../../tests/server_api_files/jws/transaction_expired_signer.jws"
+        )) {
+            Err(JwsVerifyError::CertificateExpired(CertificateKind::Signer)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match verifier.verify_and_decode_transaction(include_str!(
+            "../../tests/server_api_files/jws/transaction_leaf_without_oid.jws"
+        )) {
+            Err(JwsVerifyError::LeafCertificateOidMissing) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match verifier.verify_and_decode_transaction(include_str!(
+            "../../tests/server_api_files/jws/transaction_leaf_ca.jws"
+        )) {
+            Err(JwsVerifyError::LeafCertificateIsCa) => {}
+            x => panic!("{x:?}"),
+        }
+
+        let other_verifier = SignedDataVerifier::new(include_bytes!(
+            "../../tests/server_api_files/jws/other_root_ca.der"
+        ))?;
+        match other_verifier.verify_and_decode_transaction(TRANSACTION) {
+            Err(JwsVerifyError::NotIssuedByRootCertificate) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match verifier.verify_and_decode_transaction("a.b") {
+            Err(JwsVerifyError::MalformedJws) => {}
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+}
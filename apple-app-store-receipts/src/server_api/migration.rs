@@ -0,0 +1,469 @@
+// https://developer.apple.com/documentation/appstoreserverapi/migrating_from_verifyreceipt
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    entitlement::{EntitlementState, Entitlements},
+    objects::response_body::{Environment, ResponseBodyWithSuccess, Transaction},
+};
+
+use super::{
+    endpoints::{GetAllSubscriptionStatuses, GetTransactionInfo},
+    objects::{JwsRenewalInfoDecodedPayload, JwsTransactionDecodedPayload},
+};
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionGroupLookup {
+    /// None when the receipt lacks it, then every chain is its own lookup.
+    pub subscription_group_identifier: Option<String>,
+    /// Of the most recently purchased chain, used for the lookup.
+    pub original_transaction_id: String,
+    /// All the chains of the group, sorted.
+    pub original_transaction_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionLookup {
+    pub original_transaction_id: String,
+    pub product_id: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    pub environment: Environment,
+    /// Sorted by subscription_group_identifier.
+    pub subscriptions: Vec<SubscriptionGroupLookup>,
+    /// Non-subscription purchases, sorted by original_transaction_id.
+    pub transactions: Vec<TransactionLookup>,
+}
+
+impl MigrationPlan {
+    /// Uses both `receipt.in_app` and `latest_receipt_info`.
+    pub fn from_response_body(body: &ResponseBodyWithSuccess) -> Self {
+        // original_transaction_id -> (subscription_group_identifier, latest transaction)
+        let mut chains: BTreeMap<&str, (Option<&str>, &Transaction)> = BTreeMap::new();
+        let items = body
+            .latest_receipt_info
+            .iter()
+            .flatten()
+            .map(|x| (&x.transaction, x.subscription_group_identifier.as_deref()))
+            .chain(
+                body.receipt
+                    .in_app
+                    .iter()
+                    .flatten()
+                    .map(|x| (&x.transaction, x.subscription_group_identifier.as_deref())),
+            );
+        for (transaction, group) in items {
+            let entry = chains
+                .entry(&transaction.original_transaction_id)
+                .or_insert((group, transaction));
+            if entry.0.is_none() {
+                entry.0 = group;
+            }
+            if transaction.purchase_date > entry.1.purchase_date {
+                entry.1 = transaction;
+            }
+        }
+
+        let mut groups: BTreeMap<Option<&str>, Vec<(&str, &Transaction)>> = BTreeMap::new();
+        let mut transactions = vec![];
+        for (original_transaction_id, (group, transaction)) in chains {
+            let is_subscription = group.is_some() || transaction.expires_date.is_some();
+            if !is_subscription {
+                transactions.push(TransactionLookup {
+                    original_transaction_id: original_transaction_id.to_owned(),
+                    product_id: transaction.product_id.to_owned(),
+                });
+                continue;
+            }
+            groups
+                .entry(group)
+                .or_default()
+                .push((original_transaction_id, transaction));
+        }
+
+        let mut subscriptions = vec![];
+        for (group, mut chains) in groups {
+            chains.sort_by_key(|(id, transaction)| (transaction.purchase_date, *id));
+            match group {
+                Some(group) => {
+                    let (latest, _) = chains.last().expect("");
+                    let mut original_transaction_ids = chains
+                        .iter()
+                        .map(|(id, _)| id.to_string())
+                        .collect::<Vec<_>>();
+                    original_transaction_ids.sort();
+                    subscriptions.push(SubscriptionGroupLookup {
+                        subscription_group_identifier: Some(group.to_owned()),
+                        original_transaction_id: latest.to_string(),
+                        original_transaction_ids,
+                    });
+                }
+                None => subscriptions.extend(chains.into_iter().map(|(id, _)| {
+                    SubscriptionGroupLookup {
+                        subscription_group_identifier: None,
+                        original_transaction_id: id.to_owned(),
+                        original_transaction_ids: vec![id.to_owned()],
+                    }
+                })),
+            }
+        }
+
+        Self {
+            environment: body.environment.to_owned(),
+            subscriptions,
+            transactions,
+        }
+    }
+
+    /// Deduplicated, sorted.
+    pub fn original_transaction_ids(&self) -> Vec<&str> {
+        let mut ids = self
+            .subscriptions
+            .iter()
+            .flat_map(|x| x.original_transaction_ids.iter())
+            .chain(self.transactions.iter().map(|x| &x.original_transaction_id))
+            .map(|x| x.as_str())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    pub fn get_all_subscription_statuses_requests(
+        &self,
+        bearer_token: &str,
+    ) -> Vec<GetAllSubscriptionStatuses> {
+        self.subscriptions
+            .iter()
+            .map(|x| {
+                let mut endpoint =
+                    GetAllSubscriptionStatuses::new(bearer_token, &x.original_transaction_id);
+                endpoint.set_environment(self.environment.to_owned());
+                endpoint
+            })
+            .collect()
+    }
+
+    pub fn get_transaction_info_requests(&self, bearer_token: &str) -> Vec<GetTransactionInfo> {
+        self.transactions
+            .iter()
+            .map(|x| {
+                let mut endpoint =
+                    GetTransactionInfo::new(bearer_token, &x.original_transaction_id);
+                endpoint.set_environment(self.environment.to_owned());
+                endpoint
+            })
+            .collect()
+    }
+}
+
+/// The App Store Server API view, from the decoded `signedTransactionInfo` and `signedRenewalInfo`.
+pub fn entitlements_from_jws_payloads(
+    transactions: &[JwsTransactionDecodedPayload],
+    renewal_infos: &[JwsRenewalInfoDecodedPayload],
+    at: &DateTime<Utc>,
+) -> Entitlements {
+    let latest_receipt_info = transactions
+        .iter()
+        .map(|x| x.to_latest_receipt_info())
+        .collect::<Vec<_>>();
+    let pending_renewal_info = renewal_infos
+        .iter()
+        .map(|x| x.to_pending_renewal_info())
+        .collect::<Vec<_>>();
+
+    let mut entitlements = Entitlements::from_latest_receipt_info(&latest_receipt_info, at);
+    entitlements.apply_pending_renewal_info(&pending_renewal_info, at);
+    entitlements
+}
+
+//
+//
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonOutcome {
+    Match,
+    OnlyInOld,
+    OnlyInNew,
+    StateMismatch,
+    ExpiresDateMismatch,
+}
+
+#[derive(Debug, Clone)]
+pub struct ComparisonEntry {
+    pub product_id: String,
+    pub old_state: Option<EntitlementState>,
+    pub new_state: Option<EntitlementState>,
+    pub old_expires_date: Option<DateTime<Utc>>,
+    pub new_expires_date: Option<DateTime<Utc>>,
+    pub outcome: ComparisonOutcome,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ComparisonReport {
+    /// Sorted by product_id.
+    pub entries: Vec<ComparisonEntry>,
+}
+
+impl ComparisonReport {
+    /// `old` from verifyReceipt, `new` from the App Store Server API.
+    pub fn compare(old: &Entitlements, new: &Entitlements) -> Self {
+        let mut product_ids = old
+            .products
+            .iter()
+            .chain(new.products.iter())
+            .map(|x| x.product_id.as_str())
+            .collect::<Vec<_>>();
+        product_ids.sort_unstable();
+        product_ids.dedup();
+
+        let entries = product_ids
+            .into_iter()
+            .map(|product_id| {
+                let old = old.get(product_id);
+                let new = new.get(product_id);
+                let outcome = match (old, new) {
+                    (Some(_), None) => ComparisonOutcome::OnlyInOld,
+                    (None, Some(_)) => ComparisonOutcome::OnlyInNew,
+                    (Some(old), Some(new)) if old.state != new.state => {
+                        ComparisonOutcome::StateMismatch
+                    }
+                    (Some(old), Some(new)) if old.expires_date != new.expires_date => {
+                        ComparisonOutcome::ExpiresDateMismatch
+                    }
+                    _ => ComparisonOutcome::Match,
+                };
+                ComparisonEntry {
+                    product_id: product_id.to_owned(),
+                    old_state: old.map(|x| x.state),
+                    new_state: new.map(|x| x.state),
+                    old_expires_date: old.and_then(|x| x.expires_date),
+                    new_expires_date: new.and_then(|x| x.expires_date),
+                    outcome,
+                }
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn mismatches(&self) -> impl Iterator<Item = &ComparisonEntry> {
+        self.entries
+            .iter()
+            .filter(|x| x.outcome != ComparisonOutcome::Match)
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches().next().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use http_api_client_endpoint::RetryableEndpoint as _;
+
+    use crate::objects::response_body::ResponseBody;
+
+    fn body(
+        value: serde_json::Value,
+    ) -> Result<Box<ResponseBodyWithSuccess>, Box<dyn error::Error>> {
+        match serde_json::from_value(value)? {
+            ResponseBody::Success(body) => Ok(body),
+            ResponseBody::Error(body) => Err(format!("{body:?}").into()),
+        }
+    }
+
+    #[test]
+    fn test_migration_plan() -> Result<(), Box<dyn error::Error>> {
+        let mut value: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/entitlement_files/subscriptions.json"
+        ))?;
+        // An older lapsed chain in the monthly group
+        let mut old_chain = value["latest_receipt_info"][0].clone();
+        old_chain["transaction_id"] = "1000000000000090".into();
+        old_chain["original_transaction_id"] = "1000000000000090".into();
+        old_chain["purchase_date_ms"] = "1704067200000".into();
+        old_chain["expires_date_ms"] = "1706745600000".into();
+        value["latest_receipt_info"]
+            .as_array_mut()
+            .expect("")
+            .push(old_chain);
+
+        let plan = MigrationPlan::from_response_body(&*body(value)?);
+        assert_eq!(plan.environment, Environment::Sandbox);
+        assert_eq!(
+            plan.subscriptions
+                .iter()
+                .map(|x| (
+                    x.subscription_group_identifier.as_deref(),
+                    x.original_transaction_id.as_str(),
+                    x.original_transaction_ids.len()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("20000001"), "1000000000000010", 2),
+                (Some("20000002"), "1000000000000020", 1),
+                (Some("20000003"), "1000000000000030", 1),
+                (Some("20000004"), "1000000000000040", 1),
+                (Some("20000005"), "1000000000000060", 1),
+            ]
+        );
+        assert_eq!(
+            plan.transactions,
+            vec![TransactionLookup {
+                original_transaction_id: "1000000000000051".to_owned(),
+                product_id: "com.example.app.pro".to_owned(),
+            }]
+        );
+        assert_eq!(plan.original_transaction_ids().len(), 7);
+
+        let requests = plan.get_all_subscription_statuses_requests("TOKEN");
+        assert_eq!(requests.len(), 5);
+        assert_eq!(
+            requests[0].render_request(None)?.uri(),
+            "https://api.storekit-sandbox.itunes.apple.com/inApps/v1/subscriptions/1000000000000010"
+        );
+        let requests = plan.get_transaction_info_requests("TOKEN");
+        assert_eq!(
+            requests[0].render_request(None)?.uri(),
+            "https://api.storekit-sandbox.itunes.apple.com/inApps/v1/transactions/1000000000000051"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migration_plan_with_in_app_only() -> Result<(), Box<dyn error::Error>> {
+        let mut value: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/entitlement_files/subscriptions.json"
+        ))?;
+        let mut in_app = value["latest_receipt_info"].take();
+        // An older lapsed chain in the monthly group
+        let mut old_chain = in_app[0].clone();
+        old_chain["transaction_id"] = "1000000000000090".into();
+        old_chain["original_transaction_id"] = "1000000000000090".into();
+        old_chain["purchase_date_ms"] = "1704067200000".into();
+        old_chain["expires_date_ms"] = "1706745600000".into();
+        in_app.as_array_mut().expect("").push(old_chain);
+        value["receipt"]["in_app"] = in_app;
+        value
+            .as_object_mut()
+            .expect("")
+            .remove("latest_receipt_info");
+
+        let plan = MigrationPlan::from_response_body(&*body(value)?);
+        assert_eq!(
+            plan.subscriptions
+                .iter()
+                .map(|x| (
+                    x.subscription_group_identifier.as_deref(),
+                    x.original_transaction_id.as_str(),
+                    x.original_transaction_ids.len()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("20000001"), "1000000000000010", 2),
+                (Some("20000002"), "1000000000000020", 1),
+                (Some("20000003"), "1000000000000030", 1),
+                (Some("20000004"), "1000000000000040", 1),
+                (Some("20000005"), "1000000000000060", 1),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comparison_report() -> Result<(), Box<dyn error::Error>> {
+        let body = body(serde_json::from_str(include_str!(
+            "../../tests/entitlement_files/subscriptions.json"
+        ))?)?;
+        // 2025-01-15T00:00:00Z
+        let at = DateTime::from_timestamp_millis(1736899200000).unwrap();
+        let old = Entitlements::from_response_body(&body, &at);
+
+        let transactions = body
+            .latest_receipt_info
+            .iter()
+            .flatten()
+            .map(|x| {
+                let t = &x.transaction;
+                let mut value = serde_json::json!({
+                    "transactionId": t.transaction_id,
+                    "originalTransactionId": t.original_transaction_id,
+                    "bundleId": "com.example.app",
+                    "productId": t.product_id,
+                    "subscriptionGroupIdentifier": x.subscription_group_identifier,
+                    "purchaseDate": t.purchase_date.timestamp_millis(),
+                    "originalPurchaseDate": t.original_purchase_date.timestamp_millis(),
+                    "expiresDate": t.expires_date.map(|x| x.timestamp_millis()),
+                    "revocationDate": t.cancellation_date.map(|x| x.timestamp_millis()),
+                    "isUpgraded": x.is_upgraded,
+                    "type": "Auto-Renewable Subscription",
+                    "inAppOwnershipType": "PURCHASED",
+                    "signedDate": 1736899200000_i64,
+                    "environment": "Sandbox",
+                });
+                value.as_object_mut().expect("").retain(|_, v| !v.is_null());
+                serde_json::from_value(value)
+            })
+            .collect::<Result<Vec<JwsTransactionDecodedPayload>, _>>()?;
+        let renewal_infos = body
+            .pending_renewal_info
+            .iter()
+            .flatten()
+            .map(|x| {
+                serde_json::from_value(serde_json::json!({
+                    "originalTransactionId": x.original_transaction_id,
+                    "productId": x.product_id,
+                    "autoRenewStatus": if x.auto_renew_status == Some(true) { 1 } else { 0 },
+                    "gracePeriodExpiresDate": x.grace_period_expires_date.map(|x| x.timestamp_millis()),
+                    "isInBillingRetryPeriod": x.is_in_billing_retry_period,
+                    "signedDate": 1736899200000_i64,
+                    "environment": "Sandbox",
+                }))
+            })
+            .collect::<Result<Vec<JwsRenewalInfoDecodedPayload>, _>>()?;
+
+        let new = entitlements_from_jws_payloads(&transactions, &renewal_infos, &at);
+        let report = ComparisonReport::compare(&old, &new);
+        assert!(report.is_consistent(), "{report:?}");
+        assert_eq!(report.entries.len(), 7);
+
+        // The renewal missing on the new side
+        let new = entitlements_from_jws_payloads(
+            &transactions
+                .iter()
+                .filter(|x| x.transaction_id != "1000000000000013")
+                .cloned()
+                .collect::<Vec<_>>(),
+            &renewal_infos,
+            &at,
+        );
+        let report = ComparisonReport::compare(&old, &new);
+        assert_eq!(
+            report
+                .mismatches()
+                .map(|x| (x.product_id.as_str(), x.outcome))
+                .collect::<Vec<_>>(),
+            vec![("com.example.app.monthly", ComparisonOutcome::StateMismatch)]
+        );
+
+        let report = ComparisonReport::compare(&old, &Entitlements::default());
+        assert!(report
+            .entries
+            .iter()
+            .all(|x| x.outcome == ComparisonOutcome::OnlyInOld));
+
+        Ok(())
+    }
+}
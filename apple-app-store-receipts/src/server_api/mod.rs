@@ -0,0 +1,15 @@
+// https://developer.apple.com/documentation/appstoreserverapi
+
+pub mod auth;
+pub mod endpoints;
+pub mod jws;
+pub mod migration;
+pub mod objects;
+pub mod offer_signature;
+
+pub use auth::BearerTokenSigner;
+pub use jws::{JwsVerifyError, SignedDataVerifier};
+pub use offer_signature::{OfferJwsSigner, PromotionalOffer, PromotionalOfferSigner};
+
+pub const BASE_URL_PRODUCTION: &str = "https://api.storekit.itunes.apple.com";
+pub const BASE_URL_SANDBOX: &str = "https://api.storekit-sandbox.itunes.apple.com";
@@ -0,0 +1,163 @@
+// https://developer.apple.com/documentation/appstoreserverapi/consumptionrequest
+
+use serde::{Deserialize, Serialize};
+
+use super::u8_enum::u8_enum;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsumptionRequest {
+    /// Must be true, Apple rejects the request otherwise.
+    pub customer_consented: bool,
+    pub consumption_status: ConsumptionStatus,
+    pub platform: Platform,
+    pub sample_content_provided: bool,
+    pub delivery_status: DeliveryStatus,
+    /// UUID, or an empty string if the app doesn't set one.
+    pub app_account_token: String,
+    pub account_tenure: AccountTenure,
+    pub play_time: PlayTime,
+    pub lifetime_dollars_refunded: LifetimeDollars,
+    pub lifetime_dollars_purchased: LifetimeDollars,
+    pub user_status: UserStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund_preference: Option<RefundPreference>,
+}
+
+u8_enum! {
+    // https://developer.apple.com/documentation/appstoreserverapi/consumptionstatus
+    pub enum ConsumptionStatus {
+        Undeclared = 0,
+        NotConsumed = 1,
+        PartiallyConsumed = 2,
+        FullyConsumed = 3,
+    }
+}
+
+u8_enum! {
+    // https://developer.apple.com/documentation/appstoreserverapi/platform
+    pub enum Platform {
+        Undeclared = 0,
+        Apple = 1,
+        NonApple = 2,
+    }
+}
+
+u8_enum! {
+    // https://developer.apple.com/documentation/appstoreserverapi/deliverystatus
+    pub enum DeliveryStatus {
+        DeliveredAndWorkingProperly = 0,
+        DidNotDeliverDueToQualityIssue = 1,
+        DeliveredWrongItem = 2,
+        DidNotDeliverDueToServerOutage = 3,
+        DidNotDeliverDueToInGameCurrencyChange = 4,
+        DidNotDeliverForOtherReason = 5,
+    }
+}
+
+u8_enum! {
+    // https://developer.apple.com/documentation/appstoreserverapi/accounttenure
+    pub enum AccountTenure {
+        Undeclared = 0,
+        ZeroToThreeDays = 1,
+        ThreeDaysToTenDays = 2,
+        TenDaysToThirtyDays = 3,
+        ThirtyDaysToNinetyDays = 4,
+        NinetyDaysToOneHundredEightyDays = 5,
+        OneHundredEightyDaysToThreeHundredSixtyFiveDays = 6,
+        GreaterThanThreeHundredSixtyFiveDays = 7,
+    }
+}
+
+u8_enum! {
+    // https://developer.apple.com/documentation/appstoreserverapi/playtime
+    pub enum PlayTime {
+        Undeclared = 0,
+        ZeroToFiveMinutes = 1,
+        FiveToSixtyMinutes = 2,
+        OneToSixHours = 3,
+        SixHoursToTwentyFourHours = 4,
+        OneDayToFourDays = 5,
+        FourDaysToSixteenDays = 6,
+        OverSixteenDays = 7,
+    }
+}
+
+u8_enum! {
+    // https://developer.apple.com/documentation/appstoreserverapi/lifetimedollarsrefunded
+    // https://developer.apple.com/documentation/appstoreserverapi/lifetimedollarspurchased
+    pub enum LifetimeDollars {
+        Undeclared = 0,
+        ZeroUsd = 1,
+        OneCentToFortyNineDollarsAndNinetyNineCents = 2,
+        FiftyDollarsToNinetyNineDollarsAndNinetyNineCents = 3,
+        OneHundredDollarsToFourHundredNinetyNineDollarsAndNinetyNineCents = 4,
+        FiveHundredDollarsToNineHundredNinetyNineDollarsAndNinetyNineCents = 5,
+        OneThousandDollarsToOneThousandNineHundredNinetyNineDollarsAndNinetyNineCents = 6,
+        TwoThousandDollarsOrGreater = 7,
+    }
+}
+
+u8_enum! {
+    // https://developer.apple.com/documentation/appstoreserverapi/userstatus
+    pub enum UserStatus {
+        Undeclared = 0,
+        Active = 1,
+        Suspended = 2,
+        Terminated = 3,
+        LimitedAccess = 4,
+    }
+}
+
+u8_enum! {
+    // https://developer.apple.com/documentation/appstoreserverapi/refundpreference
+    pub enum RefundPreference {
+        Undeclared = 0,
+        PreferGrant = 1,
+        PreferDecline = 2,
+        NoPreference = 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_ser() -> Result<(), Box<dyn error::Error>> {
+        let req = ConsumptionRequest {
+            customer_consented: true,
+            consumption_status: ConsumptionStatus::PartiallyConsumed,
+            platform: Platform::Apple,
+            sample_content_provided: false,
+            delivery_status: DeliveryStatus::DeliveredAndWorkingProperly,
+            app_account_token: "".to_owned(),
+            account_tenure: AccountTenure::ThirtyDaysToNinetyDays,
+            play_time: PlayTime::OneToSixHours,
+            lifetime_dollars_refunded: LifetimeDollars::ZeroUsd,
+            lifetime_dollars_purchased: LifetimeDollars::Other(9),
+            user_status: UserStatus::Active,
+            refund_preference: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&req)?,
+            serde_json::json!({
+                "customerConsented": true,
+                "consumptionStatus": 2,
+                "platform": 1,
+                "sampleContentProvided": false,
+                "deliveryStatus": 0,
+                "appAccountToken": "",
+                "accountTenure": 4,
+                "playTime": 3,
+                "lifetimeDollarsRefunded": 1,
+                "lifetimeDollarsPurchased": 9,
+                "userStatus": 1
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+// https://developer.apple.com/documentation/appstoreserverapi/environment
+
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq, Default)]
+pub enum Environment {
+    #[default]
+    Production,
+    Sandbox,
+    Xcode,
+    LocalTesting,
+    #[serde(other)]
+    Other(String),
+}
@@ -0,0 +1,37 @@
+// https://developer.apple.com/documentation/appstoreserverapi/error_codes
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ErrorResponse {
+    #[serde(rename = "errorCode")]
+    pub error_code: u64,
+    #[serde(rename = "errorMessage")]
+    pub error_message: String,
+}
+
+impl ErrorResponse {
+    // AccountNotFoundRetryableError, AppNotFoundRetryableError,
+    // OriginalTransactionIdNotFoundRetryableError, GeneralInternalRetryableError
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.error_code, 4040002 | 4040004 | 4040006 | 5000001)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_de() -> Result<(), Box<dyn error::Error>> {
+        let err: ErrorResponse = serde_json::from_str(
+            r#"{"errorCode":4040010,"errorMessage":"Transaction id not found."}"#,
+        )?;
+        assert_eq!(err.error_code, 4040010);
+        assert!(!err.is_retryable());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+// https://developer.apple.com/documentation/appstoreserverapi/extendrenewaldaterequest
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{timestamp_millis, u8_enum::u8_enum};
+
+// Apple rejects more than 90 days.
+pub const EXTEND_BY_DAYS_MAX: u8 = 90;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendRenewalDateRequest {
+    pub extend_by_days: u8,
+    pub extend_reason_code: ExtendReasonCode,
+    /// Unique per request, up to 128 characters, e.g. a UUID.
+    pub request_identifier: String,
+}
+
+u8_enum! {
+    // https://developer.apple.com/documentation/appstoreserverapi/extendreasoncode
+    pub enum ExtendReasonCode {
+        Undeclared = 0,
+        CustomerSatisfaction = 1,
+        OtherReasons = 2,
+        ServiceIssueOrOutage = 3,
+    }
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/extendrenewaldateresponse
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendRenewalDateResponse {
+    pub original_transaction_id: String,
+    pub web_order_line_item_id: String,
+    pub success: bool,
+    #[serde(
+        default,
+        with = "timestamp_millis::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub effective_date: Option<DateTime<Utc>>,
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/massextendrenewaldaterequest
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MassExtendRenewalDateRequest {
+    pub extend_by_days: u8,
+    pub extend_reason_code: ExtendReasonCode,
+    /// Unique per request, up to 128 characters, e.g. a UUID.
+    pub request_identifier: String,
+    pub product_id: String,
+    /// ISO 3166-1 alpha-3 codes, all storefronts if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub storefront_country_codes: Vec<String>,
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/massextendrenewaldateresponse
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MassExtendRenewalDateResponse {
+    pub request_identifier: String,
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/massextendrenewaldatestatusresponse
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MassExtendRenewalDateStatusResponse {
+    pub request_identifier: String,
+    pub complete: bool,
+    #[serde(
+        default,
+        with = "timestamp_millis::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub complete_date: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub succeeded_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_count: Option<u64>,
+}
@@ -0,0 +1,39 @@
+// https://developer.apple.com/documentation/appstoreserverapi/historyresponse
+
+use serde::{Deserialize, Serialize};
+
+use super::environment::Environment;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct HistoryResponse {
+    pub revision: String,
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+    #[serde(rename = "bundleId")]
+    pub bundle_id: String,
+    #[serde(rename = "appAppleId", skip_serializing_if = "Option::is_none")]
+    pub app_apple_id: Option<u64>,
+    pub environment: Environment,
+    #[serde(rename = "signedTransactions")]
+    pub signed_transactions: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_de() -> Result<(), Box<dyn error::Error>> {
+        let res: HistoryResponse = serde_json::from_str(include_str!(
+            "../../../tests/server_api_files/history_response.json"
+        ))?;
+        assert_eq!(res.revision, "1736899200000_1000000000000013");
+        assert!(res.has_more);
+        assert_eq!(res.environment, Environment::Sandbox);
+        assert_eq!(res.signed_transactions.len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+// https://developer.apple.com/documentation/appstoreserverapi/inappownershiptype
+
+// Same values as the in_app_ownership_type of verifyReceipt.
+pub use crate::objects::response_body::InAppOwnershipType;
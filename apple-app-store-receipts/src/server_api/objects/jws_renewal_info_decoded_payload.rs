@@ -0,0 +1,121 @@
+// https://developer.apple.com/documentation/appstoreserverapi/jwsrenewalinfodecodedpayload
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    local_receipt::decode::to_pst_string,
+    objects::response_body::{ExpirationIntent, PendingRenewalInfo, PriceConsentStatus},
+};
+
+use super::{
+    environment::Environment,
+    offer_type::{OfferDiscountType, OfferType},
+    timestamp_millis,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JwsRenewalInfoDecodedPayload {
+    pub original_transaction_id: String,
+    pub product_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_renew_product_id: Option<String>,
+    // https://developer.apple.com/documentation/appstoreserverapi/autorenewstatus
+    pub auto_renew_status: u8,
+    // https://developer.apple.com/documentation/appstoreserverapi/expirationintent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_intent: Option<u8>,
+    #[serde(
+        default,
+        with = "timestamp_millis::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub grace_period_expires_date: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_in_billing_retry_period: Option<bool>,
+    // https://developer.apple.com/documentation/appstoreserverapi/priceincreasestatus
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_increase_status: Option<u8>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_type: Option<OfferType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_discount_type: Option<OfferDiscountType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_period: Option<String>,
+
+    #[serde(with = "timestamp_millis")]
+    pub signed_date: DateTime<Utc>,
+    pub environment: Environment,
+    #[serde(
+        default,
+        with = "timestamp_millis::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub recent_subscription_start_date: Option<DateTime<Utc>>,
+    #[serde(
+        default,
+        with = "timestamp_millis::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub renewal_date: Option<DateTime<Utc>>,
+    // In milliunits of the currency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renewal_price: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_account_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_transaction_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub eligible_win_back_offer_ids: Vec<String>,
+
+    // Unrecognized fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl JwsRenewalInfoDecodedPayload {
+    /// Same shape as a `pending_renewal_info` item of verifyReceipt.
+    pub fn to_pending_renewal_info(&self) -> PendingRenewalInfo {
+        PendingRenewalInfo {
+            product_id: self.product_id.to_owned(),
+            original_transaction_id: self.original_transaction_id.to_owned(),
+            auto_renew_product_id: self.auto_renew_product_id.to_owned(),
+            auto_renew_status: Some(self.auto_renew_status == 1),
+            expiration_intent: self.expiration_intent.map(|x| match x {
+                1 => ExpirationIntent::CustomerCanceled,
+                2 => ExpirationIntent::BillingError,
+                3 => ExpirationIntent::PriceIncreaseNotConsented,
+                4 => ExpirationIntent::ProductNotAvailable,
+                5 => ExpirationIntent::Unknown,
+                x => ExpirationIntent::Other(x.to_string()),
+            }),
+            grace_period_expires_date: self.grace_period_expires_date,
+            grace_period_expires_date_pst: self
+                .grace_period_expires_date
+                .as_ref()
+                .map(to_pst_string),
+            is_in_billing_retry_period: self.is_in_billing_retry_period,
+            offer_code_ref_name: match self.offer_type {
+                Some(OfferType::OfferCode) => self.offer_identifier.to_owned(),
+                _ => None,
+            },
+            price_consent_status: self.price_increase_status.map(|x| match x {
+                0 => PriceConsentStatus::NotConsented,
+                1 => PriceConsentStatus::Consented,
+                x => PriceConsentStatus::Other(x.to_string()),
+            }),
+            promotional_offer_id: match self.offer_type {
+                Some(OfferType::Promotional) => self.offer_identifier.to_owned(),
+                _ => None,
+            },
+            extra: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+// https://developer.apple.com/documentation/appstoreserverapi/jwstransactiondecodedpayload
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+use serde_json::{Map, Value};
+
+use crate::{
+    local_receipt::decode::to_pst_string,
+    objects::response_body::{LatestReceiptInfo, Transaction},
+};
+
+use super::{
+    environment::Environment,
+    in_app_ownership_type::InAppOwnershipType,
+    offer_type::{OfferDiscountType, OfferType},
+    timestamp_millis,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JwsTransactionDecodedPayload {
+    pub transaction_id: String,
+    pub original_transaction_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_order_line_item_id: Option<String>,
+    pub bundle_id: String,
+    pub product_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_group_identifier: Option<String>,
+
+    #[serde(with = "timestamp_millis")]
+    pub purchase_date: DateTime<Utc>,
+    #[serde(with = "timestamp_millis")]
+    pub original_purchase_date: DateTime<Utc>,
+    #[serde(
+        default,
+        with = "timestamp_millis::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expires_date: Option<DateTime<Utc>>,
+
+    #[serde(default = "quantity_default")]
+    pub quantity: usize,
+    #[serde(rename = "type")]
+    pub r#type: TransactionType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_account_token: Option<String>,
+    pub in_app_ownership_type: InAppOwnershipType,
+    #[serde(with = "timestamp_millis")]
+    pub signed_date: DateTime<Utc>,
+
+    // https://developer.apple.com/documentation/appstoreserverapi/revocationreason
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revocation_reason: Option<u8>,
+    #[serde(
+        default,
+        with = "timestamp_millis::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub revocation_date: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_upgraded: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_type: Option<OfferType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_discount_type: Option<OfferDiscountType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_period: Option<String>,
+
+    pub environment: Environment,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storefront: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storefront_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_reason: Option<TransactionReason>,
+    // In milliunits of the currency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_transaction_id: Option<String>,
+
+    // Unrecognized fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+fn quantity_default() -> usize {
+    1
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/type
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum TransactionType {
+    #[serde(rename = "Auto-Renewable Subscription")]
+    AutoRenewableSubscription,
+    #[serde(rename = "Non-Consumable")]
+    NonConsumable,
+    #[serde(rename = "Consumable")]
+    Consumable,
+    #[serde(rename = "Non-Renewing Subscription")]
+    NonRenewingSubscription,
+    #[serde(other)]
+    Other(String),
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/transactionreason
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum TransactionReason {
+    #[serde(rename = "PURCHASE")]
+    Purchase,
+    #[serde(rename = "RENEWAL")]
+    Renewal,
+    #[serde(other)]
+    Other(String),
+}
+
+impl JwsTransactionDecodedPayload {
+    /// Same shape as an `in_app` / `latest_receipt_info` item of verifyReceipt.
+    pub fn to_transaction(&self) -> Transaction {
+        let is_introductory = self.offer_type == Some(OfferType::Introductory);
+        let is_free_trial = self.offer_discount_type == Some(OfferDiscountType::FreeTrial);
+
+        Transaction {
+            quantity: self.quantity,
+            product_id: self.product_id.to_owned(),
+            transaction_id: self.transaction_id.to_owned(),
+            original_transaction_id: self.original_transaction_id.to_owned(),
+            purchase_date: self.purchase_date,
+            purchase_date_pst: to_pst_string(&self.purchase_date),
+            original_purchase_date: self.original_purchase_date,
+            original_purchase_date_pst: to_pst_string(&self.original_purchase_date),
+            expires_date: self.expires_date,
+            expires_date_pst: self.expires_date.as_ref().map(to_pst_string),
+            cancellation_date: self.revocation_date,
+            cancellation_date_pst: self.revocation_date.as_ref().map(to_pst_string),
+            cancellation_reason: self.revocation_reason.map(|x| x.to_string()),
+            web_order_line_item_id: self.web_order_line_item_id.to_owned(),
+            is_trial_period: Some(is_introductory && is_free_trial),
+            promotional_offer_id: match self.offer_type {
+                Some(OfferType::Promotional) => self.offer_identifier.to_owned(),
+                _ => None,
+            },
+            is_in_intro_offer_period: Some(is_introductory && !is_free_trial),
+            in_app_ownership_type: Some(self.in_app_ownership_type.to_owned()),
+            offer_code_ref_name: match self.offer_type {
+                Some(OfferType::OfferCode) => self.offer_identifier.to_owned(),
+                _ => None,
+            },
+            app_account_token: self.app_account_token.to_owned(),
+            extra: Default::default(),
+        }
+    }
+
+    pub fn to_latest_receipt_info(&self) -> LatestReceiptInfo {
+        LatestReceiptInfo {
+            transaction: self.to_transaction(),
+            subscription_group_identifier: self.subscription_group_identifier.to_owned(),
+            is_upgraded: self.is_upgraded,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_de_and_to_transaction() -> Result<(), Box<dyn error::Error>> {
+        let payload: JwsTransactionDecodedPayload = serde_json::from_str(
+            r#"{
+                "transactionId": "1000000000000001",
+                "originalTransactionId": "1000000000000001",
+                "bundleId": "com.example.app",
+                "productId": "com.example.app.coins100",
+                "purchaseDate": 1736503200000,
+                "originalPurchaseDate": 1736503200000,
+                "type": "Consumable",
+                "inAppOwnershipType": "PURCHASED",
+                "signedDate": 1736899200000,
+                "revocationReason": 1,
+                "revocationDate": 1736848800000,
+                "environment": "Production",
+                "futureField": 1
+            }"#,
+        )?;
+        assert_eq!(payload.quantity, 1);
+        assert_eq!(payload.r#type, TransactionType::Consumable);
+        assert_eq!(payload.extra.get("futureField"), Some(&Value::from(1)));
+
+        let transaction = payload.to_transaction();
+        assert_eq!(transaction.product_id, "com.example.app.coins100");
+        assert_eq!(
+            transaction.in_app_ownership_type,
+            Some(InAppOwnershipType::Purchased)
+        );
+        assert_eq!(
+            transaction.purchase_date_pst,
+            "2025-01-10 02:00:00 America/Los_Angeles"
+        );
+        assert_eq!(
+            transaction.cancellation_date,
+            DateTime::from_timestamp(1736848800, 0)
+        );
+        assert_eq!(transaction.cancellation_reason.as_deref(), Some("1"));
+        assert_eq!(transaction.expires_date, None);
+        assert_eq!(transaction.is_trial_period, Some(false));
+
+        let value = serde_json::to_value(&payload)?;
+        assert_eq!(value["purchaseDate"], Value::from(1736503200000_i64));
+        assert_eq!(value["futureField"], Value::from(1));
+        assert!(value.get("expiresDate").is_none());
+
+        Ok(())
+    }
+}
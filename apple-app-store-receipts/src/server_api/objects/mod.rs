@@ -0,0 +1,40 @@
+pub mod consumption_request;
+pub mod environment;
+pub mod error_response;
+pub mod extend_renewal_date;
+pub mod history_response;
+pub mod in_app_ownership_type;
+pub mod jws_renewal_info_decoded_payload;
+pub mod jws_transaction_decoded_payload;
+pub mod offer_type;
+pub mod order_lookup_response;
+pub mod refund_history_response;
+pub mod status_response;
+pub(crate) mod timestamp_millis;
+pub mod transaction_info_response;
+pub(crate) mod u8_enum;
+
+pub use consumption_request::{
+    AccountTenure, ConsumptionRequest, ConsumptionStatus, DeliveryStatus, LifetimeDollars,
+    Platform, PlayTime, RefundPreference, UserStatus,
+};
+pub use environment::Environment;
+pub use error_response::ErrorResponse;
+pub use extend_renewal_date::{
+    ExtendReasonCode, ExtendRenewalDateRequest, ExtendRenewalDateResponse,
+    MassExtendRenewalDateRequest, MassExtendRenewalDateResponse,
+    MassExtendRenewalDateStatusResponse,
+};
+pub use history_response::HistoryResponse;
+pub use in_app_ownership_type::InAppOwnershipType;
+pub use jws_renewal_info_decoded_payload::JwsRenewalInfoDecodedPayload;
+pub use jws_transaction_decoded_payload::{
+    JwsTransactionDecodedPayload, TransactionReason, TransactionType,
+};
+pub use offer_type::{OfferDiscountType, OfferType};
+pub use order_lookup_response::{OrderLookupResponse, OrderLookupStatus};
+pub use refund_history_response::RefundHistoryResponse;
+pub use status_response::{
+    LastTransactionsItem, StatusResponse, SubscriptionGroupIdentifierItem, SubscriptionStatus,
+};
+pub use transaction_info_response::TransactionInfoResponse;
@@ -0,0 +1,27 @@
+// https://developer.apple.com/documentation/appstoreserverapi/offertype
+
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+
+use super::u8_enum::u8_enum;
+
+u8_enum! {
+    pub enum OfferType {
+        Introductory = 1,
+        Promotional = 2,
+        OfferCode = 3,
+        WinBack = 4,
+    }
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/offerdiscounttype
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum OfferDiscountType {
+    #[serde(rename = "FREE_TRIAL")]
+    FreeTrial,
+    #[serde(rename = "PAY_AS_YOU_GO")]
+    PayAsYouGo,
+    #[serde(rename = "PAY_UP_FRONT")]
+    PayUpFront,
+    #[serde(other)]
+    Other(String),
+}
@@ -0,0 +1,64 @@
+// https://developer.apple.com/documentation/appstoreserverapi/orderlookupresponse
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OrderLookupResponse {
+    pub status: OrderLookupStatus,
+    #[serde(rename = "signedTransactions")]
+    pub signed_transactions: Vec<String>,
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/orderlookupstatus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderLookupStatus {
+    Valid,
+    Invalid,
+}
+
+impl<'de> Deserialize<'de> for OrderLookupStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(Self::Valid),
+            1 => Ok(Self::Invalid),
+            v => Err(de::Error::custom(format!(
+                "unknown order lookup status [{v}]"
+            ))),
+        }
+    }
+}
+
+impl Serialize for OrderLookupStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(match self {
+            Self::Valid => 0,
+            Self::Invalid => 1,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_de() -> Result<(), Box<dyn error::Error>> {
+        let res: OrderLookupResponse =
+            serde_json::from_str(r#"{"status":1,"signedTransactions":[]}"#)?;
+        assert_eq!(res.status, OrderLookupStatus::Invalid);
+        assert!(serde_json::from_str::<OrderLookupResponse>(
+            r#"{"status":2,"signedTransactions":[]}"#
+        )
+        .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+// https://developer.apple.com/documentation/appstoreserverapi/refundhistoryresponse
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RefundHistoryResponse {
+    pub revision: String,
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+    #[serde(rename = "signedTransactions")]
+    pub signed_transactions: Vec<String>,
+}
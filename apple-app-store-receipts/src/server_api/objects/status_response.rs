@@ -0,0 +1,75 @@
+// https://developer.apple.com/documentation/appstoreserverapi/statusresponse
+
+use serde::{Deserialize, Serialize};
+
+use super::{environment::Environment, u8_enum::u8_enum};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct StatusResponse {
+    pub environment: Environment,
+    #[serde(rename = "bundleId")]
+    pub bundle_id: String,
+    #[serde(rename = "appAppleId", skip_serializing_if = "Option::is_none")]
+    pub app_apple_id: Option<u64>,
+    pub data: Vec<SubscriptionGroupIdentifierItem>,
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/subscriptiongroupidentifieritem
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SubscriptionGroupIdentifierItem {
+    #[serde(rename = "subscriptionGroupIdentifier")]
+    pub subscription_group_identifier: String,
+    #[serde(rename = "lastTransactions")]
+    pub last_transactions: Vec<LastTransactionsItem>,
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/lasttransactionsitem
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct LastTransactionsItem {
+    pub status: SubscriptionStatus,
+    #[serde(rename = "originalTransactionId")]
+    pub original_transaction_id: String,
+    #[serde(rename = "signedTransactionInfo")]
+    pub signed_transaction_info: String,
+    #[serde(rename = "signedRenewalInfo")]
+    pub signed_renewal_info: String,
+}
+
+// https://developer.apple.com/documentation/appstoreserverapi/status
+u8_enum! {
+    pub enum SubscriptionStatus {
+        Active = 1,
+        Expired = 2,
+        BillingRetry = 3,
+        BillingGracePeriod = 4,
+        Revoked = 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_de() -> Result<(), Box<dyn error::Error>> {
+        let res: StatusResponse = serde_json::from_str(include_str!(
+            "../../../tests/server_api_files/status_response.json"
+        ))?;
+        assert_eq!(res.environment, Environment::Sandbox);
+        assert_eq!(res.app_apple_id, None);
+        assert_eq!(res.data.len(), 1);
+        let item = &res.data[0];
+        assert_eq!(item.subscription_group_identifier, "20000001");
+        assert_eq!(
+            item.last_transactions
+                .iter()
+                .map(|x| x.status)
+                .collect::<Vec<_>>(),
+            vec![SubscriptionStatus::Active, SubscriptionStatus::Other(9)]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+// The App Store Server API sends dates as UNIX time in milliseconds, numbers not strings.
+
+use chrono::{DateTime, TimeZone as _, Utc};
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v = i64::deserialize(deserializer)?;
+    Utc.timestamp_millis_opt(v)
+        .single()
+        .ok_or_else(|| de::Error::custom(format!("invalid timestamp [{v}]")))
+}
+
+pub(crate) fn serialize<S>(value: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_i64(value.timestamp_millis())
+}
+
+pub(crate) mod option {
+    use super::*;
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(deserialize_with = "super::deserialize")] DateTime<Utc>);
+
+        Option::<Wrapper>::deserialize(deserializer).map(|x| x.map(|x| x.0))
+    }
+
+    pub(crate) fn serialize<S>(
+        value: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(value) => super::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+// https://developer.apple.com/documentation/appstoreserverapi/transactioninforesponse
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TransactionInfoResponse {
+    #[serde(rename = "signedTransactionInfo")]
+    pub signed_transaction_info: String,
+}
@@ -0,0 +1,54 @@
+// For the fields Apple sends as integer codes, unknown codes are kept as `Other`.
+macro_rules! u8_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident = $code:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            Other(u8),
+        }
+
+        impl $name {
+            pub fn code(&self) -> u8 {
+                match self {
+                    $(Self::$variant => $code,)*
+                    Self::Other(v) => *v,
+                }
+            }
+        }
+
+        impl From<u8> for $name {
+            fn from(v: u8) -> Self {
+                match v {
+                    $($code => Self::$variant,)*
+                    v => Self::Other(v),
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                <u8 as serde::Deserialize>::deserialize(deserializer).map(Into::into)
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_u8(self.code())
+            }
+        }
+    };
+}
+
+pub(crate) use u8_enum;
@@ -0,0 +1,344 @@
+// https://developer.apple.com/documentation/storekit/in-app_purchase/original_api_for_in-app_purchase/subscriptions_and_offers/generating_a_signature_for_promotional_offers
+// https://developer.apple.com/documentation/storekit/generating-jws-to-sign-app-store-requests
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use p256::ecdsa::{signature::Signer as _, Signature, SigningKey};
+use serde::Serialize;
+
+use super::auth::{sign_jws_compact, signing_key_from_pem, JwsHeader, SignError};
+
+// INVISIBLE SEPARATOR
+const SEPARATOR: char = '\u{2063}';
+
+pub const AUDIENCE_PROMOTIONAL_OFFER: &str = "promotional-offer";
+pub const AUDIENCE_INTRODUCTORY_OFFER_ELIGIBILITY: &str = "introductory-offer-eligibility";
+pub const AUDIENCE_ADVANCED_COMMERCE_API: &str = "advanced-commerce-api";
+
+//
+#[derive(Debug, Clone)]
+pub struct PromotionalOffer<'a> {
+    pub product_identifier: &'a str,
+    pub offer_identifier: &'a str,
+    /// appAccountToken, empty if the app doesn't set one.
+    pub application_username: &'a str,
+    /// UUID
+    pub nonce: &'a str,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Signature for `SKPaymentDiscount` / `Product.PurchaseOption.promotionalOffer(offerID:keyID:nonce:signature:timestamp:)`.
+#[derive(Debug, Clone)]
+pub struct PromotionalOfferSigner {
+    key_id: Box<str>,
+    bundle_id: Box<str>,
+    signing_key: SigningKey,
+}
+
+impl PromotionalOfferSigner {
+    /// `private_key` is the content of the In-App Purchase key file, i.e. SubscriptionKey_XXXXXXXXXX.p8
+    pub fn new(
+        key_id: impl AsRef<str>,
+        bundle_id: impl AsRef<str>,
+        private_key: impl AsRef<str>,
+    ) -> Result<Self, SignError> {
+        Ok(Self {
+            key_id: key_id.as_ref().into(),
+            bundle_id: bundle_id.as_ref().into(),
+            signing_key: signing_key_from_pem(private_key.as_ref())?,
+        })
+    }
+
+    /// Base64 encoded DER ECDSA signature.
+    pub fn sign(&self, offer: &PromotionalOffer<'_>) -> String {
+        let signature: Signature = self.signing_key.sign(self.payload(offer).as_bytes());
+        STANDARD.encode(signature.to_der().as_bytes())
+    }
+
+    fn payload(&self, offer: &PromotionalOffer<'_>) -> String {
+        [
+            self.bundle_id.as_ref(),
+            self.key_id.as_ref(),
+            offer.product_identifier,
+            offer.offer_identifier,
+            &offer.application_username.to_lowercase(),
+            &offer.nonce.to_lowercase(),
+            &offer.timestamp.timestamp_millis().to_string(),
+        ]
+        .join(&SEPARATOR.to_string())
+    }
+}
+
+//
+//
+//
+/// Signed JWS for the StoreKit purchase options and the Advanced Commerce API.
+#[derive(Debug, Clone)]
+pub struct OfferJwsSigner {
+    issuer_id: Box<str>,
+    key_id: Box<str>,
+    bundle_id: Box<str>,
+    signing_key: SigningKey,
+}
+
+impl OfferJwsSigner {
+    pub fn new(
+        issuer_id: impl AsRef<str>,
+        key_id: impl AsRef<str>,
+        bundle_id: impl AsRef<str>,
+        private_key: impl AsRef<str>,
+    ) -> Result<Self, SignError> {
+        Ok(Self {
+            issuer_id: issuer_id.as_ref().into(),
+            key_id: key_id.as_ref().into(),
+            bundle_id: bundle_id.as_ref().into(),
+            signing_key: signing_key_from_pem(private_key.as_ref())?,
+        })
+    }
+
+    // https://developer.apple.com/documentation/storekit/product/purchaseoption/promotionaloffer(_:compactjws:)
+    pub fn sign_promotional_offer(
+        &self,
+        product_id: &str,
+        offer_identifier: &str,
+        transaction_id: Option<&str>,
+        nonce: &str,
+        now: DateTime<Utc>,
+    ) -> Result<String, SignError> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Claims<'a> {
+            product_id: &'a str,
+            offer_identifier: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            transaction_id: Option<&'a str>,
+        }
+
+        self.sign(
+            AUDIENCE_PROMOTIONAL_OFFER,
+            &Claims {
+                product_id,
+                offer_identifier,
+                transaction_id,
+            },
+            nonce,
+            now,
+        )
+    }
+
+    // https://developer.apple.com/documentation/storekit/product/purchaseoption/introductoryoffereligibility(compactjws:)
+    pub fn sign_introductory_offer_eligibility(
+        &self,
+        product_id: &str,
+        allow_introductory_offer: bool,
+        transaction_id: &str,
+        nonce: &str,
+        now: DateTime<Utc>,
+    ) -> Result<String, SignError> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Claims<'a> {
+            product_id: &'a str,
+            allow_introductory_offer: bool,
+            transaction_id: &'a str,
+        }
+
+        self.sign(
+            AUDIENCE_INTRODUCTORY_OFFER_ELIGIBILITY,
+            &Claims {
+                product_id,
+                allow_introductory_offer,
+                transaction_id,
+            },
+            nonce,
+            now,
+        )
+    }
+
+    /// `request` is an Advanced Commerce API in-app request, sent as base64 encoded JSON.
+    pub fn sign_advanced_commerce_request<T>(
+        &self,
+        request: &T,
+        nonce: &str,
+        now: DateTime<Utc>,
+    ) -> Result<String, SignError>
+    where
+        T: Serialize,
+    {
+        #[derive(Serialize)]
+        struct Claims {
+            request: String,
+        }
+
+        let request = serde_json::to_vec(request).map_err(SignError::SerFailed)?;
+        self.sign(
+            AUDIENCE_ADVANCED_COMMERCE_API,
+            &Claims {
+                request: STANDARD.encode(request),
+            },
+            nonce,
+            now,
+        )
+    }
+
+    fn sign<C>(
+        &self,
+        aud: &str,
+        claims: &C,
+        nonce: &str,
+        now: DateTime<Utc>,
+    ) -> Result<String, SignError>
+    where
+        C: Serialize,
+    {
+        #[derive(Serialize)]
+        struct CommonClaims<'a, C> {
+            iss: &'a str,
+            iat: i64,
+            aud: &'a str,
+            bid: &'a str,
+            nonce: String,
+            #[serde(flatten)]
+            claims: &'a C,
+        }
+
+        let header = JwsHeader {
+            alg: "ES256",
+            kid: Some(&self.key_id),
+            typ: Some("JWT"),
+        };
+        let claims = CommonClaims {
+            iss: &self.issuer_id,
+            iat: now.timestamp(),
+            aud,
+            bid: &self.bundle_id,
+            nonce: nonce.to_lowercase(),
+            claims,
+        };
+
+        sign_jws_compact(&header, &claims, &self.signing_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use p256::ecdsa::{signature::Verifier as _, VerifyingKey};
+    use serde_json::Value;
+
+    const PRIVATE_KEY: &str = include_str!("../../tests/server_api_files/AuthKey_TEST.p8");
+    const NONCE: &str = "5E4B4DA5-6A2C-4A4B-A2F1-2C1D0E9F8A7B";
+
+    #[test]
+    fn test_promotional_offer_signer() -> Result<(), Box<dyn error::Error>> {
+        let signer = PromotionalOfferSigner::new("2X9R4HXF34", "com.example.app", PRIVATE_KEY)?;
+        let offer = PromotionalOffer {
+            product_identifier: "com.example.app.monthly",
+            offer_identifier: "monthly_promo",
+            application_username: "",
+            nonce: NONCE,
+            timestamp: DateTime::from_timestamp(1736899200, 0).unwrap(),
+        };
+
+        let payload = signer.payload(&offer);
+        assert_eq!(
+            payload,
+            "com.example.app\u{2063}2X9R4HXF34\u{2063}com.example.app.monthly\u{2063}monthly_promo\u{2063}\u{2063}5e4b4da5-6a2c-4a4b-a2f1-2c1d0e9f8a7b\u{2063}1736899200000"
+        );
+
+        let signature = signer.sign(&offer);
+        // ECDSA with RFC 6979 nonces is deterministic.
+        assert_eq!(
+            signature,
+            "MEQCIBy5I/go87fq1pqEki/HKuKdgmUcbL24fIYBVoj5ND4bAiAZ8shs1Wnt3osnLbGJTsXCHW5JajKBSaUuCUI0l2KmtQ=="
+        );
+
+        let verifying_key = VerifyingKey::from(&signing_key_from_pem(PRIVATE_KEY)?);
+        let signature = Signature::from_der(&STANDARD.decode(signature)?)?;
+        verifying_key.verify(payload.as_bytes(), &signature)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_offer_jws_signer() -> Result<(), Box<dyn error::Error>> {
+        let signer = OfferJwsSigner::new(
+            "57246542-96fe-1a63-e053-0824d011072a",
+            "2X9R4HXF34",
+            "com.example.app",
+            PRIVATE_KEY,
+        )?;
+        let now = DateTime::from_timestamp(1736899200, 0).unwrap();
+        let verifying_key = VerifyingKey::from(&signing_key_from_pem(PRIVATE_KEY)?);
+
+        let claims = |jws: &str| -> Result<Value, Box<dyn error::Error>> {
+            let (signing_input, signature) = jws.rsplit_once('.').expect("");
+            verifying_key.verify(
+                signing_input.as_bytes(),
+                &Signature::from_slice(&URL_SAFE_NO_PAD.decode(signature)?)?,
+            )?;
+            let (_, claims) = signing_input.split_once('.').expect("");
+            Ok(serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims)?)?)
+        };
+
+        let jws = signer.sign_promotional_offer(
+            "com.example.app.monthly",
+            "monthly_promo",
+            None,
+            NONCE,
+            now,
+        )?;
+        assert_eq!(
+            jws,
+            signer.sign_promotional_offer(
+                "com.example.app.monthly",
+                "monthly_promo",
+                None,
+                NONCE,
+                now
+            )?
+        );
+        assert_eq!(
+            claims(&jws)?,
+            serde_json::json!({
+                "iss": "57246542-96fe-1a63-e053-0824d011072a",
+                "iat": 1736899200,
+                "aud": "promotional-offer",
+                "bid": "com.example.app",
+                "nonce": "5e4b4da5-6a2c-4a4b-a2f1-2c1d0e9f8a7b",
+                "productId": "com.example.app.monthly",
+                "offerIdentifier": "monthly_promo"
+            })
+        );
+
+        let jws = signer.sign_introductory_offer_eligibility(
+            "com.example.app.yearly",
+            false,
+            "1000000000000010",
+            NONCE,
+            now,
+        )?;
+        let value = claims(&jws)?;
+        assert_eq!(value["aud"], "introductory-offer-eligibility");
+        assert_eq!(value["allowIntroductoryOffer"], false);
+        assert_eq!(value["transactionId"], "1000000000000010");
+
+        let jws = signer.sign_advanced_commerce_request(
+            &serde_json::json!({"operation": "CREATE_SUBSCRIPTION"}),
+            NONCE,
+            now,
+        )?;
+        let value = claims(&jws)?;
+        assert_eq!(value["aud"], "advanced-commerce-api");
+        assert_eq!(
+            STANDARD.decode(value["request"].as_str().expect(""))?,
+            br#"{"operation":"CREATE_SUBSCRIPTION"}"#
+        );
+
+        Ok(())
+    }
+}
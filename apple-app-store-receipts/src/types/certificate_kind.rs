@@ -0,0 +1,19 @@
+use core::fmt;
+
+// Apple Worldwide Developer Relations Certification Authority
+pub const OID_APPLE_WWDR_INTERMEDIATE: &str = "1.2.840.113635.100.6.2.1";
+// Mac App Store and iTunes Store Receipt Signing
+pub const OID_APPLE_RECEIPT_SIGNING: &str = "1.2.840.113635.100.6.11.1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateKind {
+    Root,
+    Intermediate,
+    Signer,
+}
+
+impl fmt::Display for CertificateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
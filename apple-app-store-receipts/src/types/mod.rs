@@ -1 +1,2 @@
+pub mod certificate_kind;
 pub mod status;
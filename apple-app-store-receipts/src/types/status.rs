@@ -2,7 +2,7 @@
 
 use std::fmt;
 
-use serde::{de, Deserialize, Deserializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Status {
@@ -21,6 +21,35 @@ pub enum Status {
     InternalDataAccessError(u16),
 }
 
+impl Status {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Success => 0,
+            Self::Error21000 => 21000,
+            Self::Error21001 => 21001,
+            Self::Error21002 => 21002,
+            Self::Error21003 => 21003,
+            Self::Error21004 => 21004,
+            Self::Error21005 => 21005,
+            Self::Error21006 => 21006,
+            Self::Error21007 => 21007,
+            Self::Error21008 => 21008,
+            Self::Error21009 => 21009,
+            Self::Error21010 => 21010,
+            Self::InternalDataAccessError(v) => *v,
+        }
+    }
+}
+
+impl Serialize for Status {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u16(self.code())
+    }
+}
+
 impl<'de> Deserialize<'de> for Status {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -46,29 +75,90 @@ impl<'de> Deserialize<'de> for Status {
     }
 }
 
-impl fmt::Display for Status {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusClass {
+    Success,
+    /// The request or the receipt is bad, retrying won't help.
+    ClientError,
+    /// e.g. the shared secret mismatch.
+    ConfigurationError,
+    /// Sent to production but from sandbox, or the reverse.
+    WrongEnvironment,
+    /// Try again.
+    Transient,
+    /// The receipt is valid but the subscription has expired.
+    SubscriptionExpired,
+}
+
+impl Status {
+    pub fn class(&self) -> StatusClass {
+        match self {
+            Self::Success => StatusClass::Success,
+            Self::Error21000 | Self::Error21001 | Self::Error21003 | Self::Error21010 => {
+                StatusClass::ClientError
+            }
+            Self::Error21004 => StatusClass::ConfigurationError,
+            Self::Error21007 | Self::Error21008 => StatusClass::WrongEnvironment,
+            Self::Error21002
+            | Self::Error21005
+            | Self::Error21009
+            | Self::InternalDataAccessError(_) => StatusClass::Transient,
+            Self::Error21006 => StatusClass::SubscriptionExpired,
+        }
+    }
+
+    /// `is_retryable` is the field of the response body, Apple only sets it for 21100-21199.
+    pub fn should_retry(&self, is_retryable: Option<bool>) -> bool {
+        match self {
+            Self::InternalDataAccessError(_) => is_retryable == Some(true),
+            _ => self.class() == StatusClass::Transient,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
         match self {
-            Self::Success => write!(f, "[0] the receipt is valid"),
-            Self::Error21000 => write!(
-                f,
-                "[21000] The request to the App Store was not made using the HTTP POST request method."
-            ),
-            Self::Error21001 => write!(f, "[21001] This status code is no longer sent by the App Store."),
-            Self::Error21002 => write!(f, "[21002] The data in the receipt-data property was malformed or the service experienced a temporary issue. Try again."),
-            Self::Error21003 => write!(f, "[21003] The receipt could not be authenticated."),
-            Self::Error21004 => write!(f, "[21004] The shared secret you provided does not match the shared secret on file for your account."),
-            Self::Error21005 => write!(f, "[21005] The receipt server was temporarily unable to provide the receipt. Try again."),
-            Self::Error21006 => write!(f, "[21006] This receipt is valid but the subscription has expired."),
-            Self::Error21007 => write!(f, "[21007] This receipt is from the test environment, but it was sent to the production environment for verification."),
-            Self::Error21008 => write!(f, "[21008] This receipt is from the production environment, but it was sent to the test environment for verification."),
-            Self::Error21009 => write!(f, "[21009] Internal data access error. Try again later."),
-            Self::Error21010 => write!(f, "[21010] The user account cannot be found or has been deleted."),
-            Self::InternalDataAccessError(v) => write!(f, "[{v}] internal data access errors."),
+            Self::Success => "The receipt is valid.",
+            Self::Error21000 => {
+                "The request to the App Store was not made using the HTTP POST request method."
+            }
+            Self::Error21001 => "This status code is no longer sent by the App Store.",
+            Self::Error21002 => "The data in the receipt-data property was malformed or the service experienced a temporary issue. Try again.",
+            Self::Error21003 => "The receipt could not be authenticated.",
+            Self::Error21004 => "The shared secret you provided does not match the shared secret on file for your account.",
+            Self::Error21005 => "The receipt server was temporarily unable to provide the receipt. Try again.",
+            Self::Error21006 => "This receipt is valid but the subscription has expired.",
+            Self::Error21007 => "This receipt is from the test environment, but it was sent to the production environment for verification.",
+            Self::Error21008 => "This receipt is from the production environment, but it was sent to the test environment for verification.",
+            Self::Error21009 => "Internal data access error. Try again later.",
+            Self::Error21010 => "The user account cannot be found or has been deleted.",
+            Self::InternalDataAccessError(_) => "Internal data access error.",
+        }
+    }
+
+    pub fn detail(&self) -> StatusDetail {
+        StatusDetail {
+            code: self.code(),
+            class: self.class(),
+            description: self.description(),
         }
     }
 }
 
+/// For logs and API responses.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct StatusDetail {
+    pub code: u16,
+    pub class: StatusClass,
+    pub description: &'static str,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code(), self.description())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +220,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_class() -> Result<(), Box<dyn error::Error>> {
+        assert_eq!(Status::Success.class(), StatusClass::Success);
+        assert_eq!(Status::Error21003.class(), StatusClass::ClientError);
+        assert_eq!(Status::Error21004.class(), StatusClass::ConfigurationError);
+        assert_eq!(Status::Error21006.class(), StatusClass::SubscriptionExpired);
+        assert_eq!(Status::Error21007.class(), StatusClass::WrongEnvironment);
+        assert_eq!(Status::Error21008.class(), StatusClass::WrongEnvironment);
+
+        assert!(Status::Error21002.should_retry(None));
+        assert!(Status::Error21005.should_retry(None));
+        assert!(Status::Error21009.should_retry(None));
+        assert!(!Status::Error21004.should_retry(Some(true)));
+        assert!(!Status::InternalDataAccessError(21100).should_retry(None));
+        assert!(Status::InternalDataAccessError(21100).should_retry(Some(true)));
+
+        // Sentence case with a trailing period, like Apple's texts
+        for status in [
+            Status::Success,
+            Status::Error21000,
+            Status::Error21010,
+            Status::InternalDataAccessError(21150),
+        ] {
+            let description = status.description();
+            assert!(description.starts_with(char::is_uppercase), "{description}");
+            assert!(description.ends_with('.'), "{description}");
+        }
+
+        assert_eq!(
+            Status::Error21004.to_string(),
+            "[21004] The shared secret you provided does not match the shared secret on file for your account."
+        );
+        assert_eq!(
+            serde_json::to_value(Status::InternalDataAccessError(21150).detail())?,
+            serde_json::json!({
+                "code": 21150,
+                "class": "transient",
+                "description": "Internal data access error."
+            })
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,70 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::objects::response_body::ResponseBodyWithSuccess;
+
+/// Keyed by `cache_key`. The methods are sync and called from async code, so it must be an
+/// in-process cache that does not block, like `InMemoryVerificationCache`.
+pub trait VerificationCache: Send + Sync {
+    fn get(&self, key: &str, now: &DateTime<Utc>) -> Option<Arc<ResponseBodyWithSuccess>>;
+
+    fn insert(&self, key: String, value: Arc<ResponseBodyWithSuccess>, expires_at: DateTime<Utc>);
+
+    fn remove(&self, key: &str);
+}
+
+type Entries = HashMap<String, (Arc<ResponseBodyWithSuccess>, DateTime<Utc>)>;
+
+#[derive(Debug, Default)]
+pub struct InMemoryVerificationCache {
+    inner: Mutex<Entries>,
+}
+
+impl InMemoryVerificationCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expired entries are only dropped on `get`, call it periodically to free them.
+    pub fn remove_expired(&self, now: &DateTime<Utc>) {
+        self.lock().retain(|_, (_, expires_at)| *expires_at > *now);
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl VerificationCache for InMemoryVerificationCache {
+    fn get(&self, key: &str, now: &DateTime<Utc>) -> Option<Arc<ResponseBodyWithSuccess>> {
+        let mut inner = self.lock();
+        match inner.get(key) {
+            Some((value, expires_at)) if expires_at > now => Some(value.to_owned()),
+            Some(_) => {
+                inner.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: String, value: Arc<ResponseBodyWithSuccess>, expires_at: DateTime<Utc>) {
+        self.lock().insert(key, (value, expires_at));
+    }
+
+    fn remove(&self, key: &str) {
+        self.lock().remove(key);
+    }
+}
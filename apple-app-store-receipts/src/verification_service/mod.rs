@@ -0,0 +1,517 @@
+pub mod cache;
+
+pub use cache::{InMemoryVerificationCache, VerificationCache};
+
+use std::{
+    collections::HashMap,
+    error,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use chrono::{DateTime, Duration, Utc};
+use event_listener::Event;
+use http_api_client::{Client, RetryableClient, RetryableClientRespondEndpointUntilDoneError};
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    endpoints::verify_receipt::{
+        EnvironmentPolicy, ReceiptData, VerifyReceipt, VerifyReceiptError, MAX_RETRY_COUNT_DEFAULT,
+    },
+    objects::response_body::{ResponseBody, ResponseBodyWithSuccess},
+    types::status::Status,
+};
+
+pub const TTL_DEFAULT_SECONDS: i64 = 60 * 60;
+
+/// Wraps `VerifyReceipt`, caches the successful responses and coalesces the concurrent
+/// verifications of the same receipt data into one request.
+pub struct VerificationService<C, K = InMemoryVerificationCache>
+where
+    C: Client,
+{
+    client: C,
+    cache: K,
+    password: String,
+    environment_policy: EnvironmentPolicy,
+    max_retry_count: usize,
+    ttl: Duration,
+    inflights: Mutex<HashMap<String, Arc<Inflight<C::RespondError>>>>,
+    metrics: Metrics,
+}
+
+impl<C> VerificationService<C, InMemoryVerificationCache>
+where
+    C: RetryableClient + Send + Sync,
+{
+    pub fn new(client: C, password: impl AsRef<str>) -> Self {
+        Self::with_cache(client, password, InMemoryVerificationCache::new())
+    }
+}
+
+impl<C, K> VerificationService<C, K>
+where
+    C: RetryableClient + Send + Sync,
+    K: VerificationCache,
+{
+    pub fn with_cache(client: C, password: impl AsRef<str>, cache: K) -> Self {
+        Self {
+            client,
+            cache,
+            password: password.as_ref().to_owned(),
+            environment_policy: Default::default(),
+            max_retry_count: MAX_RETRY_COUNT_DEFAULT,
+            ttl: Duration::seconds(TTL_DEFAULT_SECONDS),
+            inflights: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+
+    pub fn set_environment_policy(&mut self, environment_policy: EnvironmentPolicy) -> &mut Self {
+        self.environment_policy = environment_policy;
+        self
+    }
+
+    pub fn set_max_retry_count(&mut self, max_retry_count: usize) -> &mut Self {
+        self.max_retry_count = max_retry_count;
+        self
+    }
+
+    /// Upper bound of the cache lifetime, shortened to the earliest upcoming `expires_date`.
+    pub fn set_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn cache(&self) -> &K {
+        &self.cache
+    }
+
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// Drops the cached response, e.g. after a server notification for the receipt.
+    pub fn invalidate(&self, receipt_data: &ReceiptData, exclude_old_transactions: Option<bool>) {
+        self.cache
+            .remove(&cache_key(receipt_data, exclude_old_transactions));
+    }
+
+    pub async fn verify(
+        &self,
+        receipt_data: ReceiptData,
+        exclude_old_transactions: Option<bool>,
+    ) -> Result<Arc<ResponseBodyWithSuccess>, VerificationServiceError<C::RespondError>> {
+        let key = cache_key(&receipt_data, exclude_old_transactions);
+
+        if let Some(value) = self.cache.get(&key, &Utc::now()) {
+            self.metrics.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value);
+        }
+
+        let inflight = {
+            let mut inflights = self.inflights.lock().unwrap_or_else(|err| err.into_inner());
+            match inflights.get(&key) {
+                Some(inflight) => Some(inflight.to_owned()),
+                None => {
+                    inflights.insert(key.to_owned(), Arc::new(Inflight::default()));
+                    None
+                }
+            }
+        };
+        if let Some(inflight) = inflight {
+            self.metrics.coalesced.fetch_add(1, Ordering::Relaxed);
+            return inflight.wait().await;
+        }
+
+        self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+        let guard = InflightGuard {
+            inflights: &self.inflights,
+            key: &key,
+            result: None,
+        };
+        let result = self
+            .verify_without_cache(receipt_data, exclude_old_transactions)
+            .await;
+        if let Ok(value) = &result {
+            let now = Utc::now();
+            self.cache.insert(
+                key.to_owned(),
+                value.to_owned(),
+                cache_expires_at(value, &now, self.ttl),
+            );
+        }
+        guard.complete(result)
+    }
+
+    async fn verify_without_cache(
+        &self,
+        receipt_data: ReceiptData,
+        exclude_old_transactions: Option<bool>,
+    ) -> Result<Arc<ResponseBodyWithSuccess>, VerificationServiceError<C::RespondError>> {
+        let mut verify_receipt = VerifyReceipt::new(
+            self.password.to_owned(),
+            receipt_data,
+            exclude_old_transactions,
+        );
+        verify_receipt
+            .set_environment_policy(self.environment_policy)
+            .set_max_retry_count(self.max_retry_count);
+
+        match self
+            .client
+            .respond_endpoint_until_done(&verify_receipt)
+            .await
+            .map_err(|err| VerificationServiceError::VerifyFailed(Arc::new(err)))?
+        {
+            ResponseBody::Success(body) => Ok(Arc::new(*body)),
+            ResponseBody::Error(body) => {
+                Err(VerificationServiceError::StatusNotSuccess(body.status))
+            }
+        }
+    }
+}
+
+pub type VerifyReceiptUntilDoneError<RE> =
+    RetryableClientRespondEndpointUntilDoneError<RE, VerifyReceiptError, VerifyReceiptError>;
+
+/// `RE` is the `RespondError` of the client.
+#[derive(thiserror::Error, Debug)]
+pub enum VerificationServiceError<RE>
+where
+    RE: error::Error + Send + Sync + 'static,
+{
+    /// In an `Arc` because the coalesced requests share it.
+    #[error("VerifyFailed {0}")]
+    VerifyFailed(Arc<VerifyReceiptUntilDoneError<RE>>),
+    #[error("StatusNotSuccess {0}")]
+    StatusNotSuccess(Status),
+    /// The request that this one was coalesced into was dropped before completion.
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+impl<RE> Clone for VerificationServiceError<RE>
+where
+    RE: error::Error + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::VerifyFailed(err) => Self::VerifyFailed(err.to_owned()),
+            Self::StatusNotSuccess(status) => Self::StatusNotSuccess(status.to_owned()),
+            Self::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+// The hash, not the receipt data itself, so that keys can be stored and logged.
+pub fn cache_key(receipt_data: &ReceiptData, exclude_old_transactions: Option<bool>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(receipt_data.data().as_bytes());
+    hasher.update(match exclude_old_transactions {
+        None => b"-",
+        Some(true) => b"t",
+        Some(false) => b"f",
+    });
+    hasher
+        .finalize()
+        .iter()
+        .map(|x| format!("{x:02x}"))
+        .collect()
+}
+
+/// The earliest `expires_date` after `now`, within `now + ttl`.
+pub fn cache_expires_at(
+    body: &ResponseBodyWithSuccess,
+    now: &DateTime<Utc>,
+    ttl: Duration,
+) -> DateTime<Utc> {
+    body.receipt
+        .in_app
+        .iter()
+        .flatten()
+        .map(|x| &x.transaction)
+        .chain(
+            body.latest_receipt_info
+                .iter()
+                .flatten()
+                .map(|x| &x.transaction),
+        )
+        .filter_map(|x| x.expires_date)
+        .filter(|x| x > now)
+        .fold(*now + ttl, |a, b| a.min(b))
+}
+
+//
+//
+//
+#[derive(Debug, Default)]
+struct Metrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+impl Metrics {
+    fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MetricsSnapshot {
+    pub hits: u64,
+    /// Requests sent to the App Store.
+    pub misses: u64,
+    /// Requests that waited for an identical one in flight.
+    pub coalesced: u64,
+}
+
+//
+//
+//
+type InflightResult<RE> = Result<Arc<ResponseBodyWithSuccess>, VerificationServiceError<RE>>;
+
+struct Inflight<RE>
+where
+    RE: error::Error + Send + Sync + 'static,
+{
+    result: Mutex<Option<InflightResult<RE>>>,
+    event: Event,
+}
+
+impl<RE> Default for Inflight<RE>
+where
+    RE: error::Error + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            result: Default::default(),
+            event: Default::default(),
+        }
+    }
+}
+
+impl<RE> Inflight<RE>
+where
+    RE: error::Error + Send + Sync + 'static,
+{
+    fn result(&self) -> Option<InflightResult<RE>> {
+        self.result
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .to_owned()
+    }
+
+    async fn wait(&self) -> InflightResult<RE> {
+        loop {
+            if let Some(result) = self.result() {
+                return result;
+            }
+            let listener = self.event.listen();
+            if let Some(result) = self.result() {
+                return result;
+            }
+            listener.await;
+        }
+    }
+}
+
+// Publishes the result to the waiters, or Cancelled if the verifying future is dropped.
+struct InflightGuard<'a, RE>
+where
+    RE: error::Error + Send + Sync + 'static,
+{
+    inflights: &'a Mutex<HashMap<String, Arc<Inflight<RE>>>>,
+    key: &'a str,
+    result: Option<InflightResult<RE>>,
+}
+
+impl<RE> InflightGuard<'_, RE>
+where
+    RE: error::Error + Send + Sync + 'static,
+{
+    fn complete(mut self, result: InflightResult<RE>) -> InflightResult<RE> {
+        self.result = Some(result.to_owned());
+        result
+    }
+}
+
+impl<RE> Drop for InflightGuard<'_, RE>
+where
+    RE: error::Error + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        let inflight = self
+            .inflights
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(self.key);
+        if let Some(inflight) = inflight {
+            *inflight
+                .result
+                .lock()
+                .unwrap_or_else(|err| err.into_inner()) = Some(
+                self.result
+                    .take()
+                    .unwrap_or(Err(VerificationServiceError::Cancelled)),
+            );
+            inflight.event.notify(usize::MAX);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{io::Error as IoError, sync::atomic::AtomicUsize, time::Duration as StdDuration};
+
+    use futures_lite::future::{block_on, yield_now, zip};
+    use http_api_client::{async_trait, Body, Client, Request, Response};
+
+    const RESPONSE_BODY: &str = include_str!("../../tests/entitlement_files/subscriptions.json");
+
+    #[derive(Default)]
+    struct MyClient {
+        respond_count: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Client for MyClient {
+        type RespondError = IoError;
+
+        async fn respond(
+            &self,
+            _request: Request<Body>,
+        ) -> Result<Response<Body>, Self::RespondError> {
+            self.respond_count.fetch_add(1, Ordering::SeqCst);
+            // Lets the other request start while this one is in flight.
+            yield_now().await;
+            Ok(Response::new(RESPONSE_BODY.as_bytes().to_vec()))
+        }
+    }
+
+    #[async_trait]
+    impl RetryableClient for MyClient {
+        async fn sleep(&self, _dur: StdDuration) {}
+    }
+
+    #[test]
+    fn test_verify() -> Result<(), Box<dyn error::Error>> {
+        let service = VerificationService::new(MyClient::default(), "");
+
+        let (a, b) = block_on(zip(
+            service.verify(ReceiptData::Base64String("MIIT".to_owned()), None),
+            service.verify(ReceiptData::Base64String("MIIT".to_owned()), None),
+        ));
+        assert!(Arc::ptr_eq(&a?, &b?));
+        assert_eq!(service.client.respond_count.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            service.metrics(),
+            MetricsSnapshot {
+                hits: 0,
+                misses: 1,
+                coalesced: 1
+            }
+        );
+        assert!(service.inflights.lock().expect("").is_empty());
+
+        block_on(service.verify(ReceiptData::Base64String("MIIT".to_owned()), None))?;
+        assert_eq!(service.client.respond_count.load(Ordering::SeqCst), 1);
+        assert_eq!(service.metrics().hits, 1);
+
+        block_on(service.verify(ReceiptData::Base64String("MIIT".to_owned()), Some(true)))?;
+        assert_eq!(service.client.respond_count.load(Ordering::SeqCst), 2);
+
+        service.invalidate(&ReceiptData::Base64String("MIIT".to_owned()), None);
+        block_on(service.verify(ReceiptData::Base64String("MIIT".to_owned()), None))?;
+        assert_eq!(service.client.respond_count.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            service.metrics(),
+            MetricsSnapshot {
+                hits: 1,
+                misses: 3,
+                coalesced: 1
+            }
+        );
+
+        Ok(())
+    }
+
+    struct MyFailingClient;
+
+    #[async_trait]
+    impl Client for MyFailingClient {
+        type RespondError = IoError;
+
+        async fn respond(
+            &self,
+            _request: Request<Body>,
+        ) -> Result<Response<Body>, Self::RespondError> {
+            Err(IoError::other("unreachable"))
+        }
+    }
+
+    #[async_trait]
+    impl RetryableClient for MyFailingClient {
+        async fn sleep(&self, _dur: StdDuration) {}
+    }
+
+    #[test]
+    fn test_verify_failed() -> Result<(), Box<dyn error::Error>> {
+        let service = VerificationService::new(MyFailingClient, "");
+
+        match block_on(service.verify(ReceiptData::Base64String("MIIT".to_owned()), None)) {
+            Err(VerificationServiceError::VerifyFailed(err)) => match err.as_ref() {
+                RetryableClientRespondEndpointUntilDoneError::RespondFailed(err) => {
+                    assert_eq!(err.to_string(), "unreachable")
+                }
+                err => panic!("{err:?}"),
+            },
+            x => panic!("{x:?}"),
+        }
+        assert!(service.cache().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_expires_at() -> Result<(), Box<dyn error::Error>> {
+        let body = match serde_json::from_str::<ResponseBody>(RESPONSE_BODY)? {
+            ResponseBody::Success(body) => body,
+            ResponseBody::Error(body) => return Err(format!("unexpected {body:?}").into()),
+        };
+
+        // 2025-01-01, com.example.app.basic expires at 2025-01-10
+        let now = DateTime::from_timestamp(1735689600, 0).ok_or("")?;
+        assert_eq!(
+            cache_expires_at(&body, &now, Duration::days(30)).timestamp_millis(),
+            1736467200000
+        );
+        assert_eq!(
+            cache_expires_at(&body, &now, Duration::hours(1)),
+            now + Duration::hours(1)
+        );
+
+        let now = DateTime::from_timestamp(1893456000, 0).ok_or("")?;
+        assert_eq!(
+            cache_expires_at(&body, &now, Duration::hours(1)),
+            now + Duration::hours(1)
+        );
+
+        let cache = InMemoryVerificationCache::new();
+        cache.insert("k".to_owned(), body.into(), now + Duration::seconds(1));
+        assert!(cache.get("k", &now).is_some());
+        assert!(cache.get("k", &(now + Duration::seconds(1))).is_none());
+        assert!(cache.is_empty());
+
+        Ok(())
+    }
+}
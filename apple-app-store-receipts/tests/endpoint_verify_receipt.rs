@@ -1,8 +1,10 @@
 use std::error;
 
 use apple_app_store_receipts::{
-    endpoints::verify_receipt::{ReceiptData, RetryReason, VerifyReceipt, VerifyReceiptError},
-    objects::response_body::ResponseBody,
+    endpoints::verify_receipt::{
+        EnvironmentPolicy, ReceiptData, RetryReason, VerifyReceipt, VerifyReceiptError,
+    },
+    objects::response_body::{Environment, ResponseBody},
     types::status::Status,
 };
 use http_api_client_endpoint::{
@@ -61,7 +63,10 @@ fn parse_response_with_http_503() -> Result<(), Box<dyn error::Error>> {
         .unwrap();
     match verify_receipt.parse_response(res, None) {
         Ok(Err(reason)) => {
-            assert_eq!(reason, RetryReason::ServiceUnavailable)
+            assert_eq!(
+                reason,
+                RetryReason::ServiceUnavailable(Environment::Production)
+            )
         }
         _ => panic!(),
     }
@@ -161,7 +166,10 @@ fn parse_response_with_21002() -> Result<(), Box<dyn error::Error>> {
         .unwrap();
     match verify_receipt.parse_response(res, None) {
         Ok(Err(reason)) => {
-            assert_eq!(reason, RetryReason::TryAgainWithStatus(Status::Error21002))
+            assert_eq!(
+                reason,
+                RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21002)
+            )
         }
         _ => panic!(),
     }
@@ -183,7 +191,10 @@ fn parse_response_with_many_times_21002() -> Result<(), Box<dyn error::Error>> {
         .unwrap();
     match verify_receipt.parse_response(res, None) {
         Ok(Err(reason)) => {
-            assert_eq!(reason, RetryReason::TryAgainWithStatus(Status::Error21002))
+            assert_eq!(
+                reason,
+                RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21002)
+            )
         }
         _ => panic!(),
     }
@@ -196,11 +207,14 @@ fn parse_response_with_many_times_21002() -> Result<(), Box<dyn error::Error>> {
         res,
         Some(&RetryableEndpointRetry::new(
             1,
-            RetryReason::TryAgainWithStatus(Status::Error21002),
+            RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21002),
         )),
     ) {
         Ok(Err(reason)) => {
-            assert_eq!(reason, RetryReason::TryAgainWithStatus(Status::Error21002))
+            assert_eq!(
+                reason,
+                RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21002)
+            )
         }
         _ => panic!(),
     }
@@ -213,11 +227,14 @@ fn parse_response_with_many_times_21002() -> Result<(), Box<dyn error::Error>> {
         res,
         Some(&RetryableEndpointRetry::new(
             2,
-            RetryReason::TryAgainWithStatus(Status::Error21002),
+            RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21002),
         )),
     ) {
         Ok(Err(reason)) => {
-            assert_eq!(reason, RetryReason::TryAgainWithStatus(Status::Error21002))
+            assert_eq!(
+                reason,
+                RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21002)
+            )
         }
         _ => panic!(),
     }
@@ -230,7 +247,7 @@ fn parse_response_with_many_times_21002() -> Result<(), Box<dyn error::Error>> {
         res,
         Some(&RetryableEndpointRetry::new(
             3,
-            RetryReason::TryAgainWithStatus(Status::Error21002),
+            RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21002),
         )),
     ) {
         Ok(Ok(ResponseBody::Error(body))) => {
@@ -259,7 +276,10 @@ fn parse_response_with_21104_and_retryable() -> Result<(), Box<dyn error::Error>
     match verify_receipt.parse_response(res, None) {
         Ok(Err(reason)) => assert_eq!(
             reason,
-            RetryReason::TryAgainWithStatus(Status::InternalDataAccessError(21104))
+            RetryReason::TryAgainWithStatus(
+                Environment::Production,
+                Status::InternalDataAccessError(21104)
+            )
         ),
         _ => panic!(),
     }
@@ -288,3 +308,214 @@ fn parse_response_with_21104_and_not_retryable() -> Result<(), Box<dyn error::Er
 
     Ok(())
 }
+
+#[test]
+fn render_request_with_environment_policy() -> Result<(), Box<dyn error::Error>> {
+    let mut verify_receipt = VerifyReceipt::new(
+        "pw".to_owned(),
+        ReceiptData::Base64String("foo".to_owned()),
+        None,
+    );
+
+    // ProductionThenSandbox, stays in sandbox for the following retries
+    let req = verify_receipt.render_request(Some(&RetryableEndpointRetry::new(
+        1,
+        RetryReason::GotoSandbox,
+    )))?;
+    assert_eq!(req.uri(), "https://sandbox.itunes.apple.com/verifyReceipt");
+    let req = verify_receipt.render_request(Some(&RetryableEndpointRetry::new(
+        2,
+        RetryReason::TryAgainWithStatus(Environment::Sandbox, Status::Error21002),
+    )))?;
+    assert_eq!(req.uri(), "https://sandbox.itunes.apple.com/verifyReceipt");
+    // Depends on the retry only, not on the previous requests
+    let req = verify_receipt.render_request(Some(&RetryableEndpointRetry::new(
+        1,
+        RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21002),
+    )))?;
+    assert_eq!(req.uri(), "https://buy.itunes.apple.com/verifyReceipt");
+    let req = verify_receipt.render_request(None)?;
+    assert_eq!(req.uri(), "https://buy.itunes.apple.com/verifyReceipt");
+
+    verify_receipt.set_environment_policy(EnvironmentPolicy::SandboxOnly);
+    let req = verify_receipt.render_request(None)?;
+    assert_eq!(req.uri(), "https://sandbox.itunes.apple.com/verifyReceipt");
+
+    verify_receipt.set_environment_policy(EnvironmentPolicy::SandboxThenProduction);
+    let req = verify_receipt.render_request(None)?;
+    assert_eq!(req.uri(), "https://sandbox.itunes.apple.com/verifyReceipt");
+    let req = verify_receipt.render_request(Some(&RetryableEndpointRetry::new(
+        1,
+        RetryReason::GotoProduction,
+    )))?;
+    assert_eq!(req.uri(), "https://buy.itunes.apple.com/verifyReceipt");
+    let req = verify_receipt.render_request(Some(&RetryableEndpointRetry::new(
+        2,
+        RetryReason::ServiceUnavailable(Environment::Production),
+    )))?;
+    assert_eq!(req.uri(), "https://buy.itunes.apple.com/verifyReceipt");
+
+    Ok(())
+}
+
+#[test]
+fn parse_response_with_environment_policy() -> Result<(), Box<dyn error::Error>> {
+    let mut verify_receipt = VerifyReceipt::new(
+        "pw".to_owned(),
+        ReceiptData::Base64String("foo".to_owned()),
+        None,
+    );
+
+    // ProductionOnly refuses sandbox receipts
+    verify_receipt.set_environment_policy(EnvironmentPolicy::ProductionOnly);
+    let res = Response::builder()
+        .status(StatusCode::OK)
+        .body(br#"{"status":21007}"#.to_vec())
+        .unwrap();
+    match verify_receipt.parse_response(res, None) {
+        Ok(Ok(ResponseBody::Error(body))) => assert_eq!(body.status, Status::Error21007),
+        _ => panic!(),
+    }
+
+    // SandboxOnly refuses production receipts
+    verify_receipt.set_environment_policy(EnvironmentPolicy::SandboxOnly);
+    let res = Response::builder()
+        .status(StatusCode::OK)
+        .body(br#"{"status":21008}"#.to_vec())
+        .unwrap();
+    match verify_receipt.parse_response(res, None) {
+        Ok(Ok(ResponseBody::Error(body))) => assert_eq!(body.status, Status::Error21008),
+        _ => panic!(),
+    }
+
+    verify_receipt.set_environment_policy(EnvironmentPolicy::SandboxThenProduction);
+    let res = Response::builder()
+        .status(StatusCode::OK)
+        .body(br#"{"status":21008}"#.to_vec())
+        .unwrap();
+    match verify_receipt.parse_response(res, None) {
+        Ok(Err(reason)) => assert_eq!(reason, RetryReason::GotoProduction),
+        _ => panic!(),
+    }
+
+    // The environment of the failed attempt is kept for the next retry
+    let res = Response::builder()
+        .status(StatusCode::OK)
+        .body(br#"{"status":21002}"#.to_vec())
+        .unwrap();
+    match verify_receipt.parse_response(
+        res,
+        Some(&RetryableEndpointRetry::new(1, RetryReason::GotoProduction)),
+    ) {
+        Ok(Err(reason)) => assert_eq!(
+            reason,
+            RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21002)
+        ),
+        _ => panic!(),
+    }
+
+    // ProductionThenSandbox does not handle 21008
+    verify_receipt.set_environment_policy(EnvironmentPolicy::ProductionThenSandbox);
+    let res = Response::builder()
+        .status(StatusCode::OK)
+        .body(br#"{"status":21008}"#.to_vec())
+        .unwrap();
+    match verify_receipt.parse_response(res, None) {
+        Ok(Ok(ResponseBody::Error(body))) => assert_eq!(body.status, Status::Error21008),
+        _ => panic!(),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_response_with_max_retry_count() -> Result<(), Box<dyn error::Error>> {
+    let mut verify_receipt = VerifyReceipt::new(
+        "pw".to_owned(),
+        ReceiptData::Base64String("foo".to_owned()),
+        None,
+    );
+    verify_receipt.set_max_retry_count(1);
+    assert_eq!(verify_receipt.max_retry_count(), 1);
+
+    let res = Response::builder()
+        .status(StatusCode::OK)
+        .body(br#"{"status":21005}"#.to_vec())
+        .unwrap();
+    match verify_receipt.parse_response(
+        res,
+        Some(&RetryableEndpointRetry::new(
+            1,
+            RetryReason::TryAgainWithStatus(Environment::Production, Status::Error21005),
+        )),
+    ) {
+        Ok(Ok(ResponseBody::Error(body))) => assert_eq!(body.status, Status::Error21005),
+        _ => panic!(),
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "with-base64")]
+#[test]
+fn receipt_data_from_base64() -> Result<(), Box<dyn error::Error>> {
+    use apple_app_store_receipts::endpoints::verify_receipt::ReceiptDataError;
+    use base64::{engine::general_purpose, Engine as _};
+
+    let bytes = include_bytes!("local_receipt_files/receipt.der");
+    let standard = general_purpose::STANDARD.encode(bytes);
+
+    for s in [
+        standard.to_owned(),
+        general_purpose::URL_SAFE.encode(bytes),
+        general_purpose::URL_SAFE_NO_PAD.encode(bytes),
+        format!(" {}\r\n{}\n", &standard[..64], &standard[64..]),
+    ] {
+        let receipt_data = ReceiptData::from_base64(s)?;
+        assert_eq!(receipt_data.data(), standard);
+    }
+
+    let receipt_data = ReceiptData::from_bytes(bytes.to_vec())?;
+    assert_eq!(receipt_data.data(), standard);
+
+    assert_eq!(
+        ReceiptData::from_base64("").err(),
+        Some(ReceiptDataError::Empty)
+    );
+    assert!(matches!(
+        ReceiptData::from_base64("foo!").err(),
+        Some(ReceiptDataError::Base64DecodeFailed(_))
+    ));
+    // Padding in the middle
+    assert!(matches!(
+        ReceiptData::from_base64(format!("{}={}", &standard[..64], &standard[64..])).err(),
+        Some(ReceiptDataError::Base64DecodeFailed(_))
+    ));
+    // Mixed alphabets
+    let half = standard.len() / 2;
+    let mixed = format!(
+        "{}{}",
+        &general_purpose::URL_SAFE.encode(bytes)[..half],
+        &standard[half..]
+    );
+    assert!(mixed.contains(['-', '_']) && mixed.contains(['+', '/']));
+    assert!(matches!(
+        ReceiptData::from_base64(mixed).err(),
+        Some(ReceiptDataError::Base64DecodeFailed(_))
+    ));
+    assert_eq!(
+        ReceiptData::from_base64(general_purpose::STANDARD.encode(b"not a receipt")).err(),
+        Some(ReceiptDataError::NotPkcs7SignedData)
+    );
+    // Truncated
+    assert_eq!(
+        ReceiptData::from_bytes(&bytes[..bytes.len() - 1]).err(),
+        Some(ReceiptDataError::NotPkcs7SignedData)
+    );
+    assert_eq!(
+        ReceiptData::from_base64_with_max_size(&standard, 1024).err(),
+        Some(ReceiptDataError::TooLarge(bytes.len()))
+    );
+
+    Ok(())
+}
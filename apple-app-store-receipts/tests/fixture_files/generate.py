@@ -0,0 +1,92 @@
+#!/usr/bin/env python3
+# Generates a self-generated test CA (NOT Apple) for signing the receipts built by `fixture`.
+#
+# Requires `cryptography`, run from this directory:
+#   python3 generate.py
+
+import datetime
+
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import rsa
+from cryptography.x509.oid import NameOID, ObjectIdentifier
+
+# Apple Worldwide Developer Relations Certification Authority
+OID_APPLE_WWDR_INTERMEDIATE = "1.2.840.113635.100.6.2.1"
+# Mac App Store and iTunes Store Receipt Signing
+OID_APPLE_RECEIPT_SIGNING = "1.2.840.113635.100.6.11.1"
+
+
+def name(cn):
+    return x509.Name(
+        [
+            x509.NameAttribute(NameOID.COMMON_NAME, cn),
+            x509.NameAttribute(NameOID.ORGANIZATION_NAME, "Example Fixture CA"),
+        ]
+    )
+
+
+def utc(*args):
+    return datetime.datetime(*args, tzinfo=datetime.timezone.utc)
+
+
+def key():
+    return rsa.generate_private_key(public_exponent=65537, key_size=2048)
+
+
+def cert(subject, subject_key, issuer, issuer_key, ca, oid=None):
+    builder = (
+        x509.CertificateBuilder()
+        .subject_name(subject)
+        .issuer_name(issuer)
+        .public_key(subject_key.public_key())
+        .serial_number(x509.random_serial_number())
+        .not_valid_before(utc(2000, 1, 1))
+        .not_valid_after(utc(2099, 1, 1))
+        .add_extension(x509.BasicConstraints(ca=ca, path_length=None), critical=True)
+    )
+    if oid:
+        # The value of Apple's marker extensions is NULL
+        builder = builder.add_extension(
+            x509.UnrecognizedExtension(ObjectIdentifier(oid), b"\x05\x00"), critical=False
+        )
+    return builder.sign(issuer_key, hashes.SHA256())
+
+
+def write(path, data):
+    with open(path, "wb") as f:
+        f.write(data)
+
+
+def main():
+    root_key = key()
+    root_name = name("Fixture Root CA")
+    root = cert(root_name, root_key, root_name, root_key, True)
+
+    intermediate_key = key()
+    intermediate_name = name("Fixture Intermediate CA")
+    intermediate = cert(
+        intermediate_name, intermediate_key, root_name, root_key, True, OID_APPLE_WWDR_INTERMEDIATE
+    )
+
+    signer_key = key()
+    signer_name = name("Fixture Receipt Signing")
+    signer = cert(
+        signer_name, signer_key, intermediate_name, intermediate_key, False, OID_APPLE_RECEIPT_SIGNING
+    )
+
+    write("root_ca.der", root.public_bytes(serialization.Encoding.DER))
+    write("intermediate_ca.der", intermediate.public_bytes(serialization.Encoding.DER))
+    write("signer.der", signer.public_bytes(serialization.Encoding.DER))
+    write(
+        "signer_key.der",
+        signer_key.private_bytes(
+            serialization.Encoding.DER,
+            serialization.PrivateFormat.PKCS8,
+            serialization.NoEncryption(),
+        ),
+    )
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,281 @@
+#!/usr/bin/env python3
+# Generates synthetic App Store receipts signed by a self-generated test CA.
+#
+# Requires `cryptography`, run from this directory:
+#   python3 generate.py
+
+import datetime
+import hashlib
+import uuid
+
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import rsa
+from cryptography.hazmat.primitives.serialization import pkcs7
+from cryptography.x509.oid import NameOID, ObjectIdentifier
+
+DEVICE_ID = uuid.UUID("3e2b5a2c-7c11-4d6b-9a3f-2c1a5e6b7d8f").bytes
+OPAQUE_VALUE = bytes.fromhex("0f1e2d3c4b5a69788796a5b4c3d2e1f0")
+
+# Apple Worldwide Developer Relations Certification Authority
+OID_APPLE_WWDR_INTERMEDIATE = "1.2.840.113635.100.6.2.1"
+# Mac App Store and iTunes Store Receipt Signing
+OID_APPLE_RECEIPT_SIGNING = "1.2.840.113635.100.6.11.1"
+
+
+#
+# DER
+#
+def der_len(n):
+    if n < 0x80:
+        return bytes([n])
+    b = n.to_bytes((n.bit_length() + 7) // 8, "big")
+    return bytes([0x80 | len(b)]) + b
+
+
+def der(tag, content):
+    return bytes([tag]) + der_len(len(content)) + content
+
+
+def der_integer(n):
+    return der(0x02, n.to_bytes(n.bit_length() // 8 + 1, "big", signed=True))
+
+
+def der_utf8(s):
+    return der(0x0C, s.encode())
+
+
+def der_ia5(s):
+    return der(0x16, s.encode())
+
+
+def der_set_of(items):
+    # DER SET OF is sorted by encoding
+    return der(0x31, b"".join(sorted(items)))
+
+
+def attribute(type_, value, version=1):
+    return der(0x30, der_integer(type_) + der_integer(version) + der(0x04, value))
+
+
+def date(s):
+    return der_ia5(s)
+
+
+#
+# Payload
+#
+def in_app(
+    quantity,
+    product_id,
+    transaction_id,
+    original_transaction_id,
+    purchase_date,
+    original_purchase_date,
+    expires_date=None,
+    web_order_line_item_id=0,
+    cancellation_date=None,
+    is_trial_period=0,
+    is_in_intro_offer_period=0,
+    promotional_offer_id="",
+):
+    attrs = [
+        attribute(1701, der_integer(quantity)),
+        attribute(1702, der_utf8(product_id)),
+        attribute(1703, der_utf8(transaction_id)),
+        attribute(1705, der_utf8(original_transaction_id)),
+        attribute(1704, date(purchase_date)),
+        attribute(1706, date(original_purchase_date)),
+        attribute(1708, date(expires_date or "")),
+        attribute(1711, der_integer(web_order_line_item_id)),
+        attribute(1712, date(cancellation_date or "")),
+        attribute(1713, der_integer(is_trial_period)),
+        attribute(1719, der_integer(is_in_intro_offer_period)),
+        attribute(1721, der_utf8(promotional_offer_id)),
+    ]
+    return der_set_of(attrs)
+
+
+def payload(receipt_creation_date="2025-01-15T08:00:00Z"):
+    bundle_id_data = der_utf8("com.example.app")
+    sha1_hash = hashlib.sha1(DEVICE_ID + OPAQUE_VALUE + bundle_id_data).digest()
+
+    in_apps = [
+        in_app(
+            1,
+            "com.example.app.coins100",
+            "1000000000000001",
+            "1000000000000001",
+            "2025-01-10T10:00:00Z",
+            "2025-01-10T10:00:00Z",
+        ),
+        in_app(
+            1,
+            "com.example.app.monthly",
+            "1000000000000002",
+            "1000000000000002",
+            "2024-12-20T12:00:00Z",
+            "2024-12-20T12:00:00Z",
+            expires_date="2025-01-20T12:00:00Z",
+            web_order_line_item_id=2000000000000002,
+            is_trial_period=1,
+        ),
+        in_app(
+            1,
+            "com.example.app.yearly",
+            "1000000000000003",
+            "1000000000000003",
+            "2024-07-01T08:30:00Z",
+            "2024-07-01T08:30:00Z",
+            expires_date="2025-07-01T08:30:00Z",
+            web_order_line_item_id=2000000000000003,
+            cancellation_date="2024-08-01T00:00:00Z",
+            is_in_intro_offer_period=1,
+            promotional_offer_id="yearly_promo",
+        ),
+    ]
+
+    attrs = [
+        attribute(0, der_utf8("ProductionSandbox")),
+        attribute(1, der_integer(0)),
+        attribute(2, bundle_id_data),
+        attribute(3, der_utf8("1.0.1")),
+        attribute(4, OPAQUE_VALUE),
+        attribute(5, sha1_hash),
+        attribute(12, date(receipt_creation_date)),
+        attribute(16, der_integer(0)),
+        attribute(18, date("2013-08-01T07:00:00Z")),
+        attribute(19, der_utf8("1.0")),
+    ] + [attribute(17, x) for x in in_apps]
+    return der_set_of(attrs)
+
+
+#
+# Certificates
+#
+def name(cn):
+    return x509.Name(
+        [
+            x509.NameAttribute(NameOID.COMMON_NAME, cn),
+            x509.NameAttribute(NameOID.ORGANIZATION_NAME, "Example Test CA"),
+        ]
+    )
+
+
+def utc(*args):
+    return datetime.datetime(*args, tzinfo=datetime.timezone.utc)
+
+
+def key():
+    return rsa.generate_private_key(public_exponent=65537, key_size=2048)
+
+
+def cert(subject, subject_key, issuer, issuer_key, not_before, not_after, ca, oid=None):
+    builder = (
+        x509.CertificateBuilder()
+        .subject_name(subject)
+        .issuer_name(issuer)
+        .public_key(subject_key.public_key())
+        .serial_number(x509.random_serial_number())
+        .not_valid_before(not_before)
+        .not_valid_after(not_after)
+        .add_extension(x509.BasicConstraints(ca=ca, path_length=None), critical=True)
+    )
+    if oid:
+        # The value of Apple's marker extensions is NULL
+        builder = builder.add_extension(
+            x509.UnrecognizedExtension(ObjectIdentifier(oid), b"\x05\x00"), critical=False
+        )
+    return builder.sign(issuer_key, hashes.SHA256())
+
+
+def write(path, data):
+    with open(path, "wb") as f:
+        f.write(data)
+
+
+def main():
+    root_key = key()
+    root_name = name("Test Apple Root CA")
+    root = cert(root_name, root_key, root_name, root_key, utc(2020, 1, 1), utc(2040, 1, 1), True)
+
+    wwdr_key = key()
+    wwdr_name = name("Test Apple Worldwide Developer Relations Certification Authority")
+    wwdr = cert(
+        wwdr_name,
+        wwdr_key,
+        root_name,
+        root_key,
+        utc(2020, 1, 1),
+        utc(2035, 1, 1),
+        True,
+        OID_APPLE_WWDR_INTERMEDIATE,
+    )
+
+    leaf_key = key()
+    leaf_name = name("Test Mac App Store and iTunes Store Receipt Signing")
+    leaf = cert(
+        leaf_name,
+        leaf_key,
+        wwdr_name,
+        wwdr_key,
+        utc(2024, 1, 1),
+        utc(2026, 1, 1),
+        False,
+        OID_APPLE_RECEIPT_SIGNING,
+    )
+    leaf_without_oid = cert(
+        leaf_name, leaf_key, wwdr_name, wwdr_key, utc(2024, 1, 1), utc(2026, 1, 1), False
+    )
+    leaf_ca = cert(
+        leaf_name,
+        leaf_key,
+        wwdr_name,
+        wwdr_key,
+        utc(2024, 1, 1),
+        utc(2026, 1, 1),
+        True,
+        OID_APPLE_RECEIPT_SIGNING,
+    )
+
+    other_root_key = key()
+    other_root_name = name("Other Root CA")
+    other_root = cert(
+        other_root_name,
+        other_root_key,
+        other_root_name,
+        other_root_key,
+        utc(2020, 1, 1),
+        utc(2040, 1, 1),
+        True,
+    )
+
+    def sign(
+        data,
+        options=(pkcs7.PKCS7Options.Binary, pkcs7.PKCS7Options.NoAttributes),
+        signer=leaf,
+    ):
+        return (
+            pkcs7.PKCS7SignatureBuilder()
+            .set_data(data)
+            .add_signer(signer, leaf_key, hashes.SHA256())
+            .add_certificate(wwdr)
+            .sign(serialization.Encoding.DER, list(options))
+        )
+
+    write("root_ca.der", root.public_bytes(serialization.Encoding.DER))
+    write("other_root_ca.der", other_root.public_bytes(serialization.Encoding.DER))
+    write("receipt.der", sign(payload()))
+    # With signed attributes (content type, signing time, message digest)
+    write("receipt_signed_attrs.der", sign(payload(), [pkcs7.PKCS7Options.Binary]))
+    # Created after the signing certificate expired
+    write("receipt_expired_signer.der", sign(payload("2026-06-01T00:00:00Z")))
+    # Signing certificate without the receipt signing extension
+    write("receipt_signer_without_oid.der", sign(payload(), signer=leaf_without_oid))
+    # Signing certificate is a CA
+    write("receipt_ca_signer.der", sign(payload(), signer=leaf_ca))
+    write("device_id", DEVICE_ID)
+
+
+if __name__ == "__main__":
+    main()
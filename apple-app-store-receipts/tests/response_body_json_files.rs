@@ -4,27 +4,48 @@ use apple_app_store_receipts::objects::response_body::ResponseBody;
 
 #[test]
 fn de_all() -> Result<(), Box<dyn error::Error>> {
-    let dir = PathBuf::new().join("tests/response_body_json_files");
-    for entry in fs::read_dir(dir).unwrap() {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() && Some(Some("json")) == path.extension().map(|x| x.to_str()) {
-            let content = fs::read_to_string(&path)?;
-            match serde_json::from_str::<ResponseBody>(&content) {
-                Ok(response_body) => match response_body {
-                    ResponseBody::Success(_) => {
-                        assert!(content.contains(r#""status":0"#));
+    // response_body_json_files is gitignored, fixture_files has the committed synthetic bodies
+    let mut paths = vec![];
+    for (dir, prefix) in [
+        ("tests/response_body_json_files", ""),
+        ("tests/fixture_files", "response_body_"),
+    ] {
+        for entry in fs::read_dir(PathBuf::new().join(dir)).unwrap() {
+            let path = entry?.path();
+            if path.is_file()
+                && Some(Some("json")) == path.extension().map(|x| x.to_str())
+                && path
+                    .file_name()
+                    .and_then(|x| x.to_str())
+                    .map(|x| x.starts_with(prefix))
+                    == Some(true)
+            {
+                paths.push(path);
+            }
+        }
+    }
+    assert!(paths.iter().any(|x| x.starts_with("tests/fixture_files")));
 
-                        println!("path {path:?} de successful");
-                    }
-                    ResponseBody::Error(body) => {
-                        println!("path {path:?} de successful, body: {body:?}");
-                    }
-                },
-                Err(err) => {
-                    eprintln!("path {path:?} de failed, err: {err:?}");
-                    return Err(err.into());
+    for path in paths {
+        let content = fs::read_to_string(&path)?;
+        match serde_json::from_str::<ResponseBody>(&content) {
+            Ok(response_body) => match &response_body {
+                ResponseBody::Success(_) => {
+                    assert_eq!(
+                        serde_json::to_value(&response_body)?,
+                        serde_json::from_str::<serde_json::Value>(&content)?
+                    );
+                    assert!(content.contains(r#""status":0"#));
+
+                    println!("path {path:?} de successful");
+                }
+                ResponseBody::Error(body) => {
+                    println!("path {path:?} de successful, body: {body:?}");
                 }
+            },
+            Err(err) => {
+                eprintln!("path {path:?} de failed, err: {err:?}");
+                return Err(err.into());
             }
         }
     }
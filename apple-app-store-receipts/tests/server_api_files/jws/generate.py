@@ -0,0 +1,203 @@
+#!/usr/bin/env python3
+# Generates synthetic App Store Server API JWS payloads signed by a self-generated test chain.
+#
+# Requires `cryptography`, run from this directory:
+#   python3 generate.py
+
+import base64
+import datetime
+import json
+
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec
+from cryptography.hazmat.primitives.asymmetric.utils import decode_dss_signature
+from cryptography.x509.oid import NameOID
+
+# Marker OIDs Apple puts on the WWDR intermediate and the App Store signing leaf
+OID_WWDR_INTERMEDIATE = x509.ObjectIdentifier("1.2.840.113635.100.6.2.1")
+OID_APP_STORE_LEAF = x509.ObjectIdentifier("1.2.840.113635.100.6.11.1")
+
+
+def b64url(b):
+    return base64.urlsafe_b64encode(b).rstrip(b"=").decode()
+
+
+def name(cn):
+    return x509.Name(
+        [
+            x509.NameAttribute(NameOID.COMMON_NAME, cn),
+            x509.NameAttribute(NameOID.ORGANIZATION_NAME, "Example Test CA"),
+        ]
+    )
+
+
+def utc(*args):
+    return datetime.datetime(*args, tzinfo=datetime.timezone.utc)
+
+
+def cert(subject, subject_key, issuer, issuer_key, not_before, not_after, ca, marker=None):
+    builder = (
+        x509.CertificateBuilder()
+        .subject_name(subject)
+        .issuer_name(issuer)
+        .public_key(subject_key.public_key())
+        .serial_number(x509.random_serial_number())
+        .not_valid_before(not_before)
+        .not_valid_after(not_after)
+        .add_extension(x509.BasicConstraints(ca=ca, path_length=None), critical=True)
+    )
+    if marker is not None:
+        builder = builder.add_extension(
+            x509.UnrecognizedExtension(marker, b"\x05\x00"), critical=False
+        )
+    return builder.sign(issuer_key, hashes.SHA384())
+
+
+def der(c):
+    return c.public_bytes(serialization.Encoding.DER)
+
+
+def jws(payload, key, chain):
+    header = {
+        "alg": "ES256",
+        "x5c": [base64.b64encode(der(c)).decode() for c in chain],
+    }
+    signing_input = (
+        b64url(json.dumps(header, separators=(",", ":")).encode())
+        + "."
+        + b64url(json.dumps(payload, separators=(",", ":")).encode())
+    )
+    r, s = decode_dss_signature(key.sign(signing_input.encode(), ec.ECDSA(hashes.SHA256())))
+    return signing_input + "." + b64url(r.to_bytes(32, "big") + s.to_bytes(32, "big"))
+
+
+def ms(*args):
+    return int(utc(*args).timestamp() * 1000)
+
+
+TRANSACTION = {
+    "transactionId": "1000000000000012",
+    "originalTransactionId": "1000000000000010",
+    "webOrderLineItemId": "2000000000000012",
+    "bundleId": "com.example.app",
+    "productId": "com.example.app.monthly",
+    "subscriptionGroupIdentifier": "20000001",
+    "purchaseDate": ms(2025, 1, 1, 12),
+    "originalPurchaseDate": ms(2024, 11, 1, 12),
+    "expiresDate": ms(2025, 2, 1, 12),
+    "quantity": 1,
+    "type": "Auto-Renewable Subscription",
+    "inAppOwnershipType": "PURCHASED",
+    "signedDate": ms(2025, 1, 15),
+    "offerType": 2,
+    "offerIdentifier": "monthly_promo",
+    "offerDiscountType": "PAY_AS_YOU_GO",
+    "environment": "Sandbox",
+    "transactionReason": "RENEWAL",
+    "storefront": "USA",
+    "storefrontId": "143441",
+    "price": 4990,
+    "currency": "USD",
+    "appTransactionId": "704289572311513785",
+    "someFutureField": "kept",
+}
+
+RENEWAL_INFO = {
+    "expirationIntent": 2,
+    "originalTransactionId": "1000000000000010",
+    "autoRenewProductId": "com.example.app.yearly",
+    "productId": "com.example.app.monthly",
+    "autoRenewStatus": 1,
+    "isInBillingRetryPeriod": True,
+    "priceIncreaseStatus": 1,
+    "gracePeriodExpiresDate": ms(2025, 2, 17, 12),
+    "signedDate": ms(2025, 1, 15),
+    "environment": "Sandbox",
+    "recentSubscriptionStartDate": ms(2024, 11, 1, 12),
+    "renewalDate": ms(2025, 2, 1, 12),
+    "renewalPrice": 4990,
+    "currency": "USD",
+}
+
+
+def main():
+    root_key = ec.generate_private_key(ec.SECP384R1())
+    root_name = name("Test Apple Root CA - G3")
+    root = cert(root_name, root_key, root_name, root_key, utc(2020, 1, 1), utc(2040, 1, 1), True)
+
+    wwdr_key = ec.generate_private_key(ec.SECP384R1())
+    wwdr_name = name("Test Apple Worldwide Developer Relations Certification Authority")
+    wwdr = cert(
+        wwdr_name,
+        wwdr_key,
+        root_name,
+        root_key,
+        utc(2020, 1, 1),
+        utc(2035, 1, 1),
+        True,
+        OID_WWDR_INTERMEDIATE,
+    )
+
+    leaf_key = ec.generate_private_key(ec.SECP256R1())
+    leaf_name = name("Test Prod ECC Mac App Store and iTunes Store Receipt Signing")
+    leaf = cert(
+        leaf_name,
+        leaf_key,
+        wwdr_name,
+        wwdr_key,
+        utc(2024, 1, 1),
+        utc(2026, 1, 1),
+        False,
+        OID_APP_STORE_LEAF,
+    )
+    leaf_without_marker = cert(
+        leaf_name, leaf_key, wwdr_name, wwdr_key, utc(2024, 1, 1), utc(2026, 1, 1), False
+    )
+    leaf_ca = cert(
+        leaf_name,
+        leaf_key,
+        wwdr_name,
+        wwdr_key,
+        utc(2024, 1, 1),
+        utc(2026, 1, 1),
+        True,
+        OID_APP_STORE_LEAF,
+    )
+
+    other_root_key = ec.generate_private_key(ec.SECP384R1())
+    other_root_name = name("Other Root CA")
+    other_root = cert(
+        other_root_name,
+        other_root_key,
+        other_root_name,
+        other_root_key,
+        utc(2020, 1, 1),
+        utc(2040, 1, 1),
+        True,
+    )
+
+    def write(path, data):
+        with open(path, "w" if isinstance(data, str) else "wb") as f:
+            f.write(data)
+
+    chain = [leaf, wwdr, root]
+    write("root_ca.der", der(root))
+    write("other_root_ca.der", der(other_root))
+    write("transaction.jws", jws(TRANSACTION, leaf_key, chain))
+    write("renewal_info.jws", jws(RENEWAL_INFO, leaf_key, chain))
+    # Signed after the signing certificate expired
+    write(
+        "transaction_expired_signer.jws",
+        jws(dict(TRANSACTION, signedDate=ms(2026, 6, 1)), leaf_key, chain),
+    )
+    write(
+        "transaction_leaf_without_oid.jws",
+        jws(TRANSACTION, leaf_key, [leaf_without_marker, wwdr, root]),
+    )
+    # Signing certificate is a CA
+    write("transaction_leaf_ca.jws", jws(TRANSACTION, leaf_key, [leaf_ca, wwdr, root]))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,435 @@
+// Budget pacing and forecast of a campaign from DAILY granularity reports.
+
+use core::fmt;
+use std::{collections::BTreeMap, num::ParseFloatError};
+
+use chrono::NaiveDate;
+
+use crate::{
+    objects::{
+        campaign::Campaign,
+        extended_spend_row::ExtendedSpendRowDate,
+        money::Money,
+        row::{CampaignLevelRowMetaData, Row},
+    },
+    types::currency::Currency,
+};
+
+//
+#[derive(Debug, Clone)]
+pub struct BudgetPacingOptions {
+    /// Last day included in spend to date, usually yesterday in the org time zone.
+    pub as_of: NaiveDate,
+    /// Overrides the campaign `endTime` as the end of the period.
+    pub period_end: Option<NaiveDate>,
+    /// Number of days ending at `as_of` used for the average daily spend.
+    pub lookback_days: u32,
+    /// Allowed relative deviation before a campaign is flagged as over or under pacing.
+    pub tolerance: f64,
+}
+
+impl BudgetPacingOptions {
+    pub fn new(as_of: NaiveDate) -> Self {
+        Self {
+            as_of,
+            period_end: None,
+            lookback_days: 7,
+            tolerance: 0.1,
+        }
+    }
+
+    pub fn set_period_end(&mut self, val: impl Into<Option<NaiveDate>>) -> &mut Self {
+        self.period_end = val.into();
+        self
+    }
+
+    pub fn set_lookback_days(&mut self, val: u32) -> &mut Self {
+        self.lookback_days = val.max(1);
+        self
+    }
+
+    pub fn set_tolerance(&mut self, val: f64) -> &mut Self {
+        self.tolerance = val;
+        self
+    }
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacingStatus {
+    OnTrack,
+    OverPacing,
+    UnderPacing,
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct BudgetPacing {
+    pub campaign_id: u64,
+    pub currency: Option<Currency>,
+    pub period_start: Option<NaiveDate>,
+    pub period_end: Option<NaiveDate>,
+    pub spend_to_date: f64,
+    pub average_daily_spend: f64,
+    /// None when the period has no end.
+    pub projected_period_spend: Option<f64>,
+    pub budget_amount: Option<f64>,
+    pub daily_budget_amount: Option<f64>,
+    /// None when there is no total budget or no spend.
+    pub days_until_budget_exhausted: Option<f64>,
+    pub budget_pacing: PacingStatus,
+    pub daily_budget_pacing: PacingStatus,
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetPacingError {
+    /// Spend can not be summed across currencies.
+    MixedCurrencies(Currency, Currency),
+    AmountParseFailed(Box<str>, ParseFloatError),
+    /// The rows are from a HOURLY granularity report.
+    NotDailyGranularity,
+}
+
+impl fmt::Display for BudgetPacingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for BudgetPacingError {}
+
+/// `rows` may contain other campaigns and several `countryOrRegion` groups, only the rows
+/// of `campaign` are used and they are summed per day.
+///
+/// `rows` must be from a DAILY granularity report. HOURLY rows are rejected, WEEKLY and MONTHLY
+/// rows can not be told apart from DAILY ones by their date and would be counted as one day each.
+pub fn calculate_budget_pacing<'a, I: 'a>(
+    campaign: &Campaign,
+    rows: impl IntoIterator<Item = &'a Row<CampaignLevelRowMetaData, I>>,
+    options: &BudgetPacingOptions,
+) -> Result<BudgetPacing, BudgetPacingError> {
+    let mut daily_spends: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    let mut currency: Option<Currency> = None;
+
+    for row in rows {
+        if row.metadata.campaign_id != campaign.id {
+            continue;
+        }
+        for x in row.granularity.iter().flatten() {
+            let date = match &x.date {
+                ExtendedSpendRowDate::Date(date) => *date,
+                ExtendedSpendRowDate::DateAndHour(_) => {
+                    return Err(BudgetPacingError::NotDailyGranularity)
+                }
+            };
+            if date > options.as_of {
+                continue;
+            }
+            if let Some(local_spend) = &x.local_spend {
+                match &currency {
+                    Some(currency) if *currency != local_spend.currency => {
+                        return Err(BudgetPacingError::MixedCurrencies(
+                            currency.to_owned(),
+                            local_spend.currency.to_owned(),
+                        ));
+                    }
+                    Some(_) => {}
+                    None => currency = Some(local_spend.currency.to_owned()),
+                }
+                *daily_spends.entry(date).or_default() += parse_amount(local_spend)?;
+            }
+        }
+    }
+
+    let period_start = campaign
+        .start_time
+        .map(|x| x.date_naive())
+        .or_else(|| daily_spends.keys().next().copied());
+    let period_end = options
+        .period_end
+        .or_else(|| campaign.end_time.map(|x| x.date_naive()));
+
+    let spend_to_date: f64 = daily_spends.values().sum();
+
+    let lookback_start = options.as_of - chrono::Duration::days(options.lookback_days as i64 - 1);
+    let lookback_start = match period_start {
+        Some(period_start) if period_start > lookback_start => period_start,
+        _ => lookback_start,
+    };
+    let lookback_days = (options.as_of - lookback_start).num_days() + 1;
+    let average_daily_spend = if lookback_days > 0 {
+        daily_spends
+            .range(lookback_start..=options.as_of)
+            .map(|(_, v)| v)
+            .sum::<f64>()
+            / lookback_days as f64
+    } else {
+        0.0
+    };
+
+    let projected_period_spend = period_end.map(|period_end| {
+        let remaining_days = (period_end - options.as_of).num_days().max(0);
+        spend_to_date + average_daily_spend * remaining_days as f64
+    });
+
+    let budget_amount = campaign
+        .budget_amount
+        .as_ref()
+        .and_then(|x| x.amount_f64().ok());
+    let daily_budget_amount = campaign
+        .daily_budget_amount
+        .as_ref()
+        .and_then(|x| x.amount_f64().ok());
+
+    let days_until_budget_exhausted = match budget_amount {
+        Some(budget_amount) if spend_to_date >= budget_amount => Some(0.0),
+        Some(budget_amount) if average_daily_spend > 0.0 => {
+            Some((budget_amount - spend_to_date) / average_daily_spend)
+        }
+        _ => None,
+    };
+
+    let budget_pacing = match (budget_amount, period_start, period_end) {
+        (Some(budget_amount), Some(period_start), Some(period_end))
+            if budget_amount > 0.0 && period_end >= period_start =>
+        {
+            let total_days = (period_end - period_start).num_days() + 1;
+            let elapsed_days = ((options.as_of - period_start).num_days() + 1).clamp(0, total_days);
+            let expected_spend = budget_amount * elapsed_days as f64 / total_days as f64;
+            compare(spend_to_date, expected_spend, options.tolerance)
+        }
+        _ => PacingStatus::Unknown,
+    };
+
+    let daily_budget_pacing = match daily_budget_amount {
+        Some(daily_budget_amount) if daily_budget_amount > 0.0 => {
+            compare(average_daily_spend, daily_budget_amount, options.tolerance)
+        }
+        _ => PacingStatus::Unknown,
+    };
+
+    Ok(BudgetPacing {
+        campaign_id: campaign.id,
+        currency,
+        period_start,
+        period_end,
+        spend_to_date,
+        average_daily_spend,
+        projected_period_spend,
+        budget_amount,
+        daily_budget_amount,
+        days_until_budget_exhausted,
+        budget_pacing,
+        daily_budget_pacing,
+    })
+}
+
+pub fn calculate_budget_pacings<'a, I: 'a>(
+    campaigns: &[Campaign],
+    rows: &'a [Row<CampaignLevelRowMetaData, I>],
+    options: &BudgetPacingOptions,
+) -> Result<Vec<BudgetPacing>, BudgetPacingError> {
+    campaigns
+        .iter()
+        .map(|campaign| calculate_budget_pacing(campaign, rows, options))
+        .collect()
+}
+
+fn parse_amount(money: &Money) -> Result<f64, BudgetPacingError> {
+    money
+        .amount_f64()
+        .map_err(|err| BudgetPacingError::AmountParseFailed(money.amount.to_owned(), err))
+}
+
+fn compare(actual: f64, expected: f64, tolerance: f64) -> PacingStatus {
+    if expected <= 0.0 {
+        return PacingStatus::Unknown;
+    }
+    let ratio = actual / expected;
+    if ratio > 1.0 + tolerance {
+        PacingStatus::OverPacing
+    } else if ratio < 1.0 - tolerance {
+        PacingStatus::UnderPacing
+    } else {
+        PacingStatus::OnTrack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::objects::{
+        campaign_list_response::CampaignListResponse,
+        reporting_response_body::CampaignLevelReportingResponseBody,
+    };
+
+    // Rows of 2025-01
+    fn report(campaign_id: u64, daily_spends: &[(u32, &str)]) -> serde_json::Value {
+        report_with_granularity(
+            campaign_id,
+            daily_spends
+                .iter()
+                .map(|(day, amount)| {
+                    serde_json::json!({
+                        "date": format!("2025-01-{day:02}"),
+                        "localSpend": {"amount": amount, "currency": "USD"}
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn report_with_granularity(
+        campaign_id: u64,
+        granularity: Vec<serde_json::Value>,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "other": false,
+            "granularity": granularity,
+            "metadata": {
+                "campaignId": campaign_id,
+                "campaignName": "Name",
+                "deleted": false,
+                "campaignStatus": "ENABLED",
+                "app": {"appName": "App", "adamId": 1},
+                "servingStatus": "RUNNING",
+                "servingStateReasons": null,
+                "countriesOrRegions": ["US"],
+                "modificationTime": "2025-01-01T00:00:00.000",
+                "displayStatus": "RUNNING",
+                "supplySources": ["APPSTORE_SEARCH_RESULTS"],
+                "adChannelType": "SEARCH",
+                "orgId": 1,
+                "countryOrRegionServingStateReasons": {}
+            }
+        })
+    }
+
+    #[test]
+    fn test_calculate_budget_pacing() -> Result<(), Box<dyn error::Error>> {
+        let campaigns: CampaignListResponse = serde_json::from_str(include_str!(
+            "../../tests/v5/response_body_json_files/campaign_list_response.json"
+        ))?;
+
+        // 1234567891: budget 2000, daily budget 100, 2025-01-01 ~ 2025-03-31 (90 days)
+        let body: CampaignLevelReportingResponseBody = serde_json::from_value(serde_json::json!({
+            "data": {
+                "reportingDataResponse": {
+                    "row": [
+                        report(1234567891, &(1..=10).map(|day| (day, "30")).collect::<Vec<_>>()),
+                        report(1234567891, &[(10, "10"), (11, "999")]),
+                        report(1, &[(10, "999")]),
+                    ]
+                }
+            },
+            "pagination": {"totalResults": 3, "startIndex": 0, "itemsPerPage": 3}
+        }))?;
+
+        let mut options = BudgetPacingOptions::new("2025-01-10".parse()?);
+        options.set_lookback_days(5);
+
+        let pacing = calculate_budget_pacing(
+            &campaigns.data[1],
+            &body.data.reporting_data_response.row,
+            &options,
+        )?;
+        assert_eq!(pacing.currency, Some(Currency::USD));
+        assert_eq!(pacing.period_start, Some("2025-01-01".parse()?));
+        assert_eq!(pacing.period_end, Some("2025-03-31".parse()?));
+        assert_eq!(pacing.spend_to_date, 310.0);
+        assert_eq!(pacing.average_daily_spend, 32.0);
+        assert_eq!(pacing.projected_period_spend, Some(310.0 + 32.0 * 80.0));
+        assert_eq!(
+            pacing.days_until_budget_exhausted,
+            Some((2000.0 - 310.0) / 32.0)
+        );
+        // expected 2000 * 10 / 90 = 222.2
+        assert_eq!(pacing.budget_pacing, PacingStatus::OverPacing);
+        assert_eq!(pacing.daily_budget_pacing, PacingStatus::UnderPacing);
+
+        // 1234567890: no end time
+        let pacings = calculate_budget_pacings(
+            &campaigns.data,
+            &body.data.reporting_data_response.row,
+            &options,
+        )?;
+        assert_eq!(pacings.len(), 2);
+        assert_eq!(pacings[0].spend_to_date, 0.0);
+        assert_eq!(pacings[0].projected_period_spend, None);
+        assert_eq!(pacings[0].days_until_budget_exhausted, None);
+        assert_eq!(pacings[0].budget_pacing, PacingStatus::Unknown);
+        assert_eq!(pacings[0].daily_budget_pacing, PacingStatus::UnderPacing);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_budget_pacing_with_invalid_rows() -> Result<(), Box<dyn error::Error>> {
+        let campaigns: CampaignListResponse = serde_json::from_str(include_str!(
+            "../../tests/v5/response_body_json_files/campaign_list_response.json"
+        ))?;
+        let options = BudgetPacingOptions::new("2025-01-10".parse()?);
+
+        let calculate = |granularity: Vec<serde_json::Value>| {
+            let body: CampaignLevelReportingResponseBody =
+                serde_json::from_value(serde_json::json!({
+                    "data": {
+                        "reportingDataResponse": {
+                            "row": [report_with_granularity(1234567891, granularity)]
+                        }
+                    },
+                    "pagination": {"totalResults": 1, "startIndex": 0, "itemsPerPage": 1}
+                }))
+                .unwrap();
+            calculate_budget_pacing(
+                &campaigns.data[1],
+                &body.data.reporting_data_response.row,
+                &options,
+            )
+        };
+
+        assert_eq!(
+            calculate(vec![
+                serde_json::json!({"date": "2025-01-01", "localSpend": {"amount": "1", "currency": "USD"}}),
+                serde_json::json!({"date": "2025-01-02", "localSpend": {"amount": "1", "currency": "EUR"}}),
+            ])
+            .err(),
+            Some(BudgetPacingError::MixedCurrencies(
+                Currency::USD,
+                Currency::EUR
+            ))
+        );
+
+        match calculate(vec![
+            serde_json::json!({"date": "2025-01-01", "localSpend": {"amount": "1,5", "currency": "USD"}}),
+        ]) {
+            Err(BudgetPacingError::AmountParseFailed(amount, _)) => {
+                assert_eq!(amount.as_ref(), "1,5");
+            }
+            x => panic!("{x:?}"),
+        }
+
+        assert_eq!(
+            calculate(vec![
+                serde_json::json!({"date": "2025-01-01 10", "localSpend": {"amount": "1", "currency": "USD"}}),
+            ])
+            .err(),
+            Some(BudgetPacingError::NotDailyGranularity)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(compare(100.0, 100.0, 0.1), PacingStatus::OnTrack);
+        assert_eq!(compare(109.0, 100.0, 0.1), PacingStatus::OnTrack);
+        assert_eq!(compare(111.0, 100.0, 0.1), PacingStatus::OverPacing);
+        assert_eq!(compare(89.0, 100.0, 0.1), PacingStatus::UnderPacing);
+        assert_eq!(compare(1.0, 0.0, 0.1), PacingStatus::Unknown);
+    }
+}
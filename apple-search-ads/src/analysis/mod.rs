@@ -0,0 +1,13 @@
+//
+pub mod budget_pacing;
+pub mod search_term_mining;
+
+pub use budget_pacing::{
+    calculate_budget_pacing, calculate_budget_pacings, BudgetPacing, BudgetPacingError,
+    BudgetPacingOptions, PacingStatus,
+};
+pub use search_term_mining::{
+    mine_search_terms, AdGroupSearchTermSuggestions, ExactMatchKeywordSuggestion,
+    NegativeKeywordReason, NegativeKeywordSuggestion, PauseKeywordSuggestion, SearchTermMetrics,
+    SearchTermMiningError, SearchTermMiningThresholds,
+};
@@ -0,0 +1,616 @@
+// Mine search term level reports for negative keywords and new exact match keywords.
+
+use core::fmt;
+use std::{collections::BTreeMap, num::ParseFloatError};
+
+use crate::{
+    objects::{
+        extended_spend_row::ExtendedSpendRow,
+        keyword::{Keyword, KeywordMatchType},
+        money::Money,
+        negative_keyword::NegativeKeyword,
+        reporting_search_term::{ReportingSearchTermMatchType, SearchTermSource},
+        row::{Row, SearchTermLevelRowMetaData},
+        spend_row::SpendRow,
+    },
+    types::currency::Currency,
+};
+
+//
+#[derive(Debug, Clone)]
+pub struct SearchTermMiningThresholds {
+    /// Propose a negative keyword when the term spent at least this much without any install.
+    pub min_spend_without_installs: Option<f64>,
+    /// Only check `min_ttr` when the term has at least this many impressions.
+    pub min_impressions_for_ttr: u64,
+    /// Propose a negative keyword when the term's TTR is below this value.
+    pub min_ttr: Option<f64>,
+    /// Propose a negative keyword when the term's CPA is above this value.
+    pub max_cpa: Option<f64>,
+    /// Propose an exact match keyword when the term has at least this many installs.
+    pub min_installs_for_exact_match: Option<u64>,
+}
+
+impl Default for SearchTermMiningThresholds {
+    fn default() -> Self {
+        Self {
+            min_spend_without_installs: Some(10.0),
+            min_impressions_for_ttr: 100,
+            min_ttr: None,
+            max_cpa: None,
+            min_installs_for_exact_match: Some(3),
+        }
+    }
+}
+
+impl SearchTermMiningThresholds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_min_spend_without_installs(&mut self, val: impl Into<Option<f64>>) -> &mut Self {
+        self.min_spend_without_installs = val.into();
+        self
+    }
+
+    pub fn set_min_impressions_for_ttr(&mut self, val: u64) -> &mut Self {
+        self.min_impressions_for_ttr = val;
+        self
+    }
+
+    pub fn set_min_ttr(&mut self, val: impl Into<Option<f64>>) -> &mut Self {
+        self.min_ttr = val.into();
+        self
+    }
+
+    pub fn set_max_cpa(&mut self, val: impl Into<Option<f64>>) -> &mut Self {
+        self.max_cpa = val.into();
+        self
+    }
+
+    pub fn set_min_installs_for_exact_match(&mut self, val: impl Into<Option<u64>>) -> &mut Self {
+        self.min_installs_for_exact_match = val.into();
+        self
+    }
+}
+
+//
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchTermMetrics {
+    pub impressions: u64,
+    pub taps: u64,
+    pub installs: u64,
+    pub spend: f64,
+    pub currency: Option<Currency>,
+}
+
+impl SearchTermMetrics {
+    pub fn ttr(&self) -> Option<f64> {
+        if self.impressions == 0 {
+            return None;
+        }
+        Some(self.taps as f64 / self.impressions as f64)
+    }
+
+    pub fn cpa(&self) -> Option<f64> {
+        if self.installs == 0 {
+            return None;
+        }
+        Some(self.spend / self.installs as f64)
+    }
+
+    pub fn avg_cpt(&self) -> Option<f64> {
+        if self.taps == 0 {
+            return None;
+        }
+        Some(self.spend / self.taps as f64)
+    }
+
+    fn add_spend_row(&mut self, row: &SpendRow) -> Result<(), SearchTermMiningError> {
+        self.add_spend(&row.local_spend)?;
+        self.impressions += row.impressions;
+        self.taps += row.taps;
+        // v5 reports tapInstalls, v4 reports installs
+        self.installs += row.tap_installs.or(row.installs).unwrap_or(0);
+        Ok(())
+    }
+
+    fn add_extended_spend_row(
+        &mut self,
+        row: &ExtendedSpendRow,
+    ) -> Result<(), SearchTermMiningError> {
+        if let Some(local_spend) = &row.local_spend {
+            self.add_spend(local_spend)?;
+        }
+        self.impressions += row.impressions.unwrap_or(0);
+        self.taps += row.taps.unwrap_or(0);
+        self.installs += row.tap_installs.or(row.installs).unwrap_or(0);
+        Ok(())
+    }
+
+    fn add_spend(&mut self, money: &Money) -> Result<(), SearchTermMiningError> {
+        match &self.currency {
+            Some(currency) if *currency != money.currency => {
+                return Err(SearchTermMiningError::MixedCurrencies(
+                    currency.to_owned(),
+                    money.currency.to_owned(),
+                ));
+            }
+            Some(_) => {}
+            None => self.currency = Some(money.currency.to_owned()),
+        }
+        self.spend += money.amount_f64().map_err(|err| {
+            SearchTermMiningError::AmountParseFailed(money.amount.to_owned(), err)
+        })?;
+        Ok(())
+    }
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchTermMiningError {
+    /// Spend can not be summed across currencies, mine each currency separately.
+    MixedCurrencies(Currency, Currency),
+    AmountParseFailed(Box<str>, ParseFloatError),
+}
+
+impl fmt::Display for SearchTermMiningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for SearchTermMiningError {}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NegativeKeywordReason {
+    SpendWithoutInstalls,
+    LowTtr,
+    HighCpa,
+}
+
+#[derive(Debug, Clone)]
+pub struct NegativeKeywordSuggestion {
+    pub search_term_text: Box<str>,
+    pub reason: NegativeKeywordReason,
+    pub metrics: SearchTermMetrics,
+    pub payload: NegativeKeyword,
+}
+
+/// The term underperforms but the ad group already targets it with an EXACT keyword,
+/// a negative keyword would block that keyword, so pause the keyword instead.
+#[derive(Debug, Clone)]
+pub struct PauseKeywordSuggestion {
+    pub search_term_text: Box<str>,
+    pub keyword_id: Option<u64>,
+    pub reason: NegativeKeywordReason,
+    pub metrics: SearchTermMetrics,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExactMatchKeywordSuggestion {
+    pub search_term_text: Box<str>,
+    pub metrics: SearchTermMetrics,
+    pub payload: Keyword,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdGroupSearchTermSuggestions {
+    pub campaign_id: u64,
+    pub ad_group_id: u64,
+    pub ad_group_name: Box<str>,
+    pub negative_keywords: Vec<NegativeKeywordSuggestion>,
+    pub pause_keywords: Vec<PauseKeywordSuggestion>,
+    pub exact_match_keywords: Vec<ExactMatchKeywordSuggestion>,
+}
+
+impl AdGroupSearchTermSuggestions {
+    /// Body for the Create Ad Group Negative Keywords endpoint.
+    pub fn negative_keyword_payloads(&self) -> Vec<NegativeKeyword> {
+        self.negative_keywords
+            .iter()
+            .map(|x| x.payload.to_owned())
+            .collect()
+    }
+
+    /// Body for the Create Targeting Keywords endpoint.
+    pub fn targeting_keyword_payloads(&self) -> Vec<Keyword> {
+        self.exact_match_keywords
+            .iter()
+            .map(|x| x.payload.to_owned())
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.negative_keywords.is_empty()
+            && self.pause_keywords.is_empty()
+            && self.exact_match_keywords.is_empty()
+    }
+}
+
+//
+struct SearchTermEntry {
+    search_term_text: Box<str>,
+    ad_group_name: Box<str>,
+    is_exact_targeted: bool,
+    exact_keyword_id: Option<u64>,
+    metrics: SearchTermMetrics,
+}
+
+/// `rows` may come from several pages and several `countryOrRegion` groups of the same campaign,
+/// rows of the same search term in the same ad group are merged before the thresholds are applied.
+/// Rows of a search term must share one currency.
+pub fn mine_search_terms<'a, I: 'a>(
+    campaign_id: u64,
+    rows: impl IntoIterator<Item = &'a Row<SearchTermLevelRowMetaData, I>>,
+    thresholds: &SearchTermMiningThresholds,
+) -> Result<Vec<AdGroupSearchTermSuggestions>, SearchTermMiningError> {
+    let mut entries: BTreeMap<(u64, String), SearchTermEntry> = BTreeMap::new();
+
+    for row in rows {
+        // Low volume search terms are hidden by Apple.
+        let search_term_text = match &row.metadata.search_term_text {
+            Some(x) if !row.other && !x.trim().is_empty() => x.trim(),
+            _ => continue,
+        };
+
+        let entry = entries
+            .entry((row.metadata.ad_group_id, search_term_text.to_lowercase()))
+            .or_insert_with(|| SearchTermEntry {
+                search_term_text: search_term_text.into(),
+                ad_group_name: row.metadata.ad_group_name.to_owned(),
+                is_exact_targeted: false,
+                exact_keyword_id: None,
+                metrics: SearchTermMetrics::default(),
+            });
+
+        if row.metadata.search_term_source == SearchTermSource::TARGETED
+            && row.metadata.match_type == ReportingSearchTermMatchType::EXACT
+        {
+            entry.is_exact_targeted = true;
+            entry.exact_keyword_id = entry.exact_keyword_id.or(row.metadata.keyword_id);
+        }
+
+        if let Some(total) = &row.total {
+            entry.metrics.add_spend_row(total)?;
+        } else if let Some(granularity) = &row.granularity {
+            for x in granularity {
+                entry.metrics.add_extended_spend_row(x)?;
+            }
+        }
+    }
+
+    let mut ad_groups: BTreeMap<u64, AdGroupSearchTermSuggestions> = BTreeMap::new();
+
+    for ((ad_group_id, _), entry) in entries {
+        let ad_group =
+            ad_groups
+                .entry(ad_group_id)
+                .or_insert_with(|| AdGroupSearchTermSuggestions {
+                    campaign_id,
+                    ad_group_id,
+                    ad_group_name: entry.ad_group_name.to_owned(),
+                    negative_keywords: vec![],
+                    pause_keywords: vec![],
+                    exact_match_keywords: vec![],
+                });
+
+        let negative_keyword_reason = negative_keyword_reason(&entry.metrics, thresholds);
+
+        if let (Some(reason), true) = (&negative_keyword_reason, entry.is_exact_targeted) {
+            ad_group.pause_keywords.push(PauseKeywordSuggestion {
+                search_term_text: entry.search_term_text,
+                keyword_id: entry.exact_keyword_id,
+                reason: reason.to_owned(),
+                metrics: entry.metrics,
+            });
+        } else if let Some(reason) = negative_keyword_reason {
+            let mut payload =
+                NegativeKeyword::new(entry.search_term_text.as_ref(), KeywordMatchType::EXACT);
+            payload
+                .set_campaign_id(campaign_id)
+                .set_ad_group_id(ad_group_id);
+
+            ad_group.negative_keywords.push(NegativeKeywordSuggestion {
+                search_term_text: entry.search_term_text,
+                reason,
+                metrics: entry.metrics,
+                payload,
+            });
+        } else if !entry.is_exact_targeted && is_exact_match_candidate(&entry.metrics, thresholds) {
+            let mut payload =
+                Keyword::new(entry.search_term_text.as_ref(), KeywordMatchType::EXACT);
+            payload.set_ad_group_id(ad_group_id);
+            if let (Some(avg_cpt), Some(currency)) =
+                (entry.metrics.avg_cpt(), &entry.metrics.currency)
+            {
+                payload.set_bid_amount(Money::new(currency.to_owned(), format!("{avg_cpt:.2}")));
+            }
+
+            ad_group
+                .exact_match_keywords
+                .push(ExactMatchKeywordSuggestion {
+                    search_term_text: entry.search_term_text,
+                    metrics: entry.metrics,
+                    payload,
+                });
+        }
+    }
+
+    Ok(ad_groups
+        .into_values()
+        .filter(|x| !x.is_empty())
+        .map(|mut x| {
+            x.negative_keywords
+                .sort_by(|a, b| b.metrics.spend.total_cmp(&a.metrics.spend));
+            x.pause_keywords
+                .sort_by(|a, b| b.metrics.spend.total_cmp(&a.metrics.spend));
+            x.exact_match_keywords
+                .sort_by_key(|x| core::cmp::Reverse(x.metrics.installs));
+            x
+        })
+        .collect())
+}
+
+fn negative_keyword_reason(
+    metrics: &SearchTermMetrics,
+    thresholds: &SearchTermMiningThresholds,
+) -> Option<NegativeKeywordReason> {
+    if let Some(min_spend) = thresholds.min_spend_without_installs {
+        if metrics.installs == 0 && metrics.spend >= min_spend {
+            return Some(NegativeKeywordReason::SpendWithoutInstalls);
+        }
+    }
+
+    if let Some(max_cpa) = thresholds.max_cpa {
+        if metrics.cpa().map(|cpa| cpa > max_cpa) == Some(true) {
+            return Some(NegativeKeywordReason::HighCpa);
+        }
+    }
+
+    if let Some(min_ttr) = thresholds.min_ttr {
+        if metrics.impressions >= thresholds.min_impressions_for_ttr
+            && metrics.ttr().map(|ttr| ttr < min_ttr) == Some(true)
+        {
+            return Some(NegativeKeywordReason::LowTtr);
+        }
+    }
+
+    None
+}
+
+fn is_exact_match_candidate(
+    metrics: &SearchTermMetrics,
+    thresholds: &SearchTermMiningThresholds,
+) -> bool {
+    match thresholds.min_installs_for_exact_match {
+        Some(min_installs) => metrics.installs >= min_installs && metrics.installs > 0,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::objects::reporting_response_body::SearchTermLevelReportingResponseBody;
+
+    fn row(
+        ad_group_id: u64,
+        text: Option<&str>,
+        source: &str,
+        match_type: &str,
+        (impressions, taps, installs, spend): (u64, u64, u64, &str),
+    ) -> serde_json::Value {
+        row_with_currency(
+            ad_group_id,
+            text,
+            source,
+            match_type,
+            (impressions, taps, installs, spend),
+            "USD",
+        )
+    }
+
+    fn row_with_currency(
+        ad_group_id: u64,
+        text: Option<&str>,
+        source: &str,
+        match_type: &str,
+        (impressions, taps, installs, spend): (u64, u64, u64, &str),
+        currency: &str,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "other": false,
+            "total": {
+                "impressions": impressions,
+                "taps": taps,
+                "installs": installs,
+                "newDownloads": installs,
+                "redownloads": 0,
+                "latOnInstalls": 0,
+                "latOffInstalls": 0,
+                "ttr": 0,
+                "avgCPA": {"amount": "0", "currency": "USD"},
+                "avgCPT": {"amount": "0", "currency": "USD"},
+                "localSpend": {"amount": spend, "currency": currency},
+                "conversionRate": 0
+            },
+            "metadata": {
+                "keywordId": 1,
+                "keyword": "<keyword>",
+                "matchType": match_type,
+                "adGroupId": ad_group_id,
+                "adGroupName": format!("ad group {ad_group_id}"),
+                "searchTermText": text,
+                "searchTermSource": source,
+                "countryOrRegion": "US"
+            }
+        })
+    }
+
+    #[test]
+    fn test_mine_search_terms() -> Result<(), Box<dyn error::Error>> {
+        let body: SearchTermLevelReportingResponseBody = serde_json::from_value(
+            serde_json::json!({
+                "data": {
+                    "reportingDataResponse": {
+                        "row": [
+                            row(1, Some("spend no install"), "AUTO", "AUTO", (100, 10, 0, "12")),
+                            row(1, Some("good term"), "AUTO", "AUTO", (100, 10, 2, "4")),
+                            row(1, Some("Good Term"), "TARGETED", "BROAD", (100, 10, 2, "4")),
+                            row(1, Some("already exact"), "TARGETED", "EXACT", (100, 10, 5, "5")),
+                            row(1, Some("exact no install"), "TARGETED", "EXACT", (100, 10, 0, "20")),
+                            row(1, None, "AUTO", "AUTO", (100, 10, 0, "50")),
+                            row(2, Some("low ttr"), "AUTO", "AUTO", (1000, 1, 0, "1")),
+                            row(2, Some("expensive"), "AUTO", "AUTO", (100, 20, 1, "9")),
+                            row(3, Some("nothing to do"), "AUTO", "AUTO", (10, 1, 0, "1")),
+                        ]
+                    }
+                },
+                "pagination": {"totalResults": 9, "startIndex": 0, "itemsPerPage": 9}
+            }),
+        )?;
+
+        let mut thresholds = SearchTermMiningThresholds::new();
+        thresholds.set_min_ttr(0.005).set_max_cpa(5.0);
+
+        let ret = mine_search_terms(9, body.data.reporting_data_response.row.iter(), &thresholds)?;
+        assert_eq!(ret.len(), 2);
+
+        let ad_group = &ret[0];
+        assert_eq!(ad_group.ad_group_id, 1);
+        assert_eq!(ad_group.negative_keywords.len(), 1);
+        assert_eq!(
+            ad_group.negative_keywords[0].reason,
+            NegativeKeywordReason::SpendWithoutInstalls
+        );
+        assert_eq!(
+            serde_json::to_value(ad_group.negative_keyword_payloads())?,
+            serde_json::json!([{
+                "campaignId": 9,
+                "adGroupId": 1,
+                "text": "spend no install",
+                "matchType": "EXACT"
+            }])
+        );
+        assert_eq!(ad_group.pause_keywords.len(), 1);
+        assert_eq!(
+            ad_group.pause_keywords[0].search_term_text.as_ref(),
+            "exact no install"
+        );
+        assert_eq!(ad_group.pause_keywords[0].keyword_id, Some(1));
+        assert_eq!(
+            ad_group.pause_keywords[0].reason,
+            NegativeKeywordReason::SpendWithoutInstalls
+        );
+        assert_eq!(ad_group.exact_match_keywords.len(), 1);
+        assert_eq!(ad_group.exact_match_keywords[0].metrics.installs, 4);
+        assert_eq!(
+            serde_json::to_value(ad_group.targeting_keyword_payloads())?,
+            serde_json::json!([{
+                "adGroupId": 1,
+                "text": "good term",
+                "matchType": "EXACT",
+                "bidAmount": {"currency": "USD", "amount": "0.40"}
+            }])
+        );
+
+        let ad_group = &ret[1];
+        assert_eq!(ad_group.ad_group_id, 2);
+        assert_eq!(
+            ad_group
+                .negative_keywords
+                .iter()
+                .map(|x| (x.search_term_text.as_ref(), x.reason.to_owned()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("expensive", NegativeKeywordReason::HighCpa),
+                ("low ttr", NegativeKeywordReason::LowTtr),
+            ]
+        );
+        assert!(ad_group.exact_match_keywords.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mine_search_terms_with_mixed_currencies() -> Result<(), Box<dyn error::Error>> {
+        let body: SearchTermLevelReportingResponseBody = serde_json::from_value(
+            serde_json::json!({
+                "data": {
+                    "reportingDataResponse": {
+                        "row": [
+                            row(1, Some("term"), "AUTO", "AUTO", (100, 10, 0, "12")),
+                            row_with_currency(1, Some("term"), "AUTO", "AUTO", (100, 10, 0, "12"), "EUR"),
+                        ]
+                    }
+                },
+                "pagination": {"totalResults": 2, "startIndex": 0, "itemsPerPage": 2}
+            }),
+        )?;
+
+        match mine_search_terms(
+            1,
+            body.data.reporting_data_response.row.iter(),
+            &SearchTermMiningThresholds::default(),
+        ) {
+            Err(SearchTermMiningError::MixedCurrencies(a, b)) => {
+                assert_eq!(a, Currency::USD);
+                assert_eq!(b, Currency::EUR);
+            }
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mine_search_terms_with_invalid_amount() -> Result<(), Box<dyn error::Error>> {
+        let body: SearchTermLevelReportingResponseBody =
+            serde_json::from_value(serde_json::json!({
+                "data": {
+                    "reportingDataResponse": {
+                        "row": [
+                            row(1, Some("term"), "AUTO", "AUTO", (100, 10, 0, "12")),
+                            row(1, Some("term"), "AUTO", "AUTO", (100, 10, 0, "1,2")),
+                        ]
+                    }
+                },
+                "pagination": {"totalResults": 2, "startIndex": 0, "itemsPerPage": 2}
+            }))?;
+
+        match mine_search_terms(
+            1,
+            body.data.reporting_data_response.row.iter(),
+            &SearchTermMiningThresholds::default(),
+        ) {
+            Err(SearchTermMiningError::AmountParseFailed(amount, _)) => {
+                assert_eq!(amount.as_ref(), "1,2");
+            }
+            x => panic!("{x:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mine_search_terms_with_v3_fixture() -> Result<(), Box<dyn error::Error>> {
+        let json_content = include_str!(
+            "../../tests/v3/response_body_json_files/get_search_term_level_reports.json",
+        );
+        let body: SearchTermLevelReportingResponseBody = serde_json::from_str(json_content)?;
+
+        let ret = mine_search_terms(
+            1,
+            body.data.reporting_data_response.row.iter(),
+            &SearchTermMiningThresholds::default(),
+        )?;
+        assert!(ret.is_empty());
+
+        Ok(())
+    }
+}
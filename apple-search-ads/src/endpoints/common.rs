@@ -1,13 +1,42 @@
 use core::fmt;
 
 use http_api_client_endpoint::{
-    http::{Error as HttpError, StatusCode},
-    Body,
+    http::{
+        header::{HeaderName, AUTHORIZATION},
+        Error as HttpError, HeaderMap, StatusCode,
+    },
+    Body, Request,
 };
 use serde_json::Error as SerdeJsonError;
 use url::ParseError as UrlParseError;
 
-use crate::objects::api_error_response::ApiErrorResponse;
+use crate::{
+    endpoints::{BASE_URL, DEFAULT_USER_AGENT, HEADER_KEY_X_AP_CONTEXT},
+    objects::api_error_response::ApiErrorResponse,
+};
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApiVersion {
+    #[default]
+    V4,
+    V5,
+}
+
+impl ApiVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::V4 => "v4",
+            Self::V5 => "v5",
+        }
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
 
 //
 #[derive(Debug, Clone)]
@@ -23,6 +52,7 @@ pub enum EndpointError {
     SerRequestBodyJsonFailed(SerdeJsonError),
     MakeRequestFailed(HttpError),
     DeResponseBodyOkJsonFailed(SerdeJsonError),
+    ReservedExtraHeader(HeaderName),
     Other(Box<dyn std::error::Error + Send + Sync + 'static>),
 }
 
@@ -33,3 +63,139 @@ impl fmt::Display for EndpointError {
 }
 
 impl std::error::Error for EndpointError {}
+
+//
+#[derive(Debug, Clone)]
+pub struct HttpMetadata {
+    pub base_url: Box<str>,
+    pub user_agent: Box<str>,
+    /// Replaces the same named default headers, `Authorization` and `X-AP-Context` are rejected.
+    pub extra_headers: HeaderMap,
+}
+
+impl Default for HttpMetadata {
+    fn default() -> Self {
+        Self {
+            base_url: BASE_URL.into(),
+            user_agent: DEFAULT_USER_AGENT.into(),
+            extra_headers: HeaderMap::new(),
+        }
+    }
+}
+
+impl HttpMetadata {
+    pub fn set_base_url(&mut self, val: impl AsRef<str>) -> &mut Self {
+        self.base_url = val.as_ref().trim_end_matches('/').into();
+        self
+    }
+
+    pub fn set_user_agent(&mut self, val: impl AsRef<str>) -> &mut Self {
+        self.user_agent = val.as_ref().into();
+        self
+    }
+
+    pub fn set_extra_headers(&mut self, val: HeaderMap) -> &mut Self {
+        self.extra_headers = val;
+        self
+    }
+
+    // base_url is public, so it may be assigned with a trailing slash.
+    pub(crate) fn url(&self, api_version: ApiVersion, path: impl AsRef<str>) -> String {
+        format!(
+            "{}/{}{}",
+            self.base_url.trim_end_matches('/'),
+            api_version,
+            path.as_ref()
+        )
+    }
+
+    pub(crate) fn apply_extra_headers(
+        &self,
+        request: &mut Request<Body>,
+    ) -> Result<(), EndpointError> {
+        if let Some(key) = self.extra_headers.keys().find(|x| {
+            **x == AUTHORIZATION || x.as_str().eq_ignore_ascii_case(HEADER_KEY_X_AP_CONTEXT)
+        }) {
+            return Err(EndpointError::ReservedExtraHeader(key.to_owned()));
+        }
+
+        let headers = request.headers_mut();
+        for key in self.extra_headers.keys() {
+            headers.remove(key);
+        }
+        for (key, value) in self.extra_headers.iter() {
+            headers.append(key, value.to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http_api_client_endpoint::http::header::{ACCEPT, USER_AGENT};
+
+    #[test]
+    fn test_http_metadata() {
+        let mut http_metadata = HttpMetadata::default();
+        assert_eq!(
+            http_metadata.url(ApiVersion::V4, "/me"),
+            "https://api.searchads.apple.com/api/v4/me"
+        );
+
+        let mut extra_headers = HeaderMap::new();
+        extra_headers.insert("X-Request-Id", "1".parse().unwrap());
+        extra_headers.insert(ACCEPT, "application/vnd.test+json".parse().unwrap());
+        http_metadata
+            .set_base_url("http://127.0.0.1:8080/api/")
+            .set_user_agent("my-app/1.0")
+            .set_extra_headers(extra_headers);
+        assert_eq!(
+            http_metadata.url(ApiVersion::V5, "/me"),
+            "http://127.0.0.1:8080/api/v5/me"
+        );
+        assert_eq!(http_metadata.user_agent.as_ref(), "my-app/1.0");
+
+        let mut request = Request::builder()
+            .header(USER_AGENT, "apple-search-ads")
+            .header(ACCEPT, "application/json")
+            .header(AUTHORIZATION, "Bearer TOKEN")
+            .body(vec![])
+            .unwrap();
+        http_metadata.apply_extra_headers(&mut request).unwrap();
+        assert_eq!(request.headers().get("X-Request-Id").unwrap(), "1");
+        assert_eq!(request.headers().get_all("Accept").iter().count(), 1);
+        assert_eq!(
+            request.headers().get("Accept").unwrap(),
+            "application/vnd.test+json"
+        );
+        assert_eq!(
+            request.headers().get("Authorization").unwrap(),
+            "Bearer TOKEN"
+        );
+    }
+
+    #[test]
+    fn test_http_metadata_with_reserved_extra_headers() {
+        for key in ["Authorization", "X-AP-Context"] {
+            let mut extra_headers = HeaderMap::new();
+            extra_headers.insert(
+                HeaderName::from_bytes(key.as_bytes()).unwrap(),
+                "x".parse().unwrap(),
+            );
+
+            let mut http_metadata = HttpMetadata::default();
+            http_metadata.set_extra_headers(extra_headers);
+
+            let mut request = Request::builder().body(vec![]).unwrap();
+            match http_metadata.apply_extra_headers(&mut request) {
+                Err(EndpointError::ReservedExtraHeader(name)) => {
+                    assert!(name.as_str().eq_ignore_ascii_case(key))
+                }
+                x => panic!("{x:?}"),
+            }
+        }
+    }
+}
@@ -11,12 +11,14 @@ use url::Url;
 
 use crate::{
     endpoints::{
-        common::{EndpointError, EndpointRet},
+        common::{ApiVersion, EndpointError, EndpointRet, HttpMetadata},
         HEADER_KEY_X_AP_CONTEXT,
     },
     objects::{campaign_list_response::CampaignListResponse, pagination::Pagination},
 };
 
+pub const URL_PATH: &str = "/campaigns";
+#[deprecated(note = "use URL_PATH with BASE_URL and ApiVersion")]
 pub const URL: &str = "https://api.searchads.apple.com/api/v4/campaigns";
 
 //
@@ -26,6 +28,9 @@ pub struct GetAllCampaigns {
     pub pagination: Option<Pagination>,
     //
     pub access_token: Box<str>,
+    //
+    pub api_version: ApiVersion,
+    pub http_metadata: HttpMetadata,
 }
 
 impl GetAllCampaigns {
@@ -34,6 +39,8 @@ impl GetAllCampaigns {
             org_id,
             pagination: None,
             access_token: access_token.as_ref().into(),
+            api_version: ApiVersion::default(),
+            http_metadata: HttpMetadata::default(),
         }
     }
 
@@ -41,6 +48,16 @@ impl GetAllCampaigns {
         self.pagination = pagination.into();
         self
     }
+
+    pub fn set_api_version(&mut self, val: ApiVersion) -> &mut Self {
+        self.api_version = val;
+        self
+    }
+
+    pub fn set_http_metadata(&mut self, val: HttpMetadata) -> &mut Self {
+        self.http_metadata = val;
+        self
+    }
 }
 
 impl Endpoint for GetAllCampaigns {
@@ -50,7 +67,8 @@ impl Endpoint for GetAllCampaigns {
     type ParseResponseError = EndpointError;
 
     fn render_request(&self) -> Result<Request<Body>, Self::RenderRequestError> {
-        let mut url = Url::parse(URL).map_err(EndpointError::MakeRequestUrlFailed)?;
+        let mut url = Url::parse(self.http_metadata.url(self.api_version, URL_PATH).as_str())
+            .map_err(EndpointError::MakeRequestUrlFailed)?;
 
         if let Some(ref pagination) = self.pagination {
             if let Some(limit) = pagination.limit {
@@ -63,10 +81,10 @@ impl Endpoint for GetAllCampaigns {
             }
         }
 
-        let request = Request::builder()
+        let mut request = Request::builder()
             .method(Method::GET)
             .uri(url.as_str())
-            .header(USER_AGENT, "apple-search-ads")
+            .header(USER_AGENT, self.http_metadata.user_agent.as_ref())
             .header(ACCEPT, MIME_APPLICATION_JSON)
             .header(CONTENT_TYPE, MIME_APPLICATION_JSON)
             .header(AUTHORIZATION, format!("Bearer {}", self.access_token))
@@ -74,6 +92,8 @@ impl Endpoint for GetAllCampaigns {
             .body(vec![])
             .map_err(EndpointError::MakeRequestFailed)?;
 
+        self.http_metadata.apply_extra_headers(&mut request)?;
+
         Ok(request)
     }
 
@@ -102,6 +122,8 @@ impl Endpoint for GetAllCampaigns {
 mod tests {
     use super::*;
 
+    use http_api_client_endpoint::http::HeaderMap;
+
     #[test]
     fn test_render_request() {
         let ep = GetAllCampaigns::new(1, "TOKEN");
@@ -114,4 +136,43 @@ mod tests {
         assert_eq!(req.headers().get("Authorization").unwrap(), "Bearer TOKEN");
         assert_eq!(req.headers().get("X-AP-Context").unwrap(), "orgId=1");
     }
+
+    #[test]
+    fn test_render_request_with_v5() {
+        let mut ep = GetAllCampaigns::new(1, "TOKEN");
+        ep.set_api_version(ApiVersion::V5);
+        let req = ep.render_request().unwrap();
+        assert_eq!(
+            req.uri(),
+            "https://api.searchads.apple.com/api/v5/campaigns"
+        );
+    }
+
+    #[test]
+    fn test_render_request_with_http_metadata() {
+        let mut extra_headers = HeaderMap::new();
+        extra_headers.insert("X-Request-Id", "1".parse().unwrap());
+        extra_headers.insert(ACCEPT, "application/vnd.test+json".parse().unwrap());
+
+        let mut http_metadata = HttpMetadata::default();
+        http_metadata
+            .set_user_agent("my-app/1.0")
+            .set_extra_headers(extra_headers);
+        // Assigned directly, with a trailing slash
+        http_metadata.base_url = "http://127.0.0.1:8080/api/".into();
+
+        let mut ep = GetAllCampaigns::new(1, "TOKEN");
+        ep.set_http_metadata(http_metadata);
+        let req = ep.render_request().unwrap();
+        assert_eq!(req.uri(), "http://127.0.0.1:8080/api/v4/campaigns");
+        assert_eq!(req.headers().get("User-Agent").unwrap(), "my-app/1.0");
+        assert_eq!(req.headers().get("X-Request-Id").unwrap(), "1");
+        assert_eq!(req.headers().get_all("Accept").iter().count(), 1);
+        assert_eq!(
+            req.headers().get("Accept").unwrap(),
+            "application/vnd.test+json"
+        );
+        assert_eq!(req.headers().get("Authorization").unwrap(), "Bearer TOKEN");
+        assert_eq!(req.headers().get("X-AP-Context").unwrap(), "orgId=1");
+    }
 }
@@ -14,7 +14,7 @@ use url::Url;
 
 use crate::{
     endpoints::{
-        common::{EndpointError, EndpointRet},
+        common::{ApiVersion, EndpointError, EndpointRet, HttpMetadata},
         HEADER_KEY_X_AP_CONTEXT,
     },
     objects::{
@@ -23,6 +23,8 @@ use crate::{
     },
 };
 
+pub const URL_PATH: &str = "/reports/campaigns/{}/searchterms";
+#[deprecated(note = "use URL_PATH with BASE_URL and ApiVersion")]
 pub const URL: &str = "https://api.searchads.apple.com/api/v4/reports/campaigns/{}/searchterms";
 
 //
@@ -34,6 +36,9 @@ pub struct CustomizableGetSearchTermLevelReports<M, I> {
     //
     pub access_token: Box<str>,
     //
+    pub api_version: ApiVersion,
+    pub http_metadata: HttpMetadata,
+    //
     phantom_m: PhantomData<M>,
     phantom_i: PhantomData<I>,
 }
@@ -49,10 +54,22 @@ impl<M, I> CustomizableGetSearchTermLevelReports<M, I> {
             campaign_id,
             reporting_request,
             access_token: access_token.as_ref().into(),
+            api_version: ApiVersion::default(),
+            http_metadata: HttpMetadata::default(),
             phantom_m: PhantomData,
             phantom_i: PhantomData,
         }
     }
+
+    pub fn set_api_version(&mut self, val: ApiVersion) -> &mut Self {
+        self.api_version = val;
+        self
+    }
+
+    pub fn set_http_metadata(&mut self, val: HttpMetadata) -> &mut Self {
+        self.http_metadata = val;
+        self
+    }
 }
 
 impl<M, I> Endpoint for CustomizableGetSearchTermLevelReports<M, I>
@@ -67,21 +84,22 @@ where
 
     fn render_request(&self) -> Result<Request<Body>, Self::RenderRequestError> {
         let url = Url::parse(
-            format!(
-                "https://api.searchads.apple.com/api/v4/reports/campaigns/{}/searchterms",
-                self.campaign_id
-            )
-            .as_str(),
+            self.http_metadata
+                .url(
+                    self.api_version,
+                    format!("/reports/campaigns/{}/searchterms", self.campaign_id),
+                )
+                .as_str(),
         )
         .map_err(EndpointError::MakeRequestUrlFailed)?;
 
         let body = serde_json::to_vec(&self.reporting_request)
             .map_err(EndpointError::SerRequestBodyJsonFailed)?;
 
-        let request = Request::builder()
+        let mut request = Request::builder()
             .method(Method::POST)
             .uri(url.as_str())
-            .header(USER_AGENT, "apple-search-ads")
+            .header(USER_AGENT, self.http_metadata.user_agent.as_ref())
             .header(ACCEPT, MIME_APPLICATION_JSON)
             .header(CONTENT_TYPE, MIME_APPLICATION_JSON)
             .header(AUTHORIZATION, format!("Bearer {}", self.access_token))
@@ -89,6 +107,8 @@ where
             .body(body)
             .map_err(EndpointError::MakeRequestFailed)?;
 
+        self.http_metadata.apply_extra_headers(&mut request)?;
+
         Ok(request)
     }
 
@@ -136,4 +156,15 @@ mod tests {
         assert_eq!(req.headers().get("X-AP-Context").unwrap(), "orgId=1");
         assert!(!req.body().is_empty());
     }
+
+    #[test]
+    fn test_render_request_with_v5() {
+        let mut ep = GetSearchTermLevelReports::new(1, 2, Default::default(), "TOKEN");
+        ep.set_api_version(ApiVersion::V5);
+        let req = ep.render_request().unwrap();
+        assert_eq!(
+            req.uri(),
+            "https://api.searchads.apple.com/api/v5/reports/campaigns/2/searchterms"
+        );
+    }
 }
@@ -7,26 +7,44 @@ use http_api_client_endpoint::{
     },
     Body, Endpoint, Request, Response, MIME_APPLICATION_JSON,
 };
+use url::Url;
 
 use crate::{
-    endpoints::common::{EndpointError, EndpointRet},
+    endpoints::common::{ApiVersion, EndpointError, EndpointRet, HttpMetadata},
     objects::user_acl_list_response::UserAclListResponse,
 };
 
+pub const URL_PATH: &str = "/acls";
+#[deprecated(note = "use URL_PATH with BASE_URL and ApiVersion")]
 pub const URL: &str = "https://api.searchads.apple.com/api/v4/acls";
 
 //
 #[derive(Debug, Clone)]
 pub struct GetUserAcl {
     pub access_token: Box<str>,
+    //
+    pub api_version: ApiVersion,
+    pub http_metadata: HttpMetadata,
 }
 
 impl GetUserAcl {
     pub fn new(access_token: impl AsRef<str>) -> Self {
         Self {
             access_token: access_token.as_ref().into(),
+            api_version: ApiVersion::default(),
+            http_metadata: HttpMetadata::default(),
         }
     }
+
+    pub fn set_api_version(&mut self, val: ApiVersion) -> &mut Self {
+        self.api_version = val;
+        self
+    }
+
+    pub fn set_http_metadata(&mut self, val: HttpMetadata) -> &mut Self {
+        self.http_metadata = val;
+        self
+    }
 }
 
 impl Endpoint for GetUserAcl {
@@ -36,16 +54,21 @@ impl Endpoint for GetUserAcl {
     type ParseResponseError = EndpointError;
 
     fn render_request(&self) -> Result<Request<Body>, Self::RenderRequestError> {
-        let request = Request::builder()
+        let url = Url::parse(self.http_metadata.url(self.api_version, URL_PATH).as_str())
+            .map_err(EndpointError::MakeRequestUrlFailed)?;
+
+        let mut request = Request::builder()
             .method(Method::GET)
-            .uri(URL)
-            .header(USER_AGENT, "apple-search-ads")
+            .uri(url.as_str())
+            .header(USER_AGENT, self.http_metadata.user_agent.as_ref())
             .header(ACCEPT, MIME_APPLICATION_JSON)
             .header(CONTENT_TYPE, MIME_APPLICATION_JSON)
             .header(AUTHORIZATION, format!("Bearer {}", self.access_token))
             .body(vec![])
             .map_err(EndpointError::MakeRequestFailed)?;
 
+        self.http_metadata.apply_extra_headers(&mut request)?;
+
         Ok(request)
     }
 
@@ -82,4 +105,12 @@ mod tests {
         assert_eq!(req.uri(), "https://api.searchads.apple.com/api/v4/acls");
         assert_eq!(req.headers().get("Authorization").unwrap(), "Bearer TOKEN");
     }
+
+    #[test]
+    fn test_render_request_with_v5() {
+        let mut ep = GetUserAcl::new("TOKEN");
+        ep.set_api_version(ApiVersion::V5);
+        let req = ep.render_request().unwrap();
+        assert_eq!(req.uri(), "https://api.searchads.apple.com/api/v5/acls");
+    }
 }
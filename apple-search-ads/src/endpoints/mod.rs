@@ -1,7 +1,11 @@
 //
 pub mod common;
 
-pub use common::{EndpointError, EndpointRet};
+pub use common::{ApiVersion, EndpointError, EndpointRet, HttpMetadata};
+
+pub const BASE_URL: &str = "https://api.searchads.apple.com/api";
+
+pub const DEFAULT_USER_AGENT: &str = "apple-search-ads";
 
 pub const HEADER_KEY_X_AP_CONTEXT: &str = "X-AP-Context";
 
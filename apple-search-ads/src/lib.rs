@@ -1,4 +1,5 @@
 //
+pub mod analysis;
 pub mod endpoints;
 pub mod objects;
 pub mod types;
@@ -0,0 +1,71 @@
+// https://developer.apple.com/documentation/apple_search_ads/adgroup
+
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+
+//
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum AdGroupStatus {
+    #[allow(clippy::upper_case_acronyms)]
+    ENABLED,
+    #[allow(clippy::upper_case_acronyms)]
+    PAUSED,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum AdGroupServingStatus {
+    #[allow(clippy::upper_case_acronyms)]
+    RUNNING,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    NOT_RUNNING,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum AdGroupDisplayStatus {
+    #[allow(clippy::upper_case_acronyms)]
+    RUNNING,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    ON_HOLD,
+    #[allow(clippy::upper_case_acronyms)]
+    PAUSED,
+    #[allow(clippy::upper_case_acronyms)]
+    DELETED,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum AdGroupServingStateReason {
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    AD_GROUP_PAUSED_BY_USER,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    ADGROUP_END_DATE_REACHED,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    APP_NOT_SUPPORT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    AUDIENCE_BELOW_THRESHOLD,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    CAMPAIGN_NOT_RUNNING,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    DELETED_BY_USER,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    PENDING_AUDIENCE_VERIFICATION,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    START_DATE_IN_THE_FUTURE,
+    //
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum AdGroupPricingModel {
+    #[allow(clippy::upper_case_acronyms)]
+    CPC,
+    #[allow(clippy::upper_case_acronyms)]
+    CPM,
+    #[serde(other)]
+    Other(Box<str>),
+}
@@ -20,6 +20,10 @@ pub struct Campaign {
     #[serde(rename = "adamId")]
     pub adam_id: u64,
 
+    // v5
+    #[serde(rename = "billingEvent", skip_serializing_if = "Option::is_none")]
+    pub billing_event: Option<CampaignBillingEvent>,
+
     #[serde(rename = "budgetAmount", skip_serializing_if = "Option::is_none")]
     pub budget_amount: Option<Money>,
 
@@ -158,7 +162,17 @@ pub enum CampaignAdChannelType {
     Other(Box<str>),
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum CampaignBillingEvent {
+    #[allow(clippy::upper_case_acronyms)]
+    TAPS,
+    #[allow(clippy::upper_case_acronyms)]
+    IMPRESSIONS,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
 pub enum CampaignDisplayStatus {
     #[allow(clippy::upper_case_acronyms)]
     RUNNING,
@@ -168,6 +182,8 @@ pub enum CampaignDisplayStatus {
     PAUSED,
     #[allow(clippy::upper_case_acronyms)]
     DELETED,
+    #[serde(other)]
+    Other(Box<str>),
 }
 
 #[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
@@ -237,6 +253,8 @@ pub enum CampaignServingStatus {
     RUNNING,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     NOT_RUNNING,
+    #[serde(other)]
+    Other(Box<str>),
 }
 
 #[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
@@ -245,12 +263,20 @@ pub enum CampaignStatus {
     ENABLED,
     #[allow(clippy::upper_case_acronyms)]
     PAUSED,
+    #[serde(other)]
+    Other(Box<str>),
 }
 
 #[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
 pub enum CampaignSupplySource {
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     APPSTORE_SEARCH_RESULTS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    APPSTORE_SEARCH_TAB,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    APPSTORE_TODAY_TAB,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    APPSTORE_PRODUCT_PAGES_BROWSE,
     #[allow(clippy::upper_case_acronyms)]
     NEWS,
     #[allow(clippy::upper_case_acronyms)]
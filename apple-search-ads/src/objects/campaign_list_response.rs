@@ -16,7 +16,10 @@ mod tests {
 
     use std::error;
 
-    use crate::types::payment_model::PaymentModel;
+    use crate::{
+        objects::campaign::{CampaignAdChannelType, CampaignBillingEvent, CampaignSupplySource},
+        types::payment_model::PaymentModel,
+    };
 
     #[test]
     fn test_v3_de() -> Result<(), Box<dyn error::Error>> {
@@ -46,4 +49,38 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_v5_de() -> Result<(), Box<dyn error::Error>> {
+        let json_content =
+            include_str!("../../tests/v5/response_body_json_files/campaign_list_response.json");
+
+        let body: CampaignListResponse = serde_json::from_str(json_content)?;
+        println!("{body:?}");
+
+        assert_eq!(body.data.len(), 2);
+        assert_eq!(body.pagination.total_results, 2);
+
+        let campaign = &body.data[0];
+        assert_eq!(campaign.payment_model, PaymentModel::PAYG);
+        assert_eq!(campaign.billing_event, Some(CampaignBillingEvent::TAPS));
+        assert_eq!(
+            campaign.supply_sources,
+            vec![CampaignSupplySource::APPSTORE_SEARCH_RESULTS]
+        );
+
+        let campaign = &body.data[1];
+        assert_eq!(campaign.ad_channel_type, CampaignAdChannelType::DISPLAY);
+        assert_eq!(
+            campaign.billing_event,
+            Some(CampaignBillingEvent::IMPRESSIONS)
+        );
+        assert_eq!(
+            campaign.supply_sources,
+            vec![CampaignSupplySource::APPSTORE_TODAY_TAB]
+        );
+        assert!(campaign.end_time.is_some());
+
+        Ok(())
+    }
 }
@@ -45,6 +45,45 @@ pub struct ExtendedSpendRow {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ttr: Option<f64>,
+
+    //
+    // v5
+    //
+    #[serde(rename = "tapInstalls", skip_serializing_if = "Option::is_none")]
+    pub tap_installs: Option<u64>,
+
+    #[serde(rename = "viewInstalls", skip_serializing_if = "Option::is_none")]
+    pub view_installs: Option<u64>,
+
+    #[serde(rename = "totalInstalls", skip_serializing_if = "Option::is_none")]
+    pub total_installs: Option<u64>,
+
+    #[serde(rename = "tapNewDownloads", skip_serializing_if = "Option::is_none")]
+    pub tap_new_downloads: Option<u64>,
+
+    #[serde(rename = "tapRedownloads", skip_serializing_if = "Option::is_none")]
+    pub tap_redownloads: Option<u64>,
+
+    #[serde(rename = "viewNewDownloads", skip_serializing_if = "Option::is_none")]
+    pub view_new_downloads: Option<u64>,
+
+    #[serde(rename = "viewRedownloads", skip_serializing_if = "Option::is_none")]
+    pub view_redownloads: Option<u64>,
+
+    #[serde(rename = "tapInstallCPI", skip_serializing_if = "Option::is_none")]
+    pub tap_install_cpi: Option<Money>,
+
+    #[serde(rename = "totalAvgCPI", skip_serializing_if = "Option::is_none")]
+    pub total_avg_cpi: Option<Money>,
+
+    #[serde(rename = "tapInstallRate", skip_serializing_if = "Option::is_none")]
+    pub tap_install_rate: Option<f64>,
+
+    #[serde(rename = "totalInstallRate", skip_serializing_if = "Option::is_none")]
+    pub total_install_rate: Option<f64>,
+
+    #[serde(rename = "avgCPM", skip_serializing_if = "Option::is_none")]
+    pub avg_cpm: Option<Money>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
@@ -122,6 +161,7 @@ mod tests {
 
     #[test]
     fn test_ser_and_de_date() -> Result<(), Box<dyn error::Error>> {
+        #[allow(non_local_definitions)]
         impl ExtendedSpendRowDate {
             fn from_ymd(year: i32, month: u32, day: u32) -> Self {
                 Self::Date(NaiveDate::from_ymd_opt(year, month, day).expect(""))
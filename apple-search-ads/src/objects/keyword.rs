@@ -1,12 +1,104 @@
 // https://developer.apple.com/documentation/apple_search_ads/keyword
 
+use serde::{Deserialize, Serialize};
 use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
 
+use crate::objects::money::Money;
+
 //
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Keyword {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+
+    #[serde(rename = "adGroupId", skip_serializing_if = "Option::is_none")]
+    pub ad_group_id: Option<u64>,
+
+    pub text: Box<str>,
+
+    #[serde(rename = "matchType")]
+    pub match_type: KeywordMatchType,
+
+    #[serde(rename = "bidAmount", skip_serializing_if = "Option::is_none")]
+    pub bid_amount: Option<Money>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<KeywordStatus>,
+}
+impl Keyword {
+    pub fn new(text: impl AsRef<str>, match_type: KeywordMatchType) -> Self {
+        Self {
+            id: None,
+            ad_group_id: None,
+            text: text.as_ref().into(),
+            match_type,
+            bid_amount: None,
+            status: None,
+        }
+    }
+
+    pub fn set_ad_group_id(&mut self, val: impl Into<Option<u64>>) -> &mut Self {
+        self.ad_group_id = val.into();
+        self
+    }
+
+    pub fn set_bid_amount(&mut self, val: impl Into<Option<Money>>) -> &mut Self {
+        self.bid_amount = val.into();
+        self
+    }
+
+    pub fn set_status(&mut self, val: impl Into<Option<KeywordStatus>>) -> &mut Self {
+        self.status = val.into();
+        self
+    }
+}
+
 #[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
 pub enum KeywordMatchType {
     #[allow(clippy::upper_case_acronyms)]
     EXACT,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     BROAD,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum KeywordStatus {
+    #[allow(clippy::upper_case_acronyms)]
+    ACTIVE,
+    #[allow(clippy::upper_case_acronyms)]
+    PAUSED,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::types::currency::Currency;
+
+    #[test]
+    fn test_ser() -> Result<(), Box<dyn error::Error>> {
+        let mut keyword = Keyword::new("foo", KeywordMatchType::EXACT);
+        assert_eq!(
+            serde_json::to_value(&keyword)?,
+            serde_json::json!({"text": "foo", "matchType": "EXACT"})
+        );
+
+        keyword.set_bid_amount(Money::new(Currency::USD, "1.5"));
+        assert_eq!(
+            serde_json::to_value(&keyword)?,
+            serde_json::json!({
+                "text": "foo",
+                "matchType": "EXACT",
+                "bidAmount": {"currency": "USD", "amount": "1.5"}
+            })
+        );
+
+        Ok(())
+    }
 }
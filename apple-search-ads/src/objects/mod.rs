@@ -45,8 +45,8 @@ pub mod campaign_country_or_region_serving_state_reasons;
 pub mod campaign_list_response;
 
 pub use campaign::{
-    Campaign, CampaignAdChannelType, CampaignDisplayStatus, CampaignServingStateReason,
-    CampaignServingStatus, CampaignStatus, CampaignSupplySource,
+    Campaign, CampaignAdChannelType, CampaignBillingEvent, CampaignDisplayStatus,
+    CampaignServingStateReason, CampaignServingStatus, CampaignStatus, CampaignSupplySource,
 };
 pub use campaign_country_or_region_serving_state_reasons::{
     CampaignCountryOrRegionServingStateReason, CampaignCountryOrRegionServingStateReasons,
@@ -62,12 +62,24 @@ pub mod money;
 pub use loc_invoice_details::LOCInvoiceDetails;
 pub use money::Money;
 
+//
+// Ad Group Request and Response Objects
+//
+pub mod ad_group;
+
+pub use ad_group::{
+    AdGroupDisplayStatus, AdGroupPricingModel, AdGroupServingStateReason, AdGroupServingStatus,
+    AdGroupStatus,
+};
+
 //
 // Keywords Request and Response Objects
 //
 pub mod keyword;
+pub mod negative_keyword;
 
-pub use keyword::KeywordMatchType;
+pub use keyword::{Keyword, KeywordMatchType, KeywordStatus};
+pub use negative_keyword::NegativeKeyword;
 
 //
 // Reports Request and Response Objects
@@ -96,6 +108,9 @@ pub use keyword_insights::KeywordInsights;
 pub use reporting_ad_group::ReportingAdGroup;
 pub use reporting_campaign::ReportingCampaign;
 pub use reporting_data_response::ReportingDataResponse;
+pub use reporting_keyword::{
+    ReportingKeyword, ReportingKeywordDisplayStatus, ReportingKeywordMatchType,
+};
 pub use reporting_request::{
     ReportingRequest, ReportingRequestGranularity, ReportingRequestGroupBy,
     ReportingRequestTimeZone,
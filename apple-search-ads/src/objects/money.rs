@@ -1,5 +1,7 @@
 // https://developer.apple.com/documentation/apple_search_ads/money
 
+use std::num::ParseFloatError;
+
 use serde::{Deserialize, Serialize};
 
 use crate::types::currency::Currency;
@@ -10,6 +12,19 @@ pub struct Money {
 
     pub amount: Box<str>,
 }
+impl Money {
+    pub fn new(currency: Currency, amount: impl AsRef<str>) -> Self {
+        Self {
+            currency,
+            amount: amount.as_ref().into(),
+        }
+    }
+
+    pub fn amount_f64(&self) -> Result<f64, ParseFloatError> {
+        self.amount.parse()
+    }
+}
+
 impl Default for Money {
     fn default() -> Self {
         Self {
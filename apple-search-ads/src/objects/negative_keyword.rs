@@ -0,0 +1,53 @@
+// https://developer.apple.com/documentation/apple_search_ads/negativekeyword
+
+use serde::{Deserialize, Serialize};
+
+use crate::objects::keyword::{KeywordMatchType, KeywordStatus};
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct NegativeKeyword {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+
+    #[serde(rename = "campaignId", skip_serializing_if = "Option::is_none")]
+    pub campaign_id: Option<u64>,
+
+    #[serde(rename = "adGroupId", skip_serializing_if = "Option::is_none")]
+    pub ad_group_id: Option<u64>,
+
+    pub text: Box<str>,
+
+    #[serde(rename = "matchType")]
+    pub match_type: KeywordMatchType,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<KeywordStatus>,
+}
+impl NegativeKeyword {
+    pub fn new(text: impl AsRef<str>, match_type: KeywordMatchType) -> Self {
+        Self {
+            id: None,
+            campaign_id: None,
+            ad_group_id: None,
+            text: text.as_ref().into(),
+            match_type,
+            status: None,
+        }
+    }
+
+    pub fn set_campaign_id(&mut self, val: impl Into<Option<u64>>) -> &mut Self {
+        self.campaign_id = val.into();
+        self
+    }
+
+    pub fn set_ad_group_id(&mut self, val: impl Into<Option<u64>>) -> &mut Self {
+        self.ad_group_id = val.into();
+        self
+    }
+
+    pub fn set_status(&mut self, val: impl Into<Option<KeywordStatus>>) -> &mut Self {
+        self.status = val.into();
+        self
+    }
+}
@@ -1,13 +1,23 @@
 // https://developer.apple.com/documentation/apple_search_ads/reportingadgroup
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::types::region::Region;
+use crate::{
+    objects::{
+        ad_group::{
+            AdGroupDisplayStatus, AdGroupPricingModel, AdGroupServingStateReason,
+            AdGroupServingStatus, AdGroupStatus,
+        },
+        campaign::campaign_option_date_format,
+        money::Money,
+    },
+    types::{age_range::AgeRange, device_class::DeviceClass, gender::Gender, region::Region},
+};
 
 //
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ReportingAdGroup {
-    // TODO
     #[serde(rename = "adGroupId")]
     pub ad_group_id: u64,
 
@@ -20,7 +30,144 @@ pub struct ReportingAdGroup {
     #[serde(rename = "orgId")]
     pub org_id: u64,
 
+    #[serde(rename = "adGroupStatus", skip_serializing_if = "Option::is_none")]
+    pub ad_group_status: Option<AdGroupStatus>,
+
+    #[serde(
+        rename = "adGroupServingStatus",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ad_group_serving_status: Option<AdGroupServingStatus>,
+
+    #[serde(
+        rename = "adGroupServingStateReasons",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ad_group_serving_state_reasons: Option<Vec<AdGroupServingStateReason>>,
+
+    #[serde(
+        rename = "adGroupDisplayStatus",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ad_group_display_status: Option<AdGroupDisplayStatus>,
+
+    #[serde(rename = "pricingModel", skip_serializing_if = "Option::is_none")]
+    pub pricing_model: Option<AdGroupPricingModel>,
+
+    #[serde(
+        rename = "automatedKeywordsOptIn",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub automated_keywords_opt_in: Option<bool>,
+
+    // v4 only, see default_bid
+    #[serde(rename = "defaultCpcBid", skip_serializing_if = "Option::is_none")]
+    pub default_cpc_bid: Option<Money>,
+
+    // v5 renamed defaultCpcBid to defaultBidAmount
+    #[serde(rename = "defaultBidAmount", skip_serializing_if = "Option::is_none")]
+    pub default_bid_amount: Option<Money>,
+
+    #[serde(rename = "cpaGoal", skip_serializing_if = "Option::is_none")]
+    pub cpa_goal: Option<Money>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<bool>,
+
+    #[serde(
+        default,
+        with = "campaign_option_date_format",
+        rename = "startTime",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub start_time: Option<DateTime<Utc>>,
+
+    #[serde(
+        default,
+        with = "campaign_option_date_format",
+        rename = "endTime",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub end_time: Option<DateTime<Utc>>,
+
     // Some when groupBy countryOrRegion
     #[serde(rename = "countryOrRegion", skip_serializing_if = "Option::is_none")]
     pub country_or_region: Option<Region>,
+
+    // Some when groupBy deviceClass
+    #[serde(rename = "deviceClass", skip_serializing_if = "Option::is_none")]
+    pub device_class: Option<DeviceClass>,
+
+    // Some when groupBy ageRange
+    #[serde(rename = "ageRange", skip_serializing_if = "Option::is_none")]
+    pub age_range: Option<AgeRange>,
+
+    // Some when groupBy gender
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gender: Option<Gender>,
+}
+
+impl ReportingAdGroup {
+    /// `defaultBidAmount` of v5 or `defaultCpcBid` of v4.
+    pub fn default_bid(&self) -> Option<&Money> {
+        self.default_bid_amount
+            .as_ref()
+            .or(self.default_cpc_bid.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_de_and_ser_default_bid() -> Result<(), Box<dyn error::Error>> {
+        for key in ["defaultCpcBid", "defaultBidAmount"] {
+            let value = json!({
+                "adGroupId": 1,
+                "adGroupName": "ad group 1",
+                "campaignId": 2,
+                "orgId": 3,
+                key: {"amount": "1.5", "currency": "USD"}
+            });
+
+            let ad_group: ReportingAdGroup = serde_json::from_value(value.to_owned())?;
+            assert_eq!(
+                ad_group.default_bid().map(|x| x.amount.as_ref()),
+                Some("1.5")
+            );
+            assert_eq!(serde_json::to_value(&ad_group)?, value);
+        }
+
+        Ok(())
+    }
+    #[test]
+    fn test_de_with_unknown_enum_values() -> Result<(), Box<dyn error::Error>> {
+        let value = json!({
+            "adGroupId": 1,
+            "adGroupName": "ad group 1",
+            "campaignId": 2,
+            "orgId": 3,
+            "adGroupStatus": "ARCHIVED",
+            "pricingModel": "CPT"
+        });
+
+        let ad_group: ReportingAdGroup = serde_json::from_value(value.to_owned())?;
+        assert_eq!(
+            ad_group.ad_group_status,
+            Some(AdGroupStatus::Other("ARCHIVED".into()))
+        );
+        assert_eq!(
+            ad_group.pricing_model,
+            Some(AdGroupPricingModel::Other("CPT".into()))
+        );
+        assert_eq!(serde_json::to_value(&ad_group)?, value);
+
+        Ok(())
+    }
 }
@@ -6,15 +6,15 @@ use serde::{Deserialize, Serialize};
 use crate::{
     objects::{
         campaign::{
-            campaign_date_format, CampaignAdChannelType, CampaignDisplayStatus,
-            CampaignServingStateReason, CampaignServingStatus, CampaignStatus,
-            CampaignSupplySource,
+            campaign_date_format, CampaignAdChannelType, CampaignBillingEvent,
+            CampaignDisplayStatus, CampaignServingStateReason, CampaignServingStatus,
+            CampaignStatus, CampaignSupplySource,
         },
         campaign_app_detail::CampaignAppDetail,
         campaign_country_or_region_serving_state_reasons::CampaignCountryOrRegionServingStateReasons,
         money::Money,
     },
-    types::region::Region,
+    types::{age_range::AgeRange, device_class::DeviceClass, gender::Gender, region::Region},
 };
 
 //
@@ -65,6 +65,10 @@ pub struct ReportingCampaign {
     #[serde(rename = "adChannelType")]
     pub ad_channel_type: CampaignAdChannelType,
 
+    // v5
+    #[serde(rename = "billingEvent", skip_serializing_if = "Option::is_none")]
+    pub billing_event: Option<CampaignBillingEvent>,
+
     #[serde(rename = "orgId")]
     pub org_id: u64,
 
@@ -74,4 +78,16 @@ pub struct ReportingCampaign {
     // Some when groupBy countryOrRegion
     #[serde(rename = "countryOrRegion", skip_serializing_if = "Option::is_none")]
     pub country_or_region: Option<Region>,
+
+    // Some when groupBy deviceClass
+    #[serde(rename = "deviceClass", skip_serializing_if = "Option::is_none")]
+    pub device_class: Option<DeviceClass>,
+
+    // Some when groupBy ageRange
+    #[serde(rename = "ageRange", skip_serializing_if = "Option::is_none")]
+    pub age_range: Option<AgeRange>,
+
+    // Some when groupBy gender
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gender: Option<Gender>,
 }
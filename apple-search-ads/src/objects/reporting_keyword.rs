@@ -3,29 +3,61 @@
 use serde::{Deserialize, Serialize};
 use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
 
-use crate::types::region::Region;
+use crate::{
+    objects::{keyword::KeywordStatus, money::Money},
+    types::{age_range::AgeRange, device_class::DeviceClass, gender::Gender, region::Region},
+};
 
 //
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ReportingKeyword {
-    // TODO
     #[serde(rename = "keywordId")]
     pub keyword_id: u64,
 
     pub keyword: Box<str>,
 
+    #[serde(rename = "keywordStatus", skip_serializing_if = "Option::is_none")]
+    pub keyword_status: Option<KeywordStatus>,
+
+    #[serde(
+        rename = "keywordDisplayStatus",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub keyword_display_status: Option<ReportingKeywordDisplayStatus>,
+
     #[serde(rename = "matchType")]
     pub match_type: ReportingKeywordMatchType,
 
+    #[serde(rename = "bidAmount", skip_serializing_if = "Option::is_none")]
+    pub bid_amount: Option<Money>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<bool>,
+
     #[serde(rename = "adGroupId")]
     pub ad_group_id: u64,
 
     #[serde(rename = "adGroupName")]
     pub ad_group_name: Box<str>,
 
+    #[serde(rename = "adGroupDeleted", skip_serializing_if = "Option::is_none")]
+    pub ad_group_deleted: Option<bool>,
+
     // Some when groupBy countryOrRegion
     #[serde(rename = "countryOrRegion", skip_serializing_if = "Option::is_none")]
     pub country_or_region: Option<Region>,
+
+    // Some when groupBy deviceClass
+    #[serde(rename = "deviceClass", skip_serializing_if = "Option::is_none")]
+    pub device_class: Option<DeviceClass>,
+
+    // Some when groupBy ageRange
+    #[serde(rename = "ageRange", skip_serializing_if = "Option::is_none")]
+    pub age_range: Option<AgeRange>,
+
+    // Some when groupBy gender
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gender: Option<Gender>,
 }
 
 #[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
@@ -36,4 +68,18 @@ pub enum ReportingKeywordMatchType {
     EXACT,
     #[allow(clippy::upper_case_acronyms)]
     BROAD,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum ReportingKeywordDisplayStatus {
+    #[allow(clippy::upper_case_acronyms)]
+    RUNNING,
+    #[allow(clippy::upper_case_acronyms)]
+    ACTIVE,
+    #[allow(clippy::upper_case_acronyms)]
+    PAUSED,
+    #[serde(other)]
+    Other(Box<str>),
 }
@@ -45,12 +45,26 @@ mod tests {
 
     use std::error;
 
+    use crate::{
+        objects::{
+            ad_group::{AdGroupDisplayStatus, AdGroupServingStatus, AdGroupStatus},
+            campaign::{CampaignDisplayStatus, CampaignServingStatus},
+            keyword::KeywordStatus,
+            reporting_keyword::{ReportingKeywordDisplayStatus, ReportingKeywordMatchType},
+        },
+        types::device_class::DeviceClass,
+    };
+
     #[test]
     fn test_v3_de_get_campaign_level_reports() -> Result<(), Box<dyn error::Error>> {
         let json_content =
             include_str!("../../tests/v3/response_body_json_files/get_campaign_level_reports.json");
 
-        let _: CampaignLevelReportingResponseBody = serde_json::from_str(json_content)?;
+        let body: CampaignLevelReportingResponseBody = serde_json::from_str(json_content)?;
+
+        let metadata = &body.data.reporting_data_response.row[0].metadata;
+        assert_eq!(metadata.display_status, CampaignDisplayStatus::ON_HOLD);
+        assert_eq!(metadata.serving_status, CampaignServingStatus::NOT_RUNNING);
 
         Ok(())
     }
@@ -72,7 +86,21 @@ mod tests {
         let json_content =
             include_str!("../../tests/v3/response_body_json_files/get_ad_group_level_reports.json");
 
-        let _: AdGroupLevelReportingResponseBody = serde_json::from_str(json_content)?;
+        let body: AdGroupLevelReportingResponseBody = serde_json::from_str(json_content)?;
+
+        let metadata = &body.data.reporting_data_response.row[0].metadata;
+        assert_eq!(metadata.ad_group_status, Some(AdGroupStatus::ENABLED));
+        assert_eq!(
+            metadata.ad_group_serving_status,
+            Some(AdGroupServingStatus::RUNNING)
+        );
+        assert_eq!(
+            metadata.ad_group_display_status,
+            Some(AdGroupDisplayStatus::RUNNING)
+        );
+        assert_eq!(metadata.device_class, Some(DeviceClass::IPhone));
+        assert_eq!(metadata.age_range, None);
+        assert_eq!(metadata.gender, None);
 
         Ok(())
     }
@@ -82,7 +110,15 @@ mod tests {
         let json_content =
             include_str!("../../tests/v3/response_body_json_files/get_keyword_level_reports.json");
 
-        let _: KeywordLevelReportingResponseBody = serde_json::from_str(json_content)?;
+        let body: KeywordLevelReportingResponseBody = serde_json::from_str(json_content)?;
+
+        let metadata = &body.data.reporting_data_response.row[0].metadata;
+        assert_eq!(metadata.keyword_status, Some(KeywordStatus::ACTIVE));
+        assert_eq!(
+            metadata.keyword_display_status,
+            Some(ReportingKeywordDisplayStatus::RUNNING)
+        );
+        assert_eq!(metadata.match_type, ReportingKeywordMatchType::EXACT);
 
         Ok(())
     }
@@ -110,4 +146,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_v5_de_get_campaign_level_reports() -> Result<(), Box<dyn error::Error>> {
+        let json_content =
+            include_str!("../../tests/v5/response_body_json_files/get_campaign_level_reports.json");
+
+        let body: CampaignLevelReportingResponseBody = serde_json::from_str(json_content)?;
+
+        let row = &body.data.reporting_data_response.row[0];
+        assert!(row.total.is_none());
+        let granularity = row.granularity.as_ref().unwrap();
+        assert_eq!(granularity.len(), 2);
+        assert_eq!(granularity[0].installs, None);
+        assert_eq!(granularity[0].tap_installs, Some(12));
+        assert_eq!(granularity[0].view_installs, Some(3));
+        assert_eq!(granularity[0].total_installs, Some(15));
+        assert_eq!(
+            granularity[0]
+                .tap_install_cpi
+                .as_ref()
+                .map(|x| x.amount.as_ref()),
+            Some("2.5")
+        );
+        assert_eq!(
+            row.metadata.billing_event,
+            Some(crate::objects::campaign::CampaignBillingEvent::TAPS)
+        );
+        assert_eq!(row.metadata.display_status, CampaignDisplayStatus::RUNNING);
+
+        Ok(())
+    }
 }
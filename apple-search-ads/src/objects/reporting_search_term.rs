@@ -41,6 +41,8 @@ pub enum SearchTermSource {
     AUTO,
     #[allow(clippy::upper_case_acronyms)]
     TARGETED,
+    #[serde(other)]
+    Other(Box<str>),
 }
 
 #[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
@@ -51,4 +53,6 @@ pub enum ReportingSearchTermMatchType {
     EXACT,
     #[allow(clippy::upper_case_acronyms)]
     BROAD,
+    #[serde(other)]
+    Other(Box<str>),
 }
@@ -7,34 +7,116 @@ use crate::objects::money::Money;
 //
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SpendRow {
-    #[serde(rename = "avgCPA")]
-    pub avg_cpa: Money,
+    // v4 only, v5 uses tapInstallCPI and totalAvgCPI
+    #[serde(rename = "avgCPA", skip_serializing_if = "Option::is_none")]
+    pub avg_cpa: Option<Money>,
 
     #[serde(rename = "avgCPT")]
     pub avg_cpt: Money,
 
-    #[serde(rename = "conversionRate")]
-    pub conversion_rate: f64,
+    // v4 only, v5 uses tapInstallRate and totalInstallRate
+    #[serde(rename = "conversionRate", skip_serializing_if = "Option::is_none")]
+    pub conversion_rate: Option<f64>,
 
     pub impressions: u64,
 
-    pub installs: u64,
+    // v4 only, v5 uses tapInstalls, viewInstalls and totalInstalls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installs: Option<u64>,
 
-    #[serde(rename = "latOffInstalls")]
-    pub lat_off_installs: u64,
+    // v4 only
+    #[serde(rename = "latOffInstalls", skip_serializing_if = "Option::is_none")]
+    pub lat_off_installs: Option<u64>,
 
-    #[serde(rename = "latOnInstalls")]
-    pub lat_on_installs: u64,
+    // v4 only
+    #[serde(rename = "latOnInstalls", skip_serializing_if = "Option::is_none")]
+    pub lat_on_installs: Option<u64>,
 
     #[serde(rename = "localSpend")]
     pub local_spend: Money,
 
-    #[serde(rename = "newDownloads")]
-    pub new_downloads: u64,
+    // v4 only, v5 uses tapNewDownloads
+    #[serde(rename = "newDownloads", skip_serializing_if = "Option::is_none")]
+    pub new_downloads: Option<u64>,
 
-    pub redownloads: u64,
+    // v4 only, v5 uses tapRedownloads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redownloads: Option<u64>,
 
     pub taps: u64,
 
     pub ttr: f64,
+
+    //
+    // v5
+    //
+    #[serde(rename = "tapInstalls", skip_serializing_if = "Option::is_none")]
+    pub tap_installs: Option<u64>,
+
+    #[serde(rename = "viewInstalls", skip_serializing_if = "Option::is_none")]
+    pub view_installs: Option<u64>,
+
+    #[serde(rename = "totalInstalls", skip_serializing_if = "Option::is_none")]
+    pub total_installs: Option<u64>,
+
+    #[serde(rename = "tapNewDownloads", skip_serializing_if = "Option::is_none")]
+    pub tap_new_downloads: Option<u64>,
+
+    #[serde(rename = "tapRedownloads", skip_serializing_if = "Option::is_none")]
+    pub tap_redownloads: Option<u64>,
+
+    #[serde(rename = "viewNewDownloads", skip_serializing_if = "Option::is_none")]
+    pub view_new_downloads: Option<u64>,
+
+    #[serde(rename = "viewRedownloads", skip_serializing_if = "Option::is_none")]
+    pub view_redownloads: Option<u64>,
+
+    #[serde(rename = "tapInstallCPI", skip_serializing_if = "Option::is_none")]
+    pub tap_install_cpi: Option<Money>,
+
+    #[serde(rename = "totalAvgCPI", skip_serializing_if = "Option::is_none")]
+    pub total_avg_cpi: Option<Money>,
+
+    #[serde(rename = "tapInstallRate", skip_serializing_if = "Option::is_none")]
+    pub tap_install_rate: Option<f64>,
+
+    #[serde(rename = "totalInstallRate", skip_serializing_if = "Option::is_none")]
+    pub total_install_rate: Option<f64>,
+
+    #[serde(rename = "avgCPM", skip_serializing_if = "Option::is_none")]
+    pub avg_cpm: Option<Money>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_de_and_ser_v5() -> Result<(), Box<dyn error::Error>> {
+        let row: SpendRow = serde_json::from_value(json!({
+            "avgCPT": { "amount": "0.5", "currency": "USD" },
+            "impressions": 1000,
+            "localSpend": { "amount": "30", "currency": "USD" },
+            "taps": 60,
+            "ttr": 0.06,
+            "tapInstalls": 12,
+            "tapInstallCPI": { "amount": "2.5", "currency": "USD" }
+        }))?;
+        assert!(row.avg_cpa.is_none());
+        assert!(row.conversion_rate.is_none());
+        assert!(row.installs.is_none());
+        assert!(row.new_downloads.is_none());
+        assert_eq!(row.tap_installs, Some(12));
+
+        let value = serde_json::to_value(&row)?;
+        assert!(value.get("avgCPA").is_none());
+        assert!(value.get("installs").is_none());
+        assert_eq!(value["tapInstalls"], Value::from(12));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,33 @@
+// https://developer.apple.com/documentation/apple_search_ads/reportingrequest groupBy ageRange
+
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+
+//
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AgeRange {
+    #[serde(rename = "18-24")]
+    From18To24,
+    #[serde(rename = "25-34")]
+    From25To34,
+    #[serde(rename = "35-44")]
+    From35To44,
+    #[serde(rename = "45-54")]
+    From45To54,
+    #[serde(rename = "55-64")]
+    From55To64,
+    #[serde(rename = "65+")]
+    From65,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("65+".parse::<AgeRange>().unwrap(), AgeRange::From65);
+        assert_eq!(AgeRange::From18To24.to_string(), "18-24");
+    }
+}
@@ -0,0 +1,25 @@
+// https://developer.apple.com/documentation/apple_search_ads/reportingrequest groupBy deviceClass
+
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+
+//
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DeviceClass {
+    #[serde(rename = "iPhone")]
+    IPhone,
+    #[serde(rename = "iPad")]
+    IPad,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("iPad".parse::<DeviceClass>().unwrap(), DeviceClass::IPad);
+        assert_eq!(DeviceClass::IPhone.to_string(), "iPhone");
+    }
+}
@@ -0,0 +1,29 @@
+// https://developer.apple.com/documentation/apple_search_ads/reportingrequest groupBy gender
+
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+
+//
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Gender {
+    #[serde(rename = "M")]
+    Male,
+    #[serde(rename = "F")]
+    Female,
+    #[serde(other)]
+    Other(Box<str>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("M".parse::<Gender>().unwrap(), Gender::Male);
+        assert_eq!("F".parse::<Gender>().unwrap(), Gender::Female);
+        assert_eq!(
+            "UNKNOWN".parse::<Gender>().unwrap(),
+            Gender::Other("UNKNOWN".into())
+        );
+    }
+}
@@ -1,8 +1,14 @@
 //
+pub mod age_range;
 pub mod currency;
+pub mod device_class;
+pub mod gender;
 pub mod payment_model;
 pub mod region;
 
+pub use age_range::AgeRange;
 pub use currency::Currency;
+pub use device_class::DeviceClass;
+pub use gender::Gender;
 pub use payment_model::PaymentModel;
 pub use region::Region;